@@ -55,6 +55,10 @@ fn main() -> anyhow::Result<()> {
             on_update: Box::new(|_| Ok(())),
         },
         timeout: timeout.map(Into::into),
+        connect_timeout: None,
+        min_interval: None,
+        proxy: None,
+        local_address: None,
         shell: Shell::new(),
     })?;
 
@@ -76,11 +76,16 @@ fn main() -> anyhow::Result<()> {
         } else {
             None
         },
+        keep_going: false,
         cookie_storage: CookieStorage {
             cookie_store: CookieStore::default(),
             on_update: Box::new(|_| Ok(())),
         },
         timeout: timeout.map(Into::into),
+        connect_timeout: None,
+        min_interval: None,
+        proxy: None,
+        local_address: None,
         shell: Shell::new(),
     })?;
 
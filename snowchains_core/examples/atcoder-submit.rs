@@ -73,6 +73,8 @@ fn main() -> anyhow::Result<()> {
         code: fs::read_to_string(&file)
             .with_context(|| format!("Failed to read {}", file.display()))?,
         watch_submission: !no_watch,
+        watch_timeout: None,
+        dry_run: false,
         cookie_storage: CookieStorage {
             cookie_store: CookieStore::default(),
             on_update: Box::new(move |cookie_store| -> _ {
@@ -90,6 +92,10 @@ fn main() -> anyhow::Result<()> {
             }),
         },
         timeout: timeout.map(Into::into),
+        connect_timeout: None,
+        min_interval: None,
+        proxy: None,
+        local_address: None,
         shell: Shell::new(),
     })?;
 
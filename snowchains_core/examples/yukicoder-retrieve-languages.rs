@@ -33,6 +33,10 @@ fn main() -> anyhow::Result<()> {
         credentials: (),
         cookie_storage: (),
         timeout: timeout.map(Into::into),
+        connect_timeout: None,
+        min_interval: None,
+        proxy: None,
+        local_address: None,
         shell: Shell::new(),
     })?;
 
@@ -85,8 +85,13 @@ fn main() -> anyhow::Result<()> {
         } else {
             None
         },
+        keep_going: false,
         cookie_storage: (),
         timeout: timeout.map(Into::into),
+        connect_timeout: None,
+        min_interval: None,
+        proxy: None,
+        local_address: None,
         shell: Shell::new(),
     })?;
 
@@ -1,6 +1,10 @@
-use crate::testsuite::{BatchTestCase, CheckerShell, ExpectedOutput};
+use crate::testsuite::{
+    BatchTestCase, CheckerShell, DeterministicExpectedOutput, ExpectedOutput, ExtraOutput,
+    InteractiveTestCase,
+};
 use anyhow::{anyhow, bail};
 use futures_util::{select, FutureExt as _};
+use indexmap::IndexMap;
 use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
 use std::{
     cmp,
@@ -8,30 +12,81 @@ use std::{
     env,
     ffi::{OsStr, OsString},
     future::Future,
-    io, iter,
+    io, iter, mem,
+    num::NonZeroUsize,
     path::{Path, PathBuf},
     process::{ExitStatus, Output, Stdio},
     sync::Arc,
     time::{Duration, Instant},
 };
 use termcolor::{Color, WriteColor};
-use tokio::io::AsyncWriteExt as _;
+use tokio::io::{AsyncRead, AsyncReadExt as _, AsyncWrite, AsyncWriteExt as _};
 use unicode_width::UnicodeWidthStr as _;
 
 #[non_exhaustive]
 #[derive(Debug, Clone)]
 pub struct JudgeOutcome {
     pub verdicts: Vec<Verdict>,
+    /// Numbers of the accepted runs of the test cases, and the number of runs of each of them.
+    ///
+    /// `None` unless `JudgeOptions::repeat` is more than 1.
+    pub accepted_runs: Option<(Vec<usize>, usize)>,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PrintPrettyOptions {
+    pub display_limit: Option<usize>,
+    pub skip_accepted: bool,
+    /// Prints the stderr for Accepted and Wrong Answer too.
+    ///
+    /// The stderr for Runtime Error and Memorylimit Exceeded is always printed.
+    pub show_stderr: bool,
+    /// Prints the slowest test cases first.
+    pub sort_by_time: bool,
 }
 
 impl JudgeOutcome {
     pub fn print_pretty<W: WriteColor>(
         &self,
-        mut wtr: W,
+        wtr: W,
         display_limit: Option<usize>,
     ) -> io::Result<()> {
-        for (i, verdict) in self.verdicts.iter().enumerate() {
-            if i > 0 {
+        self.print_pretty_with_options(
+            wtr,
+            PrintPrettyOptions {
+                display_limit,
+                ..Default::default()
+            },
+        )
+    }
+
+    pub fn print_pretty_with_options<W: WriteColor>(
+        &self,
+        mut wtr: W,
+        options: PrintPrettyOptions,
+    ) -> io::Result<()> {
+        let PrintPrettyOptions {
+            display_limit,
+            skip_accepted,
+            show_stderr,
+            sort_by_time,
+        } = options;
+
+        let mut order = (0..self.verdicts.len()).collect::<Vec<_>>();
+        if sort_by_time {
+            order.sort_by_key(|&i| cmp::Reverse(self.verdicts[i].elapsed()));
+        }
+
+        let mut first = true;
+
+        for i in order {
+            let verdict = &self.verdicts[i];
+
+            if skip_accepted && matches!(verdict, Verdict::Accepted { .. }) {
+                continue;
+            }
+
+            if !mem::replace(&mut first, false) {
                 writeln!(wtr)?;
             }
 
@@ -44,85 +99,177 @@ impl JudgeOutcome {
             )?;
 
             wtr.set_color(color_spec!(Bold, Fg(verdict.summary_color())))?;
-            writeln!(wtr, "{}", verdict.summary())?;
+            write!(wtr, "{} (", verdict.summary_name())?;
+            if verdict.is_close_to_timelimit() {
+                wtr.set_color(color_spec!(Bold, Fg(Color::Yellow)))?;
+            }
+            write!(wtr, "{} ms", verdict.elapsed().as_millis())?;
+            wtr.set_color(color_spec!(Bold, Fg(verdict.summary_color())))?;
+            if let Some(extra) = verdict.summary_extra() {
+                write!(wtr, ", {}", extra)?;
+            }
+            write!(wtr, ")")?;
+            if let Some((accepted_runs, repeat)) = &self.accepted_runs {
+                write!(wtr, " ({}/{} accepted)", accepted_runs[i], repeat)?;
+            }
+            writeln!(wtr)?;
             wtr.reset()?;
 
-            let mut write_text =
-                |header: &str, text: &str, highlight_numbers: bool| -> io::Result<()> {
-                    wtr.set_color(color_spec!(Bold, Fg(Color::Magenta)))?;
-                    writeln!(wtr, "{}", header)?;
-                    wtr.reset()?;
-
-                    if text.is_empty() {
-                        wtr.set_color(color_spec!(Bold, Fg(Color::Yellow)))?;
-                        writeln!(wtr, "EMPTY")?;
-                        return wtr.reset();
-                    }
+            let mut write_text = |header: &str,
+                                  text: &str,
+                                  highlight_numbers: bool,
+                                  display_limit: Option<usize>|
+             -> io::Result<()> {
+                wtr.set_color(color_spec!(Bold, Fg(Color::Magenta)))?;
+                writeln!(wtr, "{}", header)?;
+                wtr.reset()?;
+
+                if text.is_empty() {
+                    wtr.set_color(color_spec!(Bold, Fg(Color::Yellow)))?;
+                    writeln!(wtr, "EMPTY")?;
+                    return wtr.reset();
+                }
 
-                    if matches!(display_limit, Some(l) if l < text.len()) {
-                        wtr.set_color(color_spec!(Bold, Fg(Color::Yellow)))?;
-                        writeln!(wtr, "{} B", text.len())?;
-                        return wtr.reset();
-                    }
+                if matches!(display_limit, Some(l) if l < text.len()) {
+                    wtr.set_color(color_spec!(Bold, Fg(Color::Yellow)))?;
+                    writeln!(wtr, "{} B", text.len())?;
+                    return wtr.reset();
+                }
 
-                    for token in parse_to_tokens(text, highlight_numbers) {
-                        match token {
-                            Token::SpcLf(s) | Token::Plain(s) => wtr.write_all(s.as_ref())?,
-                            Token::Cr(n) => {
-                                wtr.set_color(color_spec!(Fg(Color::Yellow)))?;
-                                (0..n).try_for_each(|_| wtr.write_all(b"\\r"))?;
-                                wtr.reset()?;
-                            }
-                            Token::Tab(n) => {
-                                wtr.set_color(color_spec!(Fg(Color::Yellow)))?;
-                                (0..n).try_for_each(|_| wtr.write_all(b"\\t"))?;
-                                wtr.reset()?;
-                            }
-                            Token::OtherWhitespaceControl(s) => {
-                                wtr.set_color(color_spec!(Fg(Color::Yellow)))?;
-                                write!(wtr, "{}", s.escape_unicode())?;
-                                wtr.reset()?;
-                            }
-                            Token::HighlightedNumber(s) => {
-                                wtr.set_color(color_spec!(Fg(Color::Cyan)))?;
-                                wtr.write_all(s.as_ref())?;
-                                wtr.reset()?;
-                            }
+                for token in parse_to_tokens(text, highlight_numbers) {
+                    match token {
+                        Token::SpcLf(s) | Token::Plain(s) => wtr.write_all(s.as_ref())?,
+                        Token::Cr(n) => {
+                            wtr.set_color(color_spec!(Fg(Color::Yellow)))?;
+                            (0..n).try_for_each(|_| wtr.write_all(b"\\r"))?;
+                            wtr.reset()?;
+                        }
+                        Token::Tab(n) => {
+                            wtr.set_color(color_spec!(Fg(Color::Yellow)))?;
+                            (0..n).try_for_each(|_| wtr.write_all(b"\\t"))?;
+                            wtr.reset()?;
+                        }
+                        Token::OtherWhitespaceControl(s) => {
+                            wtr.set_color(color_spec!(Fg(Color::Yellow)))?;
+                            write!(wtr, "{}", s.escape_unicode())?;
+                            wtr.reset()?;
+                        }
+                        Token::HighlightedNumber(s) => {
+                            wtr.set_color(color_spec!(Fg(Color::Cyan)))?;
+                            wtr.write_all(s.as_ref())?;
+                            wtr.reset()?;
                         }
                     }
+                }
 
-                    if !text.ends_with('\n') {
-                        wtr.set_color(color_spec!(Fg(Color::Yellow)))?;
-                        writeln!(wtr, "⏎")?;
-                        wtr.reset()?;
-                    }
+                if !text.ends_with('\n') {
+                    wtr.set_color(color_spec!(Fg(Color::Yellow)))?;
+                    writeln!(wtr, "⏎")?;
+                    wtr.reset()?;
+                }
 
-                    Ok(())
-                };
+                Ok(())
+            };
 
-            write_text("stdin:", verdict.stdin(), false)?;
+            write_text("stdin:", verdict.stdin(), false, display_limit)?;
             if let Some(expected) = verdict.expected().expected_stdout() {
-                write_text("expected:", expected, verdict.expected().is_float())?;
+                write_text(
+                    "expected:",
+                    expected,
+                    verdict.expected().is_float(),
+                    display_limit,
+                )?;
             } else if let Some(example) = verdict.expected().example() {
-                write_text("example:", example, verdict.expected().is_float())?;
+                write_text(
+                    "example:",
+                    example,
+                    verdict.expected().is_float(),
+                    display_limit,
+                )?;
             }
             if let Some(stdout) = verdict.stdout() {
-                write_text("actual:", stdout, verdict.expected().is_float())?;
+                write_text(
+                    "actual:",
+                    stdout,
+                    verdict.expected().is_float(),
+                    display_limit,
+                )?;
             }
             if let Some(stderr) = verdict.stderr().filter(|s| !s.is_empty()) {
-                write_text("stderr:", stderr, verdict.expected().is_float())?;
+                if matches!(
+                    verdict,
+                    Verdict::RuntimeError { .. } | Verdict::MemorylimitExceeded { .. }
+                ) {
+                    let tail = tail(stderr, STDERR_TAIL_LEN);
+                    if tail.len() < stderr.len() {
+                        let header =
+                            format!("stderr (last {} B of {} B):", tail.len(), stderr.len());
+                        write_text(&header, tail, verdict.expected().is_float(), None)?;
+                    } else {
+                        write_text("stderr:", stderr, verdict.expected().is_float(), None)?;
+                    }
+                } else if show_stderr {
+                    write_text(
+                        "stderr:",
+                        stderr,
+                        verdict.expected().is_float(),
+                        display_limit,
+                    )?;
+                }
             }
             if let Some(checker_stdout) = verdict.checker_stdout().filter(|s| !s.is_empty()) {
-                write_text("checker stdout: ", checker_stdout, false)?;
+                write_text("checker stdout: ", checker_stdout, false, display_limit)?;
             }
             if let Some(checker_stderr) = verdict.checker_stderr().filter(|s| !s.is_empty()) {
-                write_text("checker stderr: ", checker_stderr, false)?;
+                write_text("checker stderr: ", checker_stderr, false, display_limit)?;
             }
             if let Some(wrong_answer_note) = verdict.wrong_answer_note() {
-                write_text("note: ", &(wrong_answer_note.to_string() + "\n"), false)?;
+                write_text(
+                    "note: ",
+                    &(wrong_answer_note.to_string() + "\n"),
+                    false,
+                    display_limit,
+                )?;
+            }
+            if let (Verdict::WrongAnswer { .. }, Some(expected), Some(actual)) = (
+                verdict,
+                verdict.expected().expected_stdout(),
+                verdict.stdout(),
+            ) {
+                if !matches!(display_limit, Some(l) if l < expected.len() || l < actual.len()) {
+                    if let Some(diff) = line_diff(expected, actual) {
+                        write_diff(&mut wtr, &diff)?;
+                    }
+                }
             }
         }
 
+        let num_accepted = self
+            .verdicts
+            .iter()
+            .filter(|v| matches!(v, Verdict::Accepted { .. }))
+            .count();
+
+        if !first {
+            writeln!(wtr)?;
+        }
+        wtr.set_color(color_spec!(
+            Bold,
+            Fg(if num_accepted == self.verdicts.len() {
+                Color::Green
+            } else {
+                Color::Yellow
+            }),
+        ))?;
+        writeln!(
+            wtr,
+            "{}/{} test{} accepted",
+            num_accepted,
+            self.verdicts.len(),
+            if self.verdicts.len() == 1 { "" } else { "s" },
+        )?;
+        wtr.reset()?;
+
         return wtr.flush();
 
         #[derive(Debug)]
@@ -199,6 +346,179 @@ impl JudgeOutcome {
         }
     }
 
+    /// Prints the verdicts in [TAP](https://testanything.org/) version 13.
+    ///
+    /// Each failure has a YAML block with the summary, the input, the expected output, the actual
+    /// output, and the stderr.
+    pub fn print_tap<W: io::Write>(
+        &self,
+        mut wtr: W,
+        display_limit: Option<usize>,
+    ) -> io::Result<()> {
+        writeln!(wtr, "TAP version 13")?;
+        writeln!(wtr, "1..{}", self.verdicts.len())?;
+
+        for (i, verdict) in self.verdicts.iter().enumerate() {
+            let description = verdict
+                .test_case_name()
+                .unwrap_or("")
+                .replace('\\', "\\\\")
+                .replace('#', "\\#");
+
+            if matches!(verdict, Verdict::Accepted { .. }) {
+                writeln!(wtr, "ok {} - {}", i + 1, description)?;
+                continue;
+            }
+
+            writeln!(wtr, "not ok {} - {}", i + 1, description)?;
+
+            let limit = |text: &str| match display_limit {
+                Some(l) if l < text.len() => format!("{} B", text.len()),
+                _ => text.to_owned(),
+            };
+
+            let mut diagnostic = IndexMap::new();
+            diagnostic.insert("message", verdict.summary());
+            diagnostic.insert("severity", "fail".to_owned());
+            diagnostic.insert("stdin", limit(verdict.stdin()));
+            if let Some(expected) = verdict.expected().expected_stdout() {
+                diagnostic.insert("expected", limit(expected));
+            }
+            if let Some(stdout) = verdict.stdout() {
+                diagnostic.insert("actual", limit(stdout));
+            }
+            if let Some(stderr) = verdict.stderr() {
+                diagnostic.insert("stderr", limit(stderr));
+            }
+
+            let diagnostic = serde_yaml::to_string(&diagnostic).map_err(io::Error::other)?;
+
+            let diagnostic = diagnostic.strip_prefix("---\n").unwrap_or(&diagnostic);
+
+            writeln!(wtr, "  ---")?;
+            for line in diagnostic.lines() {
+                writeln!(wtr, "  {}", line)?;
+            }
+            writeln!(wtr, "  ...")?;
+        }
+
+        wtr.flush()
+    }
+
+    /// Prints the verdicts in the JUnit XML format.
+    ///
+    /// Wrong Answer and Timelimit Exceeded are reported as `<failure>`s, and Runtime Error and
+    /// Memorylimit Exceeded as `<error>`s. The body of a `<failure>` for Wrong Answer is the line
+    /// diff between the expected and the actual output.
+    pub fn print_junit<W: io::Write>(
+        &self,
+        mut wtr: W,
+        suite_name: &str,
+        display_limit: Option<usize>,
+    ) -> io::Result<()> {
+        let is_error = |verdict: &Verdict| {
+            matches!(
+                verdict,
+                Verdict::RuntimeError { .. } | Verdict::MemorylimitExceeded { .. },
+            )
+        };
+
+        let errors = self.verdicts.iter().filter(|v| is_error(v)).count();
+        let failures = self
+            .verdicts
+            .iter()
+            .filter(|v| !(matches!(v, Verdict::Accepted { .. }) || is_error(v)))
+            .count();
+
+        let limit = |text: &str| match display_limit {
+            Some(l) if l < text.len() => format!("{} B", text.len()),
+            _ => text.to_owned(),
+        };
+
+        writeln!(wtr, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            wtr,
+            r#"<testsuite name="{}" tests="{}" failures="{}" errors="{}" time="{:.3}">"#,
+            xml_escape(suite_name),
+            self.verdicts.len(),
+            failures,
+            errors,
+            self.verdicts
+                .iter()
+                .map(Verdict::elapsed)
+                .sum::<Duration>()
+                .as_secs_f64(),
+        )?;
+
+        for (i, verdict) in self.verdicts.iter().enumerate() {
+            let name = match verdict.test_case_name() {
+                Some(name) => name.to_owned(),
+                None => format!("#{}", i + 1),
+            };
+
+            write!(
+                wtr,
+                r#"  <testcase name="{}" classname="{}" time="{:.3}""#,
+                xml_escape(&name),
+                xml_escape(suite_name),
+                verdict.elapsed().as_secs_f64(),
+            )?;
+
+            if matches!(verdict, Verdict::Accepted { .. }) {
+                writeln!(wtr, "/>")?;
+                continue;
+            }
+            writeln!(wtr, ">")?;
+
+            let mut body = String::new();
+
+            if let (Verdict::WrongAnswer { stdout, .. }, Some(expected)) =
+                (verdict, verdict.expected().expected_stdout())
+            {
+                match line_diff(expected, stdout) {
+                    Some(diff) => {
+                        for line in diff {
+                            let (sign, n, text) = match line {
+                                DiffLine::Removed(n, text) => ('-', n, text),
+                                DiffLine::Added(n, text) => ('+', n, text),
+                            };
+                            body += &format!("{}{}: {}\n", sign, n, text);
+                        }
+                    }
+                    None => {
+                        body += &format!("expected:\n{}\n", limit(expected));
+                        body += &format!("actual:\n{}\n", limit(stdout));
+                    }
+                }
+            }
+            if let Some(stderr) = verdict.stderr().filter(|s| !s.is_empty()) {
+                body += &format!("stderr:\n{}\n", limit(stderr));
+            }
+
+            let (tag, ty) = match verdict {
+                Verdict::Accepted { .. } => unreachable!(),
+                Verdict::WrongAnswer { .. } => ("failure", "WrongAnswer"),
+                Verdict::TimelimitExceeded { .. } => ("failure", "TimelimitExceeded"),
+                Verdict::RuntimeError { .. } => ("error", "RuntimeError"),
+                Verdict::MemorylimitExceeded { .. } => ("error", "MemorylimitExceeded"),
+            };
+
+            writeln!(
+                wtr,
+                r#"    <{} type="{}" message="{}">{}</{}>"#,
+                tag,
+                ty,
+                xml_escape(&verdict.summary()),
+                xml_escape(&body),
+                tag,
+            )?;
+            writeln!(wtr, "  </testcase>")?;
+        }
+
+        writeln!(wtr, "</testsuite>")?;
+        wtr.flush()
+    }
+
     pub fn error_on_fail(&self) -> anyhow::Result<()> {
         let fails = self
             .verdicts
@@ -224,6 +544,7 @@ pub enum Verdict {
     Accepted {
         test_case_name: Option<String>,
         elapsed: Duration,
+        timelimit: Option<Duration>,
         stdin: Arc<str>,
         stdout: Arc<str>,
         stderr: Arc<str>,
@@ -232,6 +553,7 @@ pub enum Verdict {
     WrongAnswer {
         test_case_name: Option<String>,
         elapsed: Duration,
+        timelimit: Option<Duration>,
         stdin: Arc<str>,
         stdout: Arc<str>,
         stderr: Arc<str>,
@@ -243,6 +565,7 @@ pub enum Verdict {
     RuntimeError {
         test_case_name: Option<String>,
         elapsed: Duration,
+        timelimit: Option<Duration>,
         stdin: Arc<str>,
         stdout: Arc<str>,
         stderr: Arc<str>,
@@ -255,32 +578,61 @@ pub enum Verdict {
         stdin: Arc<str>,
         expected: ExpectedOutput,
     },
+    MemorylimitExceeded {
+        test_case_name: Option<String>,
+        elapsed: Duration,
+        timelimit: Option<Duration>,
+        /// Memory limit in megabytes.
+        memorylimit: u64,
+        stdin: Arc<str>,
+        stdout: Arc<str>,
+        stderr: Arc<str>,
+        expected: ExpectedOutput,
+    },
 }
 
 impl Verdict {
-    fn test_case_name(&self) -> Option<&str> {
+    pub fn test_case_name(&self) -> Option<&str> {
         match self {
             Verdict::Accepted { test_case_name, .. }
             | Verdict::WrongAnswer { test_case_name, .. }
             | Verdict::RuntimeError { test_case_name, .. }
-            | Verdict::TimelimitExceeded { test_case_name, .. } => test_case_name.as_deref(),
+            | Verdict::TimelimitExceeded { test_case_name, .. }
+            | Verdict::MemorylimitExceeded { test_case_name, .. } => test_case_name.as_deref(),
         }
     }
 
+    fn is_worse_than(&self, other: &Self) -> bool {
+        fn rank(verdict: &Verdict) -> (u8, Duration) {
+            match *verdict {
+                Verdict::Accepted { elapsed, .. } => (0, elapsed),
+                Verdict::WrongAnswer { elapsed, .. } => (1, elapsed),
+                Verdict::RuntimeError { elapsed, .. } => (2, elapsed),
+                Verdict::MemorylimitExceeded { elapsed, .. } => (3, elapsed),
+                Verdict::TimelimitExceeded { timelimit, .. } => (4, timelimit),
+            }
+        }
+
+        rank(self) > rank(other)
+    }
+
     fn stdin(&self) -> &str {
         match self {
             Verdict::Accepted { stdin, .. }
             | Verdict::WrongAnswer { stdin, .. }
             | Verdict::RuntimeError { stdin, .. }
-            | Verdict::TimelimitExceeded { stdin, .. } => stdin,
+            | Verdict::TimelimitExceeded { stdin, .. }
+            | Verdict::MemorylimitExceeded { stdin, .. } => stdin,
         }
     }
 
-    fn stdout(&self) -> Option<&str> {
+    /// Stdout of the solution. `None` for Timelimit Exceeded.
+    pub fn stdout(&self) -> Option<&str> {
         match self {
             Verdict::Accepted { stdout, .. }
             | Verdict::WrongAnswer { stdout, .. }
-            | Verdict::RuntimeError { stdout, .. } => Some(stdout),
+            | Verdict::RuntimeError { stdout, .. }
+            | Verdict::MemorylimitExceeded { stdout, .. } => Some(stdout),
             Verdict::TimelimitExceeded { .. } => None,
         }
     }
@@ -289,7 +641,8 @@ impl Verdict {
         match self {
             Verdict::Accepted { stderr, .. }
             | Verdict::WrongAnswer { stderr, .. }
-            | Verdict::RuntimeError { stderr, .. } => Some(stderr),
+            | Verdict::RuntimeError { stderr, .. }
+            | Verdict::MemorylimitExceeded { stderr, .. } => Some(stderr),
             Verdict::TimelimitExceeded { .. } => None,
         }
     }
@@ -299,7 +652,8 @@ impl Verdict {
             Verdict::Accepted { expected, .. }
             | Verdict::WrongAnswer { expected, .. }
             | Verdict::RuntimeError { expected, .. }
-            | Verdict::TimelimitExceeded { expected, .. } => expected,
+            | Verdict::TimelimitExceeded { expected, .. }
+            | Verdict::MemorylimitExceeded { expected, .. } => expected,
         }
     }
 
@@ -324,25 +678,75 @@ impl Verdict {
         }
     }
 
-    fn summary(&self) -> String {
-        match self {
-            Self::Accepted { elapsed, .. } => format!("Accepted ({} ms)", elapsed.as_millis()),
-            Self::TimelimitExceeded { timelimit, .. } => {
-                format!("Timelimit Exceeded ({} ms)", timelimit.as_millis())
+    /// Elapsed time of the solution. The timelimit for Timelimit Exceeded.
+    pub fn elapsed(&self) -> Duration {
+        match *self {
+            Verdict::Accepted { elapsed, .. }
+            | Verdict::WrongAnswer { elapsed, .. }
+            | Verdict::RuntimeError { elapsed, .. }
+            | Verdict::MemorylimitExceeded { elapsed, .. } => elapsed,
+            Verdict::TimelimitExceeded { timelimit, .. } => timelimit,
+        }
+    }
+
+    /// Whether the solution finished but took more than 80% of the timelimit.
+    fn is_close_to_timelimit(&self) -> bool {
+        match *self {
+            Verdict::Accepted {
+                elapsed,
+                timelimit: Some(timelimit),
+                ..
+            }
+            | Verdict::WrongAnswer {
+                elapsed,
+                timelimit: Some(timelimit),
+                ..
             }
-            Self::WrongAnswer { elapsed, .. } => {
-                format!("Wrong Answer ({} ms)", elapsed.as_millis())
+            | Verdict::RuntimeError {
+                elapsed,
+                timelimit: Some(timelimit),
+                ..
             }
-            Self::RuntimeError {
-                elapsed, status, ..
-            } => format!("Runtime Error ({} ms, {})", elapsed.as_millis(), status),
+            | Verdict::MemorylimitExceeded {
+                elapsed,
+                timelimit: Some(timelimit),
+                ..
+            } => elapsed * 5 > timelimit * 4,
+            _ => false,
+        }
+    }
+
+    fn summary(&self) -> String {
+        let mut summary = format!("{} ({} ms", self.summary_name(), self.elapsed().as_millis());
+        if let Some(extra) = self.summary_extra() {
+            summary += ", ";
+            summary += &extra;
+        }
+        summary + ")"
+    }
+
+    fn summary_name(&self) -> &'static str {
+        match self {
+            Self::Accepted { .. } => "Accepted",
+            Self::TimelimitExceeded { .. } => "Timelimit Exceeded",
+            Self::WrongAnswer { .. } => "Wrong Answer",
+            Self::RuntimeError { .. } => "Runtime Error",
+            Self::MemorylimitExceeded { .. } => "Memorylimit Exceeded",
+        }
+    }
+
+    fn summary_extra(&self) -> Option<String> {
+        match self {
+            Self::RuntimeError { status, .. } => Some(status.to_string()),
+            Self::MemorylimitExceeded { memorylimit, .. } => Some(format!("{} MB", memorylimit)),
+            _ => None,
         }
     }
 
     fn summary_color(&self) -> Color {
         match self {
             Self::Accepted { .. } => Color::Green,
-            Self::TimelimitExceeded { .. } => Color::Red,
+            Self::TimelimitExceeded { .. } | Self::MemorylimitExceeded { .. } => Color::Red,
             Self::WrongAnswer { .. } | Self::RuntimeError { .. } => Color::Yellow,
         }
     }
@@ -350,7 +754,7 @@ impl Verdict {
     fn summary_style(&self) -> &'static str {
         match self {
             Self::Accepted { .. } => ".bold.green",
-            Self::TimelimitExceeded { .. } => ".bold.red",
+            Self::TimelimitExceeded { .. } | Self::MemorylimitExceeded { .. } => ".bold.red",
             Self::WrongAnswer { .. } | Self::RuntimeError { .. } => ".bold.yellow",
         }
     }
@@ -378,6 +782,7 @@ impl CommandExpression {
         stdin: Option<&Path>,
         stdout: &Path,
         stderr: &Path,
+        memorylimit: Option<u64>,
     ) -> io::Result<tokio::process::Command> {
         let mut cmd = tokio::process::Command::new(&self.program);
         let stdin = if let Some(stdin) = stdin {
@@ -393,15 +798,97 @@ impl CommandExpression {
             .stdin(stdin)
             .stdout(stdout)
             .stderr(stderr);
+
+        #[cfg(target_os = "linux")]
+        if let Some(memorylimit) = memorylimit {
+            let limit = memorylimit.saturating_mul(1024 * 1024) as libc::rlim_t;
+
+            // SAFETY: `setrlimit` is async-signal-safe and `limit` is a copied value.
+            unsafe {
+                cmd.pre_exec(move || {
+                    let rlimit = libc::rlimit {
+                        rlim_cur: limit,
+                        rlim_max: limit,
+                    };
+                    if libc::setrlimit(libc::RLIMIT_AS, &rlimit) == -1 {
+                        return Err(io::Error::last_os_error());
+                    }
+                    Ok(())
+                });
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        let _ = memorylimit;
+
         Ok(cmd)
     }
 }
 
+#[derive(Debug, Default, Clone, Copy)]
+pub struct JudgeOptions {
+    /// Maximum number of test cases to run at once.
+    ///
+    /// Defaults to the number of logical CPUs.
+    pub jobs: Option<NonZeroUsize>,
+    /// Total amount of memory in bytes that the running solutions may use at once.
+    pub memory_budget: Option<u64>,
+    /// Estimated amount of memory in bytes that one solution uses.
+    ///
+    /// Defaults to the largest `memorylimit` of the test cases, or 256MiB if none of them has one.
+    pub memory_per_case: Option<u64>,
+    /// Number of times to run each test case.
+    ///
+    /// The worst verdict is reported for each test case.
+    pub repeat: Option<NonZeroUsize>,
+    /// Accepts outputs that have lines not in the expected output.
+    ///
+    /// Applied only to the test cases compared without checkers.
+    pub extra_output: Option<ExtraOutput>,
+}
+
+impl JudgeOptions {
+    const DEFAULT_MEMORY_PER_CASE: u64 = 256 * 1024 * 1024;
+
+    /// `memorylimit` is the largest memory limit of the test cases in megabytes.
+    fn max_concurrency(self, num_cpus: usize, memorylimit: Option<u64>) -> usize {
+        let jobs = self.jobs.map_or(cmp::max(num_cpus, 1), NonZeroUsize::get);
+
+        if let Some(memory_budget) = self.memory_budget {
+            let memory_per_case = cmp::max(
+                self.memory_per_case
+                    .or_else(|| Some(memorylimit?.saturating_mul(1024 * 1024)))
+                    .unwrap_or(Self::DEFAULT_MEMORY_PER_CASE),
+                1,
+            );
+            let n = (memory_budget / memory_per_case).min(jobs as u64) as usize;
+            cmp::max(n, 1)
+        } else {
+            jobs
+        }
+    }
+}
+
 pub fn judge<C: 'static + Future<Output = tokio::io::Result<()>> + Send>(
     draw_target: ProgressDrawTarget,
     ctrl_c: fn() -> C,
     cmd: &CommandExpression,
     test_cases: &[BatchTestCase],
+) -> anyhow::Result<JudgeOutcome> {
+    judge_with_options(
+        draw_target,
+        ctrl_c,
+        cmd,
+        test_cases,
+        JudgeOptions::default(),
+    )
+}
+
+pub fn judge_with_options<C: 'static + Future<Output = tokio::io::Result<()>> + Send>(
+    draw_target: ProgressDrawTarget,
+    ctrl_c: fn() -> C,
+    cmd: &CommandExpression,
+    test_cases: &[BatchTestCase],
+    options: JudgeOptions,
 ) -> anyhow::Result<JudgeOutcome> {
     let cmd = Arc::new(cmd.clone());
     let num_test_cases = test_cases.len();
@@ -455,6 +942,8 @@ pub fn judge<C: 'static + Future<Output = tokio::io::Result<()>> + Send>(
         targets.push((test_case.clone(), pb));
     }
 
+    let memorylimit = test_cases.iter().flat_map(|t| t.memorylimit).max();
+
     let rt = tokio::runtime::Builder::new_multi_thread()
         .enable_io()
         .enable_time()
@@ -478,8 +967,12 @@ pub fn judge<C: 'static + Future<Output = tokio::io::Result<()>> + Send>(
             ctrl_c_tx.send(err_msg).unwrap();
         });
 
-        let (job_start_tx, mut job_start_rx) = tokio::sync::mpsc::channel(num_cpus::get());
-        for _ in 0..num_cpus::get() {
+        let max_concurrency = options.max_concurrency(num_cpus::get(), memorylimit);
+        let repeat = options.repeat.map_or(1, NonZeroUsize::get);
+        let extra_output = options.extra_output;
+
+        let (job_start_tx, mut job_start_rx) = tokio::sync::mpsc::channel(max_concurrency);
+        for _ in 0..max_concurrency {
             job_start_tx.send(()).await?;
         }
 
@@ -487,150 +980,65 @@ pub fn judge<C: 'static + Future<Output = tokio::io::Result<()>> + Send>(
 
         for (i, (test_case, pb)) in targets.into_iter().enumerate() {
             let cmd = cmd.clone();
-            let stdin_path = tempdir_path.join(format!("{}-stdin", i));
-            let actual_stdout_path = tempdir_path.join(format!("{}-actual-stdout", i));
-            let expected_stdout_path = tempdir_path.join(format!("{}-expected-stdout", i));
-            let stderr_path = tempdir_path.join(format!("{}-stderr", i));
+            let files = TestCaseFiles {
+                stdin: tempdir_path.join(format!("{}-stdin", i)),
+                actual_stdout: tempdir_path.join(format!("{}-actual-stdout", i)),
+                expected_stdout: tempdir_path.join(format!("{}-expected-stdout", i)),
+                stderr: tempdir_path.join(format!("{}-stderr", i)),
+            };
             let bash_exe = bash_exe.clone();
 
             job_start_rx.recv().await;
 
             let job_start_tx = job_start_tx.clone();
             let mut ctrl_c_rx = ctrl_c_rxs.pop().expect("should have enough length");
-            let pb_clone = pb.clone();
 
             results.push(tokio::task::spawn(async move {
                 let result = tokio::task::spawn(async move {
-                    tokio::fs::write(&stdin_path, test_case.input.as_ref()).await?;
-
-                    let test_case_name = test_case.name.clone();
-                    let timelimit = test_case.timelimit;
-                    let stdin = test_case.input.clone();
-                    let expected = test_case.output.clone();
-
-                    let cwd = &cmd.cwd;
-                    let cmd = cmd
-                        .build(
-                            (stdin.len() >= 10 * 1024).then(|| &*stdin_path),
-                            &actual_stdout_path,
-                            &stderr_path,
+                    tokio::fs::write(&files.stdin, test_case.input.as_ref()).await?;
+
+                    let mut worst = None::<Verdict>;
+                    let mut num_accepted = 0;
+
+                    for _ in 0..repeat {
+                        let verdict = run_test_case(
+                            &test_case,
+                            &cmd,
+                            &files,
+                            &bash_exe,
+                            extra_output,
+                            &mut ctrl_c_rx,
                         )
                         .await?;
 
-                    let started = Instant::now();
-
-                    let mut child = { cmd }.spawn()?;
-
-                    if let Some(mut child_stdin) = child.stdin.take() {
-                        child_stdin.write_all((*stdin).as_ref()).await?;
-                    }
-
-                    macro_rules! with_ctrl_c {
-                        ($future:expr) => {
-                            select! {
-                                __output = $future => __output,
-                                err_msg = ctrl_c_rx.recv().fuse() => {
-                                    let _ = child.kill();
-                                    bail!("{}", err_msg?);
-                                },
-                            }
-                        };
-                    }
-
-                    let status = if let Some(timelimit) = timelimit {
-                        let timeout = timelimit + Duration::from_millis(100);
-
-                        if let Ok(status) =
-                            with_ctrl_c!(tokio::time::timeout(timeout, child.wait()).fuse())
-                        {
-                            status?
-                        } else {
-                            let _ = child.kill().await;
-                            let verdict = Verdict::TimelimitExceeded {
-                                test_case_name,
-                                timelimit,
-                                stdin,
-                                expected,
-                            };
-                            tokio::task::block_in_place(|| {
-                                pb_clone.set_style(progress_style(&format!(
-                                    "{{prefix}}{{msg:{}}}",
-                                    verdict.summary_style(),
-                                )));
-                                pb_clone.finish_with_message(&verdict.summary());
-                            });
-                            return Ok(verdict);
+                        if matches!(verdict, Verdict::Accepted { .. }) {
+                            num_accepted += 1;
+                        }
+                        match &worst {
+                            Some(worst) if !verdict.is_worse_than(worst) => {}
+                            _ => worst = Some(verdict),
                         }
-                    } else {
-                        with_ctrl_c!(child.wait().fuse())?
-                    };
-
-                    let elapsed = Instant::now() - started;
-
-                    let stdout = utf8(tokio::fs::read(&actual_stdout_path).await?)?;
-                    let stderr = utf8(tokio::fs::read(&stderr_path).await?)?;
-
-                    if matches!(timelimit, Some(t) if t < elapsed) {
-                        Ok(Verdict::TimelimitExceeded {
-                            test_case_name,
-                            timelimit: timelimit.unwrap(),
-                            stdin,
-                            expected,
-                        })
-                    } else if !status.success() {
-                        Ok(Verdict::RuntimeError {
-                            test_case_name,
-                            elapsed,
-                            stdin,
-                            stdout,
-                            stderr,
-                            expected,
-                            status,
-                        })
-                    } else if let Err((checker_stdout, checker_stderr, note)) = check(
-                        &test_case.output,
-                        &stdout,
-                        cwd,
-                        &stdin_path,
-                        &actual_stdout_path,
-                        &expected_stdout_path,
-                        &bash_exe,
-                    )
-                    .await?
-                    {
-                        Ok(Verdict::WrongAnswer {
-                            test_case_name,
-                            elapsed,
-                            stdin,
-                            stdout,
-                            stderr,
-                            checker_stdout,
-                            checker_stderr,
-                            expected,
-                            note,
-                        })
-                    } else {
-                        Ok(Verdict::Accepted {
-                            test_case_name,
-                            elapsed,
-                            stdin,
-                            stdout,
-                            stderr,
-                            expected,
-                        })
                     }
+
+                    let verdict = worst.expect("`repeat` should be positive");
+                    Ok::<_, anyhow::Error>((verdict, num_accepted))
                 })
                 .await
                 .unwrap();
 
                 match &result {
-                    Ok(verdict) => {
+                    Ok((verdict, num_accepted)) => {
+                        let mut msg = verdict.summary();
+                        if repeat > 1 {
+                            msg += &format!(" ({}/{} accepted)", num_accepted, repeat);
+                        }
+
                         tokio::task::block_in_place(|| {
                             pb.set_style(progress_style(&format!(
                                 "{{prefix}}{{msg:{}}}",
                                 verdict.summary_style(),
                             )));
-                            pb.finish_with_message(&verdict.summary());
+                            pb.finish_with_message(&msg);
                         });
                     }
                     Err(err) => {
@@ -642,65 +1050,481 @@ pub fn judge<C: 'static + Future<Output = tokio::io::Result<()>> + Send>(
                 }
 
                 job_start_tx.send(()).await?;
-                let verdict = result?;
-                Ok::<_, anyhow::Error>((i, verdict))
+                let (verdict, num_accepted) = result?;
+                Ok::<_, anyhow::Error>((i, verdict, num_accepted))
             }));
         }
 
         let mut verdicts = vec![None; num_targets];
+        let mut accepted_runs = vec![0; num_targets];
         for result in results {
-            let (i, element) = result.await??;
+            let (i, element, num_accepted) = result.await??;
             verdicts[i] = Some(element);
+            accepted_runs[i] = num_accepted;
         }
         let verdicts = verdicts.into_iter().map(Option::unwrap).collect();
+        let accepted_runs = Some((accepted_runs, repeat)).filter(|_| repeat > 1);
 
-        Ok::<_, anyhow::Error>(JudgeOutcome { verdicts })
+        Ok::<_, anyhow::Error>(JudgeOutcome {
+            verdicts,
+            accepted_runs,
+        })
     });
 
     mp.join()?;
 
     let outcome = rt.block_on(outcome)??;
     tempdir.close()?;
-    return Ok(outcome);
+    Ok(outcome)
+}
+
+/// Judges an interactive problem.
+///
+/// For each test case, the solution and the tester are run with their stdins and stdouts
+/// connected to each other. The solution is accepted if the tester exits with code 0.
+pub fn judge_interactive<C: 'static + Future<Output = tokio::io::Result<()>> + Send>(
+    draw_target: ProgressDrawTarget,
+    ctrl_c: fn() -> C,
+    cmd: &CommandExpression,
+    test_cases: &[InteractiveTestCase],
+) -> anyhow::Result<JudgeOutcome> {
+    let cmd = cmd.clone();
+    let num_test_cases = test_cases.len();
+
+    let quoted_name_width = test_cases
+        .iter()
+        .flat_map(|InteractiveTestCase { name, .. }| name.as_ref())
+        .map(|s| format!("{:?}", s).width())
+        .max()
+        .unwrap_or(0);
+
+    let mp = MultiProgress::with_draw_target(draw_target);
+
+    let mut targets = vec![];
+
+    for (i, test_case) in test_cases.iter().enumerate() {
+        let pb = mp.add(ProgressBar::new_spinner());
+
+        pb.set_style(progress_style("{prefix}{spinner} {msg:bold}"));
+
+        pb.set_prefix(&format!(
+            "{}/{} ({} ",
+            align_right(&(i + 1).to_string(), num_test_cases.to_string().len()),
+            num_test_cases,
+            align_left(
+                &format!("{:?})", test_case.name.as_deref().unwrap_or("")),
+                quoted_name_width + 1,
+            ),
+        ));
+
+        pb.set_message("Waiting...");
+        pb.enable_steady_tick(50);
+
+        targets.push((test_case.clone(), pb));
+    }
+
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .enable_io()
+        .enable_time()
+        .build()?;
+
+    let outcome = rt.spawn(async move {
+        let (ctrl_c_tx, mut ctrl_c_rx) = tokio::sync::broadcast::channel(1);
+
+        tokio::task::spawn(async move {
+            let err_msg = match ctrl_c().await {
+                Ok(()) => "Recieved Ctrl-c".to_owned(),
+                Err(err) => err.to_string(),
+            };
+            ctrl_c_tx.send(err_msg).unwrap();
+        });
+
+        let mut verdicts = vec![];
+
+        // Each test case runs two processes that wait for each other, so run them one by one.
+        for (test_case, pb) in targets {
+            tokio::task::block_in_place(|| pb.set_message("Judging..."));
+
+            match run_interactive_test_case(&test_case, &cmd, &mut ctrl_c_rx).await {
+                Ok(verdict) => {
+                    tokio::task::block_in_place(|| {
+                        pb.set_style(progress_style(&format!(
+                            "{{prefix}}{{msg:{}}}",
+                            verdict.summary_style(),
+                        )));
+                        pb.finish_with_message(&verdict.summary());
+                    });
+                    verdicts.push(verdict);
+                }
+                Err(err) => {
+                    tokio::task::block_in_place(|| {
+                        pb.set_style(progress_style("{prefix}{msg}"));
+                        pb.finish_with_message(&format!("{:?}", err));
+                    });
+                    return Err(err);
+                }
+            }
+        }
+
+        Ok::<_, anyhow::Error>(JudgeOutcome {
+            verdicts,
+            accepted_runs: None,
+        })
+    });
+
+    mp.join()?;
+
+    rt.block_on(outcome)?
+}
+
+fn progress_style(template: impl AsRef<str>) -> ProgressStyle {
+    ProgressStyle::default_spinner().template(template.as_ref())
+}
+
+fn align_left(s: &str, n: usize) -> String {
+    let spaces = n.saturating_sub(s.width());
+    s.chars().chain(itertools::repeat_n(' ', spaces)).collect()
+}
 
-    fn progress_style(template: impl AsRef<str>) -> ProgressStyle {
-        ProgressStyle::default_spinner().template(template.as_ref())
+fn align_right(s: &str, n: usize) -> String {
+    let spaces = n.saturating_sub(s.width());
+    itertools::repeat_n(' ', spaces).chain(s.chars()).collect()
+}
+
+struct TestCaseFiles {
+    stdin: PathBuf,
+    actual_stdout: PathBuf,
+    expected_stdout: PathBuf,
+    stderr: PathBuf,
+}
+
+async fn run_test_case(
+    test_case: &BatchTestCase,
+    cmd: &CommandExpression,
+    files: &TestCaseFiles,
+    bash_exe: &Path,
+    extra_output: Option<ExtraOutput>,
+    ctrl_c_rx: &mut tokio::sync::broadcast::Receiver<String>,
+) -> anyhow::Result<Verdict> {
+    let TestCaseFiles {
+        stdin: stdin_path,
+        actual_stdout: actual_stdout_path,
+        expected_stdout: _,
+        stderr: stderr_path,
+    } = files;
+
+    let test_case_name = test_case.name.clone();
+    let timelimit = test_case.timelimit;
+    let memorylimit = test_case.memorylimit;
+    let stdin = test_case.input.clone();
+    let expected = test_case.output.clone();
+
+    let cwd = &cmd.cwd;
+    let cmd = cmd
+        .build(
+            (stdin.len() >= 10 * 1024).then(|| &**stdin_path),
+            actual_stdout_path,
+            stderr_path,
+            memorylimit,
+        )
+        .await?;
+
+    let started = Instant::now();
+
+    let mut child = { cmd }.spawn()?;
+
+    if let Some(mut child_stdin) = child.stdin.take() {
+        child_stdin.write_all((*stdin).as_ref()).await?;
     }
 
-    fn align_left(s: &str, n: usize) -> String {
-        let spaces = n.saturating_sub(s.width());
-        s.chars().chain(itertools::repeat_n(' ', spaces)).collect()
+    macro_rules! with_ctrl_c {
+        ($future:expr) => {
+            select! {
+                __output = $future => __output,
+                err_msg = ctrl_c_rx.recv().fuse() => {
+                    let _ = child.kill();
+                    bail!("{}", err_msg?);
+                },
+            }
+        };
     }
 
-    fn align_right(s: &str, n: usize) -> String {
-        let spaces = n.saturating_sub(s.width());
-        itertools::repeat_n(' ', spaces).chain(s.chars()).collect()
+    let status = if let Some(timelimit) = timelimit {
+        let timeout = timelimit + Duration::from_millis(100);
+
+        if let Ok(status) = with_ctrl_c!(tokio::time::timeout(timeout, child.wait()).fuse()) {
+            status?
+        } else {
+            let _ = child.kill().await;
+            return Ok(Verdict::TimelimitExceeded {
+                test_case_name,
+                timelimit,
+                stdin,
+                expected,
+            });
+        }
+    } else {
+        with_ctrl_c!(child.wait().fuse())?
+    };
+
+    let elapsed = Instant::now() - started;
+
+    let stdout = utf8(tokio::fs::read(&actual_stdout_path).await?)?;
+    let stderr = utf8(tokio::fs::read(&stderr_path).await?)?;
+
+    if matches!(timelimit, Some(t) if t < elapsed) {
+        Ok(Verdict::TimelimitExceeded {
+            test_case_name,
+            timelimit: timelimit.unwrap(),
+            stdin,
+            expected,
+        })
+    } else if let Some(memorylimit) = memorylimit.filter(|_| exceeds_memorylimit(status, &stderr)) {
+        Ok(Verdict::MemorylimitExceeded {
+            test_case_name,
+            elapsed,
+            timelimit,
+            memorylimit,
+            stdin,
+            stdout,
+            stderr,
+            expected,
+        })
+    } else if !status.success() {
+        Ok(Verdict::RuntimeError {
+            test_case_name,
+            elapsed,
+            timelimit,
+            stdin,
+            stdout,
+            stderr,
+            expected,
+            status,
+        })
+    } else if let Err((checker_stdout, checker_stderr, note)) = check(
+        &test_case.output,
+        &stdout,
+        cwd,
+        files,
+        bash_exe,
+        extra_output,
+    )
+    .await?
+    {
+        Ok(Verdict::WrongAnswer {
+            test_case_name,
+            elapsed,
+            timelimit,
+            stdin,
+            stdout,
+            stderr,
+            checker_stdout,
+            checker_stderr,
+            expected,
+            note,
+        })
+    } else {
+        Ok(Verdict::Accepted {
+            test_case_name,
+            elapsed,
+            timelimit,
+            stdin,
+            stdout,
+            stderr,
+            expected,
+        })
     }
 }
 
+async fn run_interactive_test_case(
+    test_case: &InteractiveTestCase,
+    cmd: &CommandExpression,
+    ctrl_c_rx: &mut tokio::sync::broadcast::Receiver<String>,
+) -> anyhow::Result<Verdict> {
+    let test_case_name = test_case.name.clone();
+    let timelimit = test_case.timelimit;
+    let expected = ExpectedOutput::Deterministic(DeterministicExpectedOutput::Pass);
+
+    let spawn = |cmd: &CommandExpression| {
+        tokio::process::Command::new(&cmd.program)
+            .args(&cmd.args)
+            .current_dir(&cmd.cwd)
+            .envs(&cmd.env)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+    };
+
+    let started = Instant::now();
+
+    let mut solver = spawn(cmd)?;
+    let mut tester = spawn(&test_case.tester)?;
+
+    // Relay the messages ourselves to record them.
+    let to_solver = tokio::task::spawn(relay(
+        tester.stdout.take().unwrap(),
+        solver.stdin.take().unwrap(),
+    ));
+    let to_tester = tokio::task::spawn(relay(
+        solver.stdout.take().unwrap(),
+        tester.stdin.take().unwrap(),
+    ));
+    let solver_stderr = tokio::task::spawn(read_to_end(solver.stderr.take().unwrap()));
+    let tester_stderr = tokio::task::spawn(read_to_end(tester.stderr.take().unwrap()));
+
+    let statuses = async {
+        let wait = async { Ok::<_, io::Error>((solver.wait().await?, tester.wait().await?)) };
+        match timelimit {
+            Some(timelimit) => {
+                let timeout = timelimit + Duration::from_millis(100);
+                tokio::time::timeout(timeout, wait).await.ok()
+            }
+            None => Some(wait.await),
+        }
+    };
+
+    let statuses = select! {
+        statuses = statuses.fuse() => statuses,
+        err_msg = ctrl_c_rx.recv().fuse() => bail!("{}", err_msg?),
+    };
+
+    let (solver_status, tester_status) = match statuses {
+        Some(statuses) => statuses?,
+        None => {
+            let _ = solver.kill().await;
+            let _ = tester.kill().await;
+            return Ok(Verdict::TimelimitExceeded {
+                test_case_name,
+                timelimit: timelimit.unwrap(),
+                stdin: utf8(to_solver.await??)?,
+                expected,
+            });
+        }
+    };
+
+    let elapsed = Instant::now() - started;
+
+    let stdin = utf8(to_solver.await??)?;
+    let stdout = utf8(to_tester.await??)?;
+    let stderr = utf8(solver_stderr.await??)?;
+    let tester_stderr = utf8(tester_stderr.await??)?;
+
+    if matches!(timelimit, Some(t) if t < elapsed) {
+        Ok(Verdict::TimelimitExceeded {
+            test_case_name,
+            timelimit: timelimit.unwrap(),
+            stdin,
+            expected,
+        })
+    } else if !solver_status.success() {
+        Ok(Verdict::RuntimeError {
+            test_case_name,
+            elapsed,
+            timelimit,
+            stdin,
+            stdout,
+            stderr,
+            expected,
+            status: solver_status,
+        })
+    } else if !tester_status.success() {
+        Ok(Verdict::WrongAnswer {
+            test_case_name,
+            elapsed,
+            timelimit,
+            stdin,
+            stdout,
+            stderr,
+            checker_stdout: Arc::from(""),
+            checker_stderr: tester_stderr,
+            expected,
+            note: None,
+        })
+    } else {
+        Ok(Verdict::Accepted {
+            test_case_name,
+            elapsed,
+            timelimit,
+            stdin,
+            stdout,
+            stderr,
+            expected,
+        })
+    }
+}
+
+/// Copies `src` to `dst` until EOF, returning what has been read.
+///
+/// Keeps reading even after `dst` is closed so that the whole transcript is returned.
+async fn relay(
+    mut src: impl AsyncRead + Unpin,
+    dst: impl AsyncWrite + Unpin,
+) -> io::Result<Vec<u8>> {
+    let mut transcript = vec![];
+    let mut buf = [0; 8 * 1024];
+    let mut dst = Some(dst);
+
+    loop {
+        let n = src.read(&mut buf).await?;
+        if n == 0 {
+            break Ok(transcript);
+        }
+        transcript.extend_from_slice(&buf[..n]);
+
+        if let Some(w) = &mut dst {
+            if w.write_all(&buf[..n]).await.is_err() || w.flush().await.is_err() {
+                dst = None;
+            }
+        }
+    }
+}
+
+async fn read_to_end(mut src: impl AsyncRead + Unpin) -> io::Result<Vec<u8>> {
+    let mut buf = vec![];
+    src.read_to_end(&mut buf).await?;
+    Ok(buf)
+}
+
 async fn check(
     expected: &ExpectedOutput,
     actual: &str,
     cwd: &Path,
-    stdin_path: &Path,
-    actual_stdout_path: &Path,
-    expected_stdout_path: &Path,
+    files: &TestCaseFiles,
     bash_exe: &Path,
+    extra_output: Option<ExtraOutput>,
 ) -> anyhow::Result<Result<(), (Arc<str>, Arc<str>, Option<WrongAnswerNote>)>> {
     match expected {
-        ExpectedOutput::Deterministic(expected) => Ok(if expected.accepts(actual) {
-            Ok(())
-        } else {
-            let note = expected
-                .expected_stdout()
-                .filter(|expected| expected.split_whitespace().eq(actual.split_whitespace()))
-                .map(|_| WrongAnswerNote::WordsMatched);
-            Err((Arc::from(""), Arc::from(""), note))
-        }),
-        ExpectedOutput::Checker { text, cmd, shell } => {
-            let (program, args) = match shell {
-                CheckerShell::Bash => (bash_exe, [OsStr::new("-c"), OsStr::new(cmd)]),
-            };
+        ExpectedOutput::Deterministic(expected) => {
+            let accepted = expected.accepts(actual)
+                || matches!(
+                    (extra_output, expected.expected_stdout()),
+                    (Some(extra_output), Some(text)) if extra_output.accepts(text, actual)
+                );
+
+            Ok(if accepted {
+                Ok(())
+            } else {
+                let note = expected
+                    .expected_stdout()
+                    .filter(|expected| expected.split_whitespace().eq(actual.split_whitespace()))
+                    .map(|_| WrongAnswerNote::WordsMatched);
+                Err((Arc::from(""), Arc::from(""), note))
+            })
+        }
+        ExpectedOutput::Checker {
+            text,
+            cmd,
+            shell,
+            working_dir,
+        } => {
+            let TestCaseFiles {
+                stdin: stdin_path,
+                actual_stdout: actual_stdout_path,
+                expected_stdout: expected_stdout_path,
+                ..
+            } = files;
 
             let mut env_vars = vec![("INPUT", stdin_path), ("ACTUAL_OUTPUT", actual_stdout_path)];
             if let Some(text) = text {
@@ -708,6 +1532,17 @@ async fn check(
                 env_vars.push(("EXPECTED_OUTPUT", expected_stdout_path));
             }
 
+            let (program, args) = match shell {
+                CheckerShell::Bash => (
+                    bash_exe,
+                    [OsStr::new("-c"), OsStr::new(cmd), OsStr::new("bash")]
+                        .iter()
+                        .copied()
+                        .chain(env_vars.iter().map(|(_, path)| path.as_os_str()))
+                        .collect::<Vec<_>>(),
+                ),
+            };
+
             let Output {
                 status,
                 stdout,
@@ -715,7 +1550,7 @@ async fn check(
             } = tokio::process::Command::new(program)
                 .args(&args)
                 .envs(env_vars)
-                .current_dir(cwd)
+                .current_dir(working_dir.as_deref().unwrap_or(cwd))
                 .stdin(Stdio::null())
                 .stdout(Stdio::piped())
                 .stderr(Stdio::piped())
@@ -734,8 +1569,472 @@ async fn check(
     }
 }
 
+/// Maximum length of the stderr printed for Runtime Error.
+const STDERR_TAIL_LEN: usize = 4 * 1024;
+
+/// Returns the last `max_len` bytes of `s` at most, starting from a line if possible.
+fn tail(s: &str, max_len: usize) -> &str {
+    if s.len() <= max_len {
+        return s;
+    }
+    let mut start = s.len() - max_len;
+    while !s.is_char_boundary(start) {
+        start += 1;
+    }
+    if s.as_bytes()[start - 1] != b'\n' {
+        if let Some(i) = s[start..].find('\n').filter(|&i| start + i + 1 < s.len()) {
+            start += i + 1;
+        }
+    }
+    &s[start..]
+}
+
+/// With `RLIMIT_AS`, a solution that runs out of memory fails to allocate instead of growing.
+///
+/// Such a failure is recognized by the message that the runtime prints on it, or by `SIGKILL`.
+fn exceeds_memorylimit(status: ExitStatus, stderr: &str) -> bool {
+    const ALLOCATION_FAILURES: &[&str] = &[
+        "memory allocation of", // Rust
+        "std::bad_alloc",       // C++
+        "MemoryError",          // Python
+        "OutOfMemoryError",     // Java
+        "out of memory",        // Go, GHC, and others
+    ];
+
+    if status.success() {
+        return false;
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use std::os::unix::process::ExitStatusExt as _;
+
+        if status.signal() == Some(libc::SIGKILL) {
+            return true;
+        }
+    }
+
+    ALLOCATION_FAILURES.iter().any(|msg| stderr.contains(msg))
+}
+
+/// Escapes `text` for XML, replacing the control characters that XML 1.0 does not allow with
+/// U+FFFD.
+fn xml_escape(text: &str) -> String {
+    let mut ret = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => ret += "&amp;",
+            '<' => ret += "&lt;",
+            '>' => ret += "&gt;",
+            '"' => ret += "&quot;",
+            '\'' => ret += "&apos;",
+            '\t' | '\n' | '\r' => ret.push(c),
+            c if c < ' ' => ret.push('\u{fffd}'),
+            c => ret.push(c),
+        }
+    }
+    ret
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DiffLine<'a> {
+    Removed(usize, &'a str),
+    Added(usize, &'a str),
+}
+
+/// Computes the lines removed from `expected` and added in `actual` with LCS.
+///
+/// Returns `None` if the outputs are too large.
+fn line_diff<'a>(expected: &'a str, actual: &'a str) -> Option<Vec<DiffLine<'a>>> {
+    const MAX_TABLE_SIZE: usize = 1 << 22;
+
+    let expected = expected.lines().collect::<Vec<_>>();
+    let actual = actual.lines().collect::<Vec<_>>();
+
+    let (n, m) = (expected.len(), actual.len());
+
+    if (n + 1).saturating_mul(m + 1) > MAX_TABLE_SIZE {
+        return None;
+    }
+
+    // `lcs[i][j]`: the length of the LCS of `expected[i..]` and `actual[j..]`
+    let mut lcs = vec![vec![0; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if expected[i] == actual[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                cmp::max(lcs[i + 1][j], lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut diff = vec![];
+
+    while i < n || j < m {
+        if i < n && j < m && expected[i] == actual[j] {
+            i += 1;
+            j += 1;
+        } else if j == m || (i < n && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            diff.push(DiffLine::Removed(i + 1, expected[i]));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(j + 1, actual[j]));
+            j += 1;
+        }
+    }
+
+    Some(diff)
+}
+
+fn write_diff(mut wtr: impl WriteColor, diff: &[DiffLine<'_>]) -> io::Result<()> {
+    const MAX_LINES: usize = 20;
+
+    if diff.is_empty() {
+        return Ok(());
+    }
+
+    wtr.set_color(color_spec!(Bold, Fg(Color::Magenta)))?;
+    writeln!(wtr, "diff:")?;
+    wtr.reset()?;
+
+    for &line in diff.iter().take(MAX_LINES) {
+        let (sign, line_number, text, color) = match line {
+            DiffLine::Removed(n, text) => ('-', n, text, Color::Red),
+            DiffLine::Added(n, text) => ('+', n, text, Color::Green),
+        };
+
+        let content = text.trim_end_matches(|c| c == ' ' || c == '\t');
+        let trailing = &text[content.len()..];
+
+        wtr.set_color(color_spec!(Fg(color)))?;
+        write!(wtr, "{}{:>4} | {}", sign, line_number, content)?;
+        wtr.reset()?;
+
+        if !trailing.is_empty() {
+            wtr.set_color(color_spec!(Fg(Color::Yellow)))?;
+            for c in trailing.chars() {
+                write!(wtr, "{}", if c == '\t' { "\\t" } else { "␣" })?;
+            }
+            wtr.reset()?;
+        }
+
+        writeln!(wtr)?;
+    }
+
+    if diff.len() > MAX_LINES {
+        writeln!(wtr, "... ({} more)", diff.len() - MAX_LINES)?;
+    }
+
+    Ok(())
+}
+
 fn utf8(bytes: Vec<u8>) -> anyhow::Result<Arc<str>> {
     String::from_utf8(bytes)
         .map(Into::into)
         .map_err(|_| anyhow!("the output was not a valid UTF-8 string"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{DiffLine, JudgeOptions, JudgeOutcome, PrintPrettyOptions, Verdict};
+    use crate::testsuite::{DeterministicExpectedOutput, ExpectedOutput};
+    use std::{num::NonZeroUsize, time::Duration};
+    use termcolor::NoColor;
+
+    #[test]
+    fn line_diff() {
+        use DiffLine::{Added, Removed};
+
+        let line_diff = |expected, actual| super::line_diff(expected, actual).unwrap();
+
+        assert_eq!(Vec::<DiffLine<'_>>::new(), line_diff("1\n2\n", "1\n2\n"));
+        assert_eq!(
+            vec![Removed(2, "2"), Added(2, "3")],
+            line_diff("1\n2\n", "1\n3\n"),
+        );
+        assert_eq!(
+            vec![Removed(2, "2 "), Added(2, "2")],
+            line_diff("1\n2 \n3\n", "1\n2\n3\n"),
+        );
+        assert_eq!(vec![Added(3, "3")], line_diff("1\n2\n", "1\n2\n3\n"));
+        assert_eq!(vec![Removed(1, "0")], line_diff("0\n1\n", "1\n"));
+    }
+
+    #[test]
+    fn tail() {
+        assert_eq!("abc\n", super::tail("abc\n", 4));
+        assert_eq!("ghi\n", super::tail("abc\ndef\nghi\n", 6));
+        assert_eq!("def\nghi\n", super::tail("abc\ndef\nghi\n", 8));
+        assert_eq!("efghi", super::tail("abcdefghi", 5));
+        assert_eq!("い", super::tail("あい", 4));
+    }
+
+    #[test]
+    fn print_tap() -> anyhow::Result<()> {
+        let expected = ExpectedOutput::Deterministic(DeterministicExpectedOutput::Lines {
+            text: "3\n".into(),
+        });
+
+        let outcome = JudgeOutcome {
+            verdicts: vec![
+                Verdict::Accepted {
+                    test_case_name: Some("sample1".to_owned()),
+                    elapsed: Duration::from_millis(10),
+                    timelimit: None,
+                    stdin: "1 2\n".into(),
+                    stdout: "3\n".into(),
+                    stderr: "".into(),
+                    expected: expected.clone(),
+                },
+                Verdict::TimelimitExceeded {
+                    test_case_name: Some("#2".to_owned()),
+                    timelimit: Duration::from_secs(2),
+                    stdin: "1 2\n".into(),
+                    expected,
+                },
+            ],
+            accepted_runs: None,
+        };
+
+        let mut output = vec![];
+        outcome.print_tap(&mut output, None)?;
+
+        assert_eq!(
+            r#"TAP version 13
+1..2
+ok 1 - sample1
+not ok 2 - \#2
+  ---
+  message: Timelimit Exceeded (2000 ms)
+  severity: fail
+  stdin: "1 2\n"
+  expected: "3\n"
+  ...
+"#,
+            String::from_utf8(output)?,
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn print_junit() -> anyhow::Result<()> {
+        let expected = ExpectedOutput::Deterministic(DeterministicExpectedOutput::Lines {
+            text: "3\n".into(),
+        });
+
+        let outcome = JudgeOutcome {
+            verdicts: vec![
+                Verdict::Accepted {
+                    test_case_name: Some("sample1".to_owned()),
+                    elapsed: Duration::from_millis(10),
+                    timelimit: None,
+                    stdin: "1 2\n".into(),
+                    stdout: "3\n".into(),
+                    stderr: "".into(),
+                    expected: expected.clone(),
+                },
+                Verdict::WrongAnswer {
+                    test_case_name: Some("<2>".to_owned()),
+                    elapsed: Duration::from_millis(20),
+                    timelimit: None,
+                    stdin: "1 2\n".into(),
+                    stdout: "4\n".into(),
+                    stderr: "".into(),
+                    checker_stdout: "".into(),
+                    checker_stderr: "".into(),
+                    expected: expected.clone(),
+                    note: None,
+                },
+                Verdict::TimelimitExceeded {
+                    test_case_name: None,
+                    timelimit: Duration::from_secs(2),
+                    stdin: "1 2\n".into(),
+                    expected,
+                },
+            ],
+            accepted_runs: None,
+        };
+
+        let mut output = vec![];
+        outcome.print_junit(&mut output, "atcoder/practice/a", None)?;
+
+        assert_eq!(
+            r##"<?xml version="1.0" encoding="UTF-8"?>
+<testsuite name="atcoder/practice/a" tests="3" failures="2" errors="0" time="2.030">
+  <testcase name="sample1" classname="atcoder/practice/a" time="0.010"/>
+  <testcase name="&lt;2&gt;" classname="atcoder/practice/a" time="0.020">
+    <failure type="WrongAnswer" message="Wrong Answer (20 ms)">-1: 3
++1: 4
+</failure>
+  </testcase>
+  <testcase name="#3" classname="atcoder/practice/a" time="2.000">
+    <failure type="TimelimitExceeded" message="Timelimit Exceeded (2000 ms)"></failure>
+  </testcase>
+</testsuite>
+"##,
+            String::from_utf8(output)?,
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn print_pretty_sort_by_time() -> anyhow::Result<()> {
+        let expected = ExpectedOutput::Deterministic(DeterministicExpectedOutput::Lines {
+            text: "3\n".into(),
+        });
+
+        let accepted = |name: &str, elapsed| Verdict::Accepted {
+            test_case_name: Some(name.to_owned()),
+            elapsed: Duration::from_millis(elapsed),
+            timelimit: Some(Duration::from_secs(2)),
+            stdin: "1 2\n".into(),
+            stdout: "3\n".into(),
+            stderr: "".into(),
+            expected: expected.clone(),
+        };
+
+        let outcome = JudgeOutcome {
+            verdicts: vec![accepted("fast", 10), accepted("slow", 1800)],
+            accepted_runs: None,
+        };
+
+        assert!(!outcome.verdicts[0].is_close_to_timelimit());
+        assert!(outcome.verdicts[1].is_close_to_timelimit());
+
+        let mut output = NoColor::new(vec![]);
+        outcome.print_pretty_with_options(
+            &mut output,
+            PrintPrettyOptions {
+                sort_by_time: true,
+                ..Default::default()
+            },
+        )?;
+        let output = String::from_utf8(output.into_inner())?;
+
+        assert!(output.starts_with("2/2 (\"slow\") Accepted (1800 ms)\n"));
+        assert!(output.contains("\n1/2 (\"fast\") Accepted (10 ms)\n"));
+
+        let outcome = JudgeOutcome {
+            accepted_runs: Some((vec![10, 9], 10)),
+            ..outcome
+        };
+
+        let mut output = NoColor::new(vec![]);
+        outcome.print_pretty(&mut output, None)?;
+        let output = String::from_utf8(output.into_inner())?;
+
+        assert!(output.starts_with("1/2 (\"fast\") Accepted (10 ms) (10/10 accepted)\n"));
+        assert!(output.contains("\n2/2 (\"slow\") Accepted (1800 ms) (9/10 accepted)\n"));
+        assert!(output.ends_with("\n\n2/2 tests accepted\n"));
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn print_pretty_runtime_error() -> anyhow::Result<()> {
+        use std::{os::unix::process::ExitStatusExt as _, process::ExitStatus};
+
+        let outcome = JudgeOutcome {
+            verdicts: vec![Verdict::RuntimeError {
+                test_case_name: Some("sample1".to_owned()),
+                elapsed: Duration::from_millis(10),
+                timelimit: None,
+                stdin: "1 2 3\n".into(),
+                stdout: "".into(),
+                stderr: "index out of bounds\n".into(),
+                expected: ExpectedOutput::Deterministic(DeterministicExpectedOutput::Lines {
+                    text: "3\n".into(),
+                }),
+                status: ExitStatus::from_raw(101 << 8),
+            }],
+            accepted_runs: None,
+        };
+
+        let mut output = NoColor::new(vec![]);
+        outcome.print_pretty(&mut output, Some(4))?;
+        let output = String::from_utf8(output.into_inner())?;
+
+        assert!(output.contains("\nstdin:\n6 B\n"));
+        assert!(output.contains("\nstderr:\nindex out of bounds\n"));
+        Ok(())
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn exceeds_memorylimit() {
+        use std::{os::unix::process::ExitStatusExt as _, process::ExitStatus};
+
+        let (success, failure, killed) = (
+            ExitStatus::from_raw(0),
+            ExitStatus::from_raw(1 << 8),
+            ExitStatus::from_raw(libc::SIGKILL),
+        );
+
+        assert!(!super::exceeds_memorylimit(success, ""));
+        assert!(!super::exceeds_memorylimit(success, "MemoryError"));
+        assert!(!super::exceeds_memorylimit(failure, ""));
+        assert!(super::exceeds_memorylimit(killed, ""));
+        assert!(super::exceeds_memorylimit(
+            failure,
+            "memory allocation of 1048576 bytes failed\n",
+        ));
+        assert!(super::exceeds_memorylimit(
+            failure,
+            "terminate called after throwing an instance of 'std::bad_alloc'\n",
+        ));
+    }
+
+    #[test]
+    fn xml_escape() {
+        assert_eq!(
+            "&lt;a href=&quot;x&quot;&gt;&amp;&apos;\n\u{fffd}",
+            super::xml_escape("<a href=\"x\">&'\n\u{1b}"),
+        );
+    }
+
+    #[test]
+    fn max_concurrency() {
+        const MIB: u64 = 1024 * 1024;
+
+        let options = |memory_budget, memory_per_case| JudgeOptions {
+            memory_budget,
+            memory_per_case,
+            ..Default::default()
+        };
+
+        assert_eq!(8, options(None, None).max_concurrency(8, None));
+        assert_eq!(4, options(Some(1024 * MIB), None).max_concurrency(8, None));
+        assert_eq!(
+            8,
+            options(Some(1024 * MIB), None).max_concurrency(8, Some(128))
+        );
+        assert_eq!(
+            4,
+            options(Some(1024 * MIB), Some(256 * MIB)).max_concurrency(8, Some(128))
+        );
+        assert_eq!(
+            8,
+            options(Some(1024 * MIB), Some(64 * MIB)).max_concurrency(8, None)
+        );
+        assert_eq!(
+            2,
+            options(Some(1024 * MIB), Some(512 * MIB)).max_concurrency(8, None)
+        );
+        assert_eq!(
+            1,
+            options(Some(100 * MIB), Some(512 * MIB)).max_concurrency(8, None)
+        );
+
+        let jobs = |jobs| JudgeOptions {
+            jobs: NonZeroUsize::new(jobs),
+            memory_budget: Some(1024 * MIB),
+            memory_per_case: Some(64 * MIB),
+            ..Default::default()
+        };
+
+        assert_eq!(1, jobs(1).max_concurrency(8, None));
+        assert_eq!(16, jobs(32).max_concurrency(8, None));
+    }
+}
@@ -1,21 +1,27 @@
+use crate::{judge::CommandExpression, web::Shell};
 use anyhow::{bail, ensure, Context as _};
 use camino::Utf8PathBuf;
 use humantime_serde::Serde;
 use ignore::{overrides::OverrideBuilder, WalkBuilder};
+use indicatif::{ProgressBar, ProgressStyle};
 use itertools::{EitherOrBoth, Itertools as _};
 use maplit::hashmap;
+use regex::Regex;
 use serde::{de::Error as _, Deserialize, Deserializer, Serialize};
 use std::{
     borrow::Borrow,
     collections::{BTreeMap, BTreeSet, HashMap, HashSet},
-    fs,
+    fmt, fs,
     hash::Hash,
-    path::Path,
+    io::{self, Read as _},
+    ops,
+    path::{Path, PathBuf},
     str::FromStr,
     sync::Arc,
     time::Duration,
 };
 use url::Url;
+use zip::ZipArchive;
 
 #[derive(Deserialize, Serialize, Debug, PartialEq)]
 #[serde(tag = "type")]
@@ -33,6 +39,9 @@ impl TestSuite {
 
                 yaml += &key_value("type", "Batch").ok()?;
                 yaml += &key_value("timelimit", Serde::from(suite.timelimit)).ok()?;
+                if let Some(memorylimit) = suite.memorylimit {
+                    yaml += &key_value("memorylimit", memorylimit).ok()?;
+                }
                 yaml += &key_value("match", &suite.r#match).ok()?;
 
                 yaml += if suite.cases.is_empty() {
@@ -153,6 +162,9 @@ impl TestSuite {
 pub struct BatchTestSuite {
     #[serde(default, with = "humantime_serde")]
     pub timelimit: Option<Duration>,
+    /// Memory limit in megabytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memorylimit: Option<u64>,
     pub r#match: Match,
     #[serde(default)]
     pub cases: Vec<PartialBatchTestCase>,
@@ -164,17 +176,69 @@ impl BatchTestSuite {
     pub fn load_test_cases<
         S: Borrow<str> + Eq + Hash,
         F: FnMut(Option<&Url>) -> anyhow::Result<Vec<PartialBatchTestCase>>,
+    >(
+        &self,
+        parent_dir: &Path,
+        names: Option<HashSet<S>>,
+        prepare_system_test_cases: F,
+    ) -> anyhow::Result<Vec<BatchTestCase>> {
+        struct LogShell;
+
+        impl Shell for LogShell {
+            fn warn<T: fmt::Display>(&mut self, message: T) -> io::Result<()> {
+                log::warn!("{}", message);
+                Ok(())
+            }
+        }
+
+        self.load_test_cases_with_shell(parent_dir, names, prepare_system_test_cases, LogShell)
+    }
+
+    /// Same as [`load_test_cases`], but shows a progress bar while extracting Zip archives, and
+    /// reports the skipped entries with `shell`.
+    ///
+    /// [`load_test_cases`]: #method.load_test_cases
+    pub fn load_test_cases_with_shell<
+        S: Borrow<str> + Eq + Hash,
+        F: FnMut(Option<&Url>) -> anyhow::Result<Vec<PartialBatchTestCase>>,
     >(
         &self,
         parent_dir: &Path,
         mut names: Option<HashSet<S>>,
         mut prepare_system_test_cases: F,
+        mut shell: impl Shell,
     ) -> anyhow::Result<Vec<BatchTestCase>> {
-        let mut cases = self.cases.clone();
-        for extend in &self.extend {
-            cases.extend(extend.load_test_cases(parent_dir, &mut prepare_system_test_cases)?);
+        let progress = ProgressBar::with_draw_target(0, shell.progress_draw_target());
+
+        progress.set_style(
+            ProgressStyle::default_bar()
+                .template("{prefix:.bold} {pos}/{len} {wide_bar} {percent}%"),
+        );
+        progress.set_prefix("Extracting");
+
+        let mut warnings = vec![];
+
+        let cases = (|| -> anyhow::Result<_> {
+            let mut cases = self.cases.clone();
+            for extend in &self.extend {
+                cases.extend(extend.load_test_cases(
+                    parent_dir,
+                    &progress,
+                    &mut warnings,
+                    &mut prepare_system_test_cases,
+                )?);
+            }
+            Ok(cases)
+        })();
+
+        progress.finish_and_clear();
+
+        for warning in warnings {
+            shell.warn(warning)?;
         }
 
+        let cases = cases?;
+
         let cases = cases
             .into_iter()
             .filter(
@@ -183,7 +247,19 @@ impl BatchTestSuite {
                     _ => true,
                 },
             )
-            .map(|case| BatchTestCase::new(case, self.timelimit, &self.r#match))
+            .map(|case| {
+                let mut case =
+                    BatchTestCase::new(case, self.timelimit, self.memorylimit, &self.r#match);
+                if let ExpectedOutput::Checker {
+                    working_dir: Some(working_dir),
+                    ..
+                } = &mut case.output
+                {
+                    let rel_path = working_dir.strip_prefix(".").unwrap_or(working_dir);
+                    *working_dir = parent_dir.join(rel_path);
+                }
+                case
+            })
             .collect();
 
         if let Some(names) = names {
@@ -226,6 +302,26 @@ pub enum Additional {
         timelimit: Option<Duration>,
         #[serde(skip_serializing_if = "Option::is_none")]
         r#match: Option<Match>,
+        /// Keeps the subdirectories in the names of the test cases (e.g. `01_subtask1/03`).
+        #[serde(default, skip_serializing_if = "ops::Not::not")]
+        group_by_dir: bool,
+    },
+    /// Reads the test cases out of a Zip archive.
+    ///
+    /// `in` and `out` are regular expressions for the entry names. The first capture group is
+    /// the name of the test case (e.g. `^in/(.+)\.txt$`).
+    Zip {
+        path: Utf8PathBuf,
+        r#in: String,
+        out: String,
+        #[serde(
+            default,
+            with = "humantime_serde",
+            skip_serializing_if = "Option::is_none"
+        )]
+        timelimit: Option<Duration>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        r#match: Option<Match>,
     },
     SystemTestCases {
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -237,6 +333,8 @@ impl Additional {
     fn load_test_cases(
         &self,
         parent_dir: &Path,
+        progress: &ProgressBar,
+        warnings: &mut Vec<String>,
         mut prepare_system_test_cases: impl FnMut(
             Option<&Url>,
         ) -> anyhow::Result<Vec<PartialBatchTestCase>>,
@@ -248,31 +346,42 @@ impl Additional {
                 out,
                 r#match,
                 timelimit,
+                group_by_dir,
             } => {
                 let base = Path::new(base);
                 let base = parent_dir.join(base.strip_prefix(".").unwrap_or(base));
                 let base = base.strip_prefix(".").unwrap_or(&base);
 
-                let mut cases = BTreeMap::<_, (Option<_>, Option<_>)>::new();
+                let mut cases = InOutPairs::new();
 
-                let walk = |overrides| -> _ {
-                    WalkBuilder::new(base)
+                let walk = |glob: &str| -> anyhow::Result<_> {
+                    let overrides = OverrideBuilder::new(base).add(glob)?.build()?;
+                    let root = base.join(literal_dir_prefix(glob));
+
+                    Ok(WalkBuilder::new(base)
                         .max_depth(Some(128))
                         .overrides(overrides)
                         .standard_filters(false)
                         .build()
-                        .map::<anyhow::Result<_>, _>(|entry| {
+                        .map::<anyhow::Result<_>, _>(move |entry| {
                             let path = entry?.into_path();
 
                             if path.is_dir() {
                                 return Ok(None);
                             }
 
-                            let name = path
-                                .file_stem()
-                                .unwrap_or_default()
-                                .to_string_lossy()
-                                .into_owned();
+                            let name = match path.strip_prefix(&root) {
+                                Ok(rel_path) if *group_by_dir => rel_path
+                                    .with_extension("")
+                                    .iter()
+                                    .map(|s| s.to_string_lossy())
+                                    .join("/"),
+                                _ => path
+                                    .file_stem()
+                                    .unwrap_or_default()
+                                    .to_string_lossy()
+                                    .into_owned(),
+                            };
 
                             let content = fs::read_to_string(&path)
                                 .with_context(|| format!("Could not read {}", path.display()))?
@@ -280,59 +389,234 @@ impl Additional {
 
                             Ok(Some((name, content)))
                         })
-                        .flat_map(Result::transpose)
+                        .flat_map(Result::transpose))
                 };
 
-                for result in walk(OverrideBuilder::new(base).add(r#in)?.build()?) {
+                for result in walk(r#in)? {
                     let (name, content) = result?;
                     let (entry, _) = cases.entry(name.clone()).or_default();
                     ensure!(entry.is_none(), "Duplicated name: {:?}", name);
                     *entry = Some(content);
                 }
 
-                for result in walk(OverrideBuilder::new(base).add(out)?.build()?) {
+                for result in walk(out)? {
                     let (name, content) = result?;
                     let (_, entry) = cases.entry(name.clone()).or_default();
                     ensure!(entry.is_none(), "Duplicated name: {:?}", name);
                     *entry = Some(content);
                 }
 
-                cases
-                    .into_iter()
-                    .map(|kv| {
-                        let (name, r#in, out) = match kv {
-                            (_, (None, None)) => unreachable!(),
-                            (name, (None, Some(_))) => bail!("No input file for {:?}", name),
-                            (name, (Some(r#in), out)) => (name, r#in, out),
-                        };
+                pair_in_out(cases, *timelimit, r#match)
+            }
+            Self::Zip {
+                path,
+                r#in,
+                out,
+                timelimit,
+                r#match,
+            } => {
+                let path = Path::new(path);
+                let path = parent_dir.join(path.strip_prefix(".").unwrap_or(path));
 
-                        Ok(PartialBatchTestCase {
-                            name: Some(name),
-                            r#in,
-                            out,
-                            timelimit: *timelimit,
-                            r#match: r#match.clone(),
-                        })
-                    })
-                    .collect()
+                let regex = |s: &str| -> anyhow::Result<_> {
+                    Regex::new(s).with_context(|| format!("Invalid regex: {:?}", s))
+                };
+                let (in_regex, out_regex) = (regex(r#in)?, regex(out)?);
+
+                let mut archive = fs::File::open(&path)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|file| ZipArchive::new(file).map_err(Into::into))
+                    .with_context(|| format!("Could not open {}", path.display()))?;
+
+                let mut cases = InOutPairs::new();
+
+                // Unreadable entries are skipped so that the rest of a broken archive is still
+                // usable.
+                let mut skipped_entries = vec![];
+                let mut skipped_names = BTreeSet::new();
+
+                progress.set_length(archive.len() as _);
+                progress.set_position(0);
+
+                for i in 0..archive.len() {
+                    progress.inc(1);
+
+                    let mut file = match archive.by_index(i) {
+                        Ok(file) => file,
+                        Err(err) => {
+                            skipped_entries.push(format!("#{} ({})", i, err));
+                            continue;
+                        }
+                    };
+
+                    if file.is_dir() {
+                        continue;
+                    }
+
+                    let entry_name = file.name().to_owned();
+
+                    let (name, is_in) = if let Some(caps) = in_regex.captures(&entry_name) {
+                        (caps.get(1).map(|m| m.as_str().to_owned()), true)
+                    } else if let Some(caps) = out_regex.captures(&entry_name) {
+                        (caps.get(1).map(|m| m.as_str().to_owned()), false)
+                    } else {
+                        continue;
+                    };
+                    let name = name.unwrap_or_else(|| entry_name.clone());
+
+                    let mut content = "".to_owned();
+                    if let Err(err) = file.read_to_string(&mut content) {
+                        skipped_entries.push(format!("{} ({})", entry_name, err));
+                        skipped_names.insert(name);
+                        continue;
+                    }
+
+                    let (in_entry, out_entry) = cases.entry(name.clone()).or_default();
+                    let entry = if is_in { in_entry } else { out_entry };
+                    ensure!(entry.is_none(), "Duplicated name: {:?}", name);
+                    *entry = Some(content.into());
+                }
+
+                if !skipped_entries.is_empty() {
+                    cases.retain(|name, _| !skipped_names.contains(name));
+
+                    ensure!(
+                        !cases.is_empty(),
+                        "Could not read any test case in {}: [{}]",
+                        path.display(),
+                        skipped_entries.join(", "),
+                    );
+
+                    warnings.push(format!(
+                        "Skipped unreadable entries in {}: [{}]",
+                        path.display(),
+                        skipped_entries.join(", "),
+                    ));
+                }
+
+                let orphans = orphan_inputs(&cases);
+                if !orphans.is_empty() {
+                    warnings.push(format!(
+                        "No output entries in {} for: [{}]",
+                        path.display(),
+                        orphans.join(", "),
+                    ));
+                }
+
+                let orphans = orphan_outputs(&cases);
+                if !orphans.is_empty() {
+                    warnings.push(format!(
+                        "Skipped the output entries without inputs in {}: [{}]",
+                        path.display(),
+                        orphans.join(", "),
+                    ));
+                    cases.retain(|_, (r#in, _)| r#in.is_some());
+                }
+
+                pair_in_out(cases, *timelimit, r#match)
             }
             Self::SystemTestCases { problem } => prepare_system_test_cases(problem.as_ref()),
         }
     }
 }
 
+/// Inputs and outputs of test cases keyed by their names, before they are paired.
+pub type InOutPairs<T> = BTreeMap<String, (Option<T>, Option<T>)>;
+
+fn pair_in_out(
+    cases: InOutPairs<Arc<str>>,
+    timelimit: Option<Duration>,
+    r#match: &Option<Match>,
+) -> anyhow::Result<Vec<PartialBatchTestCase>> {
+    cases
+        .into_iter()
+        .map(|kv| {
+            let (name, r#in, out) = match kv {
+                (_, (None, None)) => unreachable!(),
+                (name, (None, Some(_))) => bail!("No input file for {:?}", name),
+                (name, (Some(r#in), out)) => (name, r#in, out),
+            };
+
+            Ok(PartialBatchTestCase {
+                name: Some(name),
+                r#in,
+                out,
+                timelimit,
+                r#match: r#match.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Returns the names of the test cases without outputs, if any other test case has an output.
+///
+/// They usually mean that the outputs are named differently (e.g. `in/1.txt` and `output/1.txt`).
+fn orphan_inputs(cases: &InOutPairs<Arc<str>>) -> Vec<&str> {
+    if cases.values().all(|(_, out)| out.is_none()) {
+        return vec![];
+    }
+
+    cases
+        .iter()
+        .filter(|(_, (r#in, out))| r#in.is_some() && out.is_none())
+        .map(|(name, _)| &**name)
+        .collect()
+}
+
+/// Returns the names of the test cases without inputs.
+fn orphan_outputs(cases: &InOutPairs<Arc<str>>) -> Vec<String> {
+    cases
+        .iter()
+        .filter(|(_, (r#in, _))| r#in.is_none())
+        .map(|(name, _)| name.clone())
+        .collect()
+}
+
+/// Returns the leading directories of `glob` that contain no wildcards.
+fn literal_dir_prefix(glob: &str) -> &str {
+    let glob = glob.trim_start_matches('/');
+
+    let mut end = 0;
+    for (i, c) in glob.char_indices() {
+        if "*?[{\\".contains(c) {
+            break;
+        }
+        if c == '/' {
+            end = i;
+        }
+    }
+    &glob[..end]
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub enum Match {
+    Any,
+    NonEmpty,
     Exact,
     SplitWhitespace,
     Lines,
+    /// Compares the lines as `Lines` but in any order, for problems that accept any order of the
+    /// answers.
+    Unordered,
     Float {
         relative_error: Option<PositiveFinite<f64>>,
         absolute_error: Option<PositiveFinite<f64>>,
     },
+    /// Runs `cmd` with `shell` for each test case.
+    ///
+    /// The paths to the input, the actual output, and the expected output (if any) are passed as
+    /// positional parameters (`$1`, `$2`, `$3`) and environment variables (`$INPUT`,
+    /// `$ACTUAL_OUTPUT`, `$EXPECTED_OUTPUT`).
+    /// The output is accepted if and only if `cmd` exits with code 0.
+    /// Otherwise the stdout and stderr of `cmd` are shown as the reason of the wrong answer.
+    ///
+    /// `cmd` runs in `working_dir` relative to the test suite file if set, and in the working
+    /// directory of the solution otherwise.
     Checker {
         cmd: String,
         shell: CheckerShell,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        working_dir: Option<Utf8PathBuf>,
     },
 }
 
@@ -345,21 +629,124 @@ pub enum CheckerShell {
 pub struct InteractiveTestSuite {
     #[serde(default, with = "humantime_serde")]
     pub timelimit: Option<Duration>,
+    /// Program that talks with the solution. Required to judge.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tester: Option<Tester>,
+    /// Additional arguments for `tester`. Each element makes a test case.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub each_args: Vec<Vec<String>>,
+}
+
+impl InteractiveTestSuite {
+    pub fn load_test_cases<S: Borrow<str> + Eq + Hash>(
+        &self,
+        parent_dir: &Path,
+        mut names: Option<HashSet<S>>,
+    ) -> anyhow::Result<Vec<InteractiveTestCase>> {
+        let Tester {
+            command,
+            working_dir,
+        } = self
+            .tester
+            .as_ref()
+            .with_context(|| "`tester` is required to judge interactive problems")?;
+
+        let (program, args) = command
+            .split_first()
+            .with_context(|| "`tester.command` must not be empty")?;
+
+        let cwd = match working_dir {
+            Some(working_dir) => {
+                let working_dir = Path::new(working_dir);
+                parent_dir.join(working_dir.strip_prefix(".").unwrap_or(working_dir))
+            }
+            None => parent_dir.to_owned(),
+        };
+
+        let each_args = if self.each_args.is_empty() {
+            vec![None]
+        } else {
+            self.each_args.iter().map(Some).collect()
+        };
+
+        let cases = each_args
+            .into_iter()
+            .map(|each_args| {
+                let name = each_args.map(|each_args| each_args.join(" "));
+                (name, each_args)
+            })
+            .filter(|(name, _)| match (names.as_mut(), name.as_ref()) {
+                (Some(names), Some(name)) => names.remove(name),
+                _ => true,
+            })
+            .map(|(name, each_args)| InteractiveTestCase {
+                name,
+                timelimit: self.timelimit,
+                tester: CommandExpression {
+                    program: program.into(),
+                    args: args
+                        .iter()
+                        .chain(each_args.into_iter().flatten())
+                        .map(Into::into)
+                        .collect(),
+                    cwd: cwd.clone(),
+                    env: BTreeMap::new(),
+                },
+            })
+            .collect();
+
+        if let Some(names) = names {
+            if !names.is_empty() {
+                bail!(
+                    "No such test cases: {:?}",
+                    names.iter().map(Borrow::borrow).collect::<BTreeSet<_>>(),
+                );
+            }
+        }
+
+        Ok(cases)
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Tester {
+    /// Program and arguments.
+    pub command: Vec<String>,
+    /// Working directory, relative to the directory of the test suite file. Defaults to the
+    /// directory itself.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<Utf8PathBuf>,
+}
+
+#[derive(Debug, Clone)]
+pub struct InteractiveTestCase {
+    pub name: Option<String>,
+    pub timelimit: Option<Duration>,
+    /// The solution is accepted if and only if this exits with code 0.
+    pub tester: CommandExpression,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct BatchTestCase {
     pub name: Option<String>,
     pub timelimit: Option<Duration>,
+    /// Memory limit in megabytes.
+    pub memorylimit: Option<u64>,
     pub input: Arc<str>,
     pub output: ExpectedOutput,
 }
 
 impl BatchTestCase {
-    fn new(case: PartialBatchTestCase, timelimit: Option<Duration>, matching: &Match) -> Self {
+    fn new(
+        case: PartialBatchTestCase,
+        timelimit: Option<Duration>,
+        memorylimit: Option<u64>,
+        matching: &Match,
+    ) -> Self {
         BatchTestCase {
             name: case.name,
             timelimit: case.timelimit.or(timelimit),
+            memorylimit,
             input: case.r#in,
             output: ExpectedOutput::new(case.out, case.r#match.unwrap_or_else(|| matching.clone())),
         }
@@ -373,13 +760,29 @@ pub enum ExpectedOutput {
         text: Option<Arc<str>>,
         cmd: String,
         shell: CheckerShell,
+        /// Absolute if set.
+        working_dir: Option<PathBuf>,
     },
 }
 
 impl ExpectedOutput {
     fn new(text: Option<Arc<str>>, matching: Match) -> Self {
         match (text, matching) {
-            (text, Match::Checker { cmd, shell }) => Self::Checker { text, cmd, shell },
+            (
+                text,
+                Match::Checker {
+                    cmd,
+                    shell,
+                    working_dir,
+                },
+            ) => Self::Checker {
+                text,
+                cmd,
+                shell,
+                working_dir: working_dir.map(Into::into),
+            },
+            (_, Match::Any) => Self::Deterministic(DeterministicExpectedOutput::Pass),
+            (_, Match::NonEmpty) => Self::Deterministic(DeterministicExpectedOutput::NonEmpty),
             (Some(text), Match::Exact) => {
                 Self::Deterministic(DeterministicExpectedOutput::Exact { text })
             }
@@ -389,6 +792,9 @@ impl ExpectedOutput {
             (Some(text), Match::Lines) => {
                 Self::Deterministic(DeterministicExpectedOutput::Lines { text })
             }
+            (Some(text), Match::Unordered) => {
+                Self::Deterministic(DeterministicExpectedOutput::Unordered { text })
+            }
             (
                 Some(text),
                 Match::Float {
@@ -429,6 +835,7 @@ impl ExpectedOutput {
 #[derive(Debug, Clone, PartialEq)]
 pub enum DeterministicExpectedOutput {
     Pass,
+    NonEmpty,
     Exact {
         text: Arc<str>,
     },
@@ -438,6 +845,9 @@ pub enum DeterministicExpectedOutput {
     Lines {
         text: Arc<str>,
     },
+    Unordered {
+        text: Arc<str>,
+    },
     Float {
         text: Arc<str>,
         relative_error: Option<PositiveFinite<f64>>,
@@ -449,9 +859,13 @@ impl DeterministicExpectedOutput {
     pub(crate) fn accepts(&self, actual: &str) -> bool {
         match self {
             Self::Pass => true,
+            Self::NonEmpty => !actual.trim().is_empty(),
             Self::Exact { text } => &**text == actual,
             Self::SplitWhitespace { text } => text.split_whitespace().eq(actual.split_whitespace()),
-            Self::Lines { text } => text.lines().eq(actual.lines()),
+            Self::Lines { text } => trimmed_lines(text).eq(trimmed_lines(actual)),
+            Self::Unordered { text } => trimmed_lines(text)
+                .sorted()
+                .eq(trimmed_lines(actual).sorted()),
             Self::Float {
                 text,
                 relative_error,
@@ -469,7 +883,7 @@ impl DeterministicExpectedOutput {
                                 match (s1.parse::<f64>(), s2.parse::<f64>()) {
                                     (Ok(v1), Ok(v2)) => {
                                         (v1 - v2).abs() <= absolute_error
-                                            || ((v1 - v2) / v2).abs() <= relative_error
+                                            || (v1 - v2).abs() <= relative_error * v1.abs()
                                     }
                                     _ => s1 == s2,
                                 }
@@ -486,15 +900,48 @@ impl DeterministicExpectedOutput {
 
     pub(crate) fn expected_stdout(&self) -> Option<&str> {
         match self {
-            Self::Pass => None,
+            Self::Pass | Self::NonEmpty => None,
             Self::Exact { text }
             | Self::SplitWhitespace { text }
             | Self::Lines { text }
+            | Self::Unordered { text }
             | Self::Float { text, .. } => Some(text),
         }
     }
 }
 
+/// How to tolerate lines of the actual output that are not in the expected one.
+///
+/// The lines are compared with trailing whitespace removed.
+#[derive(strum::EnumString, strum::EnumVariantNames, Debug, Clone, Copy, PartialEq, Eq)]
+#[strum(serialize_all = "lowercase")]
+pub enum ExtraOutput {
+    /// The expected lines must be at the beginning of the actual output.
+    Prefix,
+    /// The expected lines must appear in the actual output in order.
+    Subsequence,
+}
+
+impl ExtraOutput {
+    pub(crate) fn accepts(self, expected: &str, actual: &str) -> bool {
+        let mut actual = trimmed_lines(actual);
+
+        match self {
+            Self::Prefix => trimmed_lines(expected).all(|line| actual.next() == Some(line)),
+            Self::Subsequence => trimmed_lines(expected).all(|line| actual.any(|l| l == line)),
+        }
+    }
+}
+
+/// Lines of `text` with trailing whitespace removed, excluding a trailing blank line.
+fn trimmed_lines(text: &str) -> impl Iterator<Item = &str> {
+    let mut lines = text.lines().map(str::trim_end).collect::<Vec<_>>();
+    if lines.last() == Some(&"") {
+        lines.pop();
+    }
+    lines.into_iter()
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Serialize)]
 #[serde(transparent)]
 pub struct PositiveFinite<F>(F);
@@ -581,12 +1028,21 @@ mod serde_fn {
 #[cfg(test)]
 mod tests {
     use crate::testsuite::{
-        Additional, BatchTestSuite, DeterministicExpectedOutput, Match, PartialBatchTestCase,
-        PositiveFinite, TestSuite,
+        Additional, BatchTestSuite, DeterministicExpectedOutput, ExpectedOutput, ExtraOutput,
+        InteractiveTestSuite, Match, PartialBatchTestCase, PositiveFinite, TestSuite,
     };
     use difference::assert_diff;
+    use indicatif::ProgressBar;
+    use maplit::hashset;
     use pretty_assertions::assert_eq;
-    use std::time::Duration;
+    use std::{
+        collections::{BTreeMap, HashSet},
+        fs::File,
+        io::Write as _,
+        path::Path,
+        time::Duration,
+    };
+    use zip::{write::FileOptions, ZipWriter};
 
     #[test]
     fn atcoder_abc162_a() {
@@ -617,6 +1073,7 @@ extend: []
 "#,
             &TestSuite::Batch(BatchTestSuite {
                 timelimit: Some(Duration::from_secs(2)),
+                memorylimit: None,
                 r#match: Match::Lines,
                 cases: vec![
                     PartialBatchTestCase {
@@ -649,6 +1106,37 @@ extend: []
             r#"---
 type: Batch
 timelimit: 2s
+memorylimit: 1024
+match: Lines
+
+cases:
+  - name: Sample 1
+    in: |
+      117
+    out: |
+      Yes
+
+extend: []
+"#,
+            &TestSuite::Batch(BatchTestSuite {
+                timelimit: Some(Duration::from_secs(2)),
+                memorylimit: Some(1024),
+                r#match: Match::Lines,
+                cases: vec![PartialBatchTestCase {
+                    name: Some("Sample 1".to_owned()),
+                    r#in: "117\n".into(),
+                    out: Some("Yes\n".into()),
+                    timelimit: None,
+                    r#match: None,
+                }],
+                extend: vec![],
+            }),
+        );
+
+        test_serialize_deserialize(
+            r#"---
+type: Batch
+timelimit: 2s
 match: Lines
 
 cases: []
@@ -661,6 +1149,7 @@ extend:
 "#,
             &TestSuite::Batch(BatchTestSuite {
                 timelimit: Some(Duration::from_secs(2)),
+                memorylimit: None,
                 r#match: Match::Lines,
                 cases: vec![],
                 extend: vec![Additional::Text {
@@ -669,6 +1158,7 @@ extend:
                     out: "/out/*.txt".into(),
                     timelimit: None,
                     r#match: None,
+                    group_by_dir: false,
                 }],
             }),
         );
@@ -701,6 +1191,7 @@ extend: []
 "#,
             &TestSuite::Batch(BatchTestSuite {
                 timelimit: Some(Duration::from_secs(2)),
+                memorylimit: None,
                 r#match: Match::Float {
                     relative_error: Some(PositiveFinite(0.01)),
                     absolute_error: Some(PositiveFinite(0.01)),
@@ -755,6 +1246,7 @@ extend: []
 "#,
             &TestSuite::Batch(BatchTestSuite {
                 timelimit: Some(Duration::from_secs(2)),
+                memorylimit: None,
                 r#match: Match::Lines,
                 cases: vec![
                     PartialBatchTestCase {
@@ -777,16 +1269,272 @@ extend: []
         );
     }
 
+    #[test]
+    fn interactive_load_test_cases() {
+        let suite = serde_yaml::from_str::<InteractiveTestSuite>(
+            r#"---
+timelimit: 2s
+tester:
+  command: [python3, ./tester.py]
+  working_dir: ./testers
+each_args:
+  - ["1"]
+  - ["2", "--hard"]
+"#,
+        )
+        .unwrap();
+
+        let cases = suite
+            .load_test_cases(Path::new("/base"), None::<HashSet<String>>)
+            .unwrap();
+
+        assert_eq!(
+            ["1", "2 --hard"],
+            *cases
+                .iter()
+                .map(|c| c.name.as_deref().unwrap())
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(
+            ["./tester.py", "2", "--hard"],
+            *cases[1]
+                .tester
+                .args
+                .iter()
+                .map(|a| a.to_str().unwrap())
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(Path::new("/base/testers"), cases[1].tester.cwd);
+        assert_eq!(Some(Duration::from_secs(2)), cases[1].timelimit);
+
+        let err = suite
+            .load_test_cases(Path::new("/base"), Some(hashset!["3".to_owned()]))
+            .unwrap_err();
+        assert!(err.to_string().contains("No such test cases"));
+    }
+
+    #[test]
+    fn checker_working_dir() {
+        let suite = serde_yaml::from_str::<BatchTestSuite>(
+            r#"---
+timelimit: 2s
+match:
+  Checker:
+    cmd: ./checker "$INPUT" "$ACTUAL_OUTPUT"
+    shell: Bash
+    working_dir: ./checkers
+cases:
+  - in: "1\n"
+"#,
+        )
+        .unwrap();
+
+        let cases = suite
+            .load_test_cases(
+                Path::new("/base"),
+                None::<HashSet<String>>,
+                |_| unreachable!(),
+            )
+            .unwrap();
+
+        match &cases[0].output {
+            ExpectedOutput::Checker { working_dir, .. } => {
+                assert_eq!(Some(Path::new("/base/checkers")), working_dir.as_deref())
+            }
+            output => panic!("{:?}", output),
+        }
+    }
+
+    #[test]
+    fn zip_load_test_cases() -> anyhow::Result<()> {
+        let tempdir = tempfile::Builder::new()
+            .prefix("snowchains-core-test-")
+            .tempdir()?;
+
+        let mut zip = ZipWriter::new(File::create(tempdir.path().join("a.zip"))?);
+        for (entry, content) in &[
+            ("in/1.txt", "1\n"),
+            ("out/1.txt", "2\n"),
+            ("in/2.txt", "3\n"),
+            ("out/3.txt", "4\n"),
+            ("README", ""),
+        ] {
+            zip.start_file(*entry, FileOptions::default())?;
+            zip.write_all(content.as_bytes())?;
+        }
+        zip.finish()?;
+
+        let suite = BatchTestSuite {
+            timelimit: None,
+            memorylimit: None,
+            r#match: Match::Lines,
+            cases: vec![],
+            extend: vec![Additional::Zip {
+                path: "./a.zip".into(),
+                r#in: r"^in/(.+)\.txt$".to_owned(),
+                out: r"^out/(.+)\.txt$".to_owned(),
+                timelimit: None,
+                r#match: None,
+            }],
+        };
+
+        let mut warnings = vec![];
+
+        let cases = suite
+            .extend
+            .iter()
+            .map(|a| {
+                a.load_test_cases(
+                    tempdir.path(),
+                    &ProgressBar::hidden(),
+                    &mut warnings,
+                    |_| unreachable!(),
+                )
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?
+            .concat();
+
+        assert_eq!(
+            [(Some("1"), "1\n", Some("2\n")), (Some("2"), "3\n", None),],
+            *cases
+                .iter()
+                .map(|c| (c.name.as_deref(), &*c.r#in, c.out.as_deref()))
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(2, warnings.len());
+        assert!(warnings[0].starts_with("No output entries in "));
+        assert!(warnings[0].ends_with(": [2]"));
+        assert!(warnings[1].starts_with("Skipped the output entries without inputs in "));
+        assert!(warnings[1].ends_with(": [3]"));
+
+        let write_zip = |entries: &[(&str, &[u8])]| -> anyhow::Result<_> {
+            let mut zip = ZipWriter::new(File::create(tempdir.path().join("b.zip"))?);
+            for (entry, content) in entries {
+                zip.start_file(*entry, FileOptions::default())?;
+                zip.write_all(content)?;
+            }
+            zip.finish()?;
+            Ok(())
+        };
+
+        let load = |warnings: &mut _| {
+            Additional::Zip {
+                path: "./b.zip".into(),
+                r#in: r"^in/(.+)\.txt$".to_owned(),
+                out: r"^out/(.+)\.txt$".to_owned(),
+                timelimit: None,
+                r#match: None,
+            }
+            .load_test_cases(
+                tempdir.path(),
+                &ProgressBar::hidden(),
+                warnings,
+                |_| unreachable!(),
+            )
+        };
+
+        write_zip(&[
+            ("in/1.txt", &b"1\n"[..]),
+            ("in/2.txt", &b"\xff\n"[..]),
+            ("out/2.txt", &b"2\n"[..]),
+        ])?;
+        let mut warnings = vec![];
+        assert_eq!(
+            [Some("1")],
+            *load(&mut warnings)?
+                .iter()
+                .map(|c| c.name.as_deref())
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(1, warnings.len());
+        assert!(warnings[0].starts_with("Skipped unreadable entries in "));
+        assert!(warnings[0].contains(": [in/2.txt ("));
+
+        write_zip(&[("in/1.txt", &b"\xff\n"[..])])?;
+        assert!(load(&mut vec![]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn orphans() {
+        let cases = |cases: &[(&str, Option<&str>, Option<&str>)]| {
+            cases
+                .iter()
+                .map(|&(name, r#in, out)| {
+                    (name.to_owned(), (r#in.map(Into::into), out.map(Into::into)))
+                })
+                .collect::<BTreeMap<_, _>>()
+        };
+
+        assert_eq!(
+            ["2"],
+            *super::orphan_inputs(&cases(&[("1", Some(""), Some("")), ("2", Some(""), None)])),
+        );
+        assert!(
+            super::orphan_inputs(&cases(&[("1", Some(""), None), ("2", Some(""), None)]))
+                .is_empty()
+        );
+        assert!(super::orphan_inputs(&cases(&[("1", Some(""), Some(""))])).is_empty());
+
+        assert_eq!(
+            ["2"],
+            *super::orphan_outputs(&cases(&[("1", Some(""), Some("")), ("2", None, Some(""))])),
+        );
+        assert!(super::orphan_outputs(&cases(&[("1", Some(""), None)])).is_empty());
+    }
+
     fn test_serialize_deserialize(yaml: &str, expected: &TestSuite) {
         let actual = serde_yaml::from_str::<TestSuite>(yaml).unwrap();
         assert_eq!(*expected, actual);
         assert_diff!(yaml, &actual.to_yaml_pretty(), "\n", 0);
     }
 
+    #[test]
+    fn unordered() {
+        test_serialize_deserialize(
+            r#"---
+type: Batch
+timelimit: 2s
+match: Unordered
+
+cases:
+  - in: |
+      3
+    out: |
+      1 2
+      1 3
+      2 3
+
+extend: []
+"#,
+            &TestSuite::Batch(BatchTestSuite {
+                timelimit: Some(Duration::from_secs(2)),
+                memorylimit: None,
+                r#match: Match::Unordered,
+                cases: vec![PartialBatchTestCase {
+                    name: None,
+                    r#in: "3\n".into(),
+                    out: Some("1 2\n1 3\n2 3\n".into()),
+                    timelimit: None,
+                    r#match: None,
+                }],
+                extend: vec![],
+            }),
+        );
+    }
+
     #[test]
     fn expected_output_accepts() {
         assert!(DeterministicExpectedOutput::Pass.accepts("ミ゙"));
 
+        assert!(DeterministicExpectedOutput::Pass.accepts(""));
+
+        assert!(DeterministicExpectedOutput::NonEmpty.accepts("1\n"));
+
+        assert!(!DeterministicExpectedOutput::NonEmpty.accepts(""));
+
+        assert!(!DeterministicExpectedOutput::NonEmpty.accepts(" \n"));
+
         assert!(DeterministicExpectedOutput::Exact {
             text: "1 2\n".into()
         }
@@ -837,6 +1585,78 @@ extend: []
         }
         .accepts("1\n2\n"));
 
+        assert!(DeterministicExpectedOutput::Lines {
+            text: "1 2\n3\n".into()
+        }
+        .accepts("1 2\r\n3\r\n"));
+
+        assert!(DeterministicExpectedOutput::Lines {
+            text: "1 2\r\n3\r\n".into()
+        }
+        .accepts("1 2\n3\n"));
+
+        assert!(DeterministicExpectedOutput::Lines {
+            text: "1 2\n3\n".into()
+        }
+        .accepts("1 2\r\n3\r\n\r\n"));
+
+        assert!(DeterministicExpectedOutput::Lines {
+            text: "1 2\n3\n".into()
+        }
+        .accepts("1 2\n3"));
+
+        assert!(DeterministicExpectedOutput::Lines {
+            text: "1 2\n3".into()
+        }
+        .accepts("1 2\n3\n"));
+
+        assert!(DeterministicExpectedOutput::Lines {
+            text: "1\n\n".into()
+        }
+        .accepts("1"));
+
+        assert!(!DeterministicExpectedOutput::Lines {
+            text: "1\n\n\n".into()
+        }
+        .accepts("1"));
+
+        assert!(!DeterministicExpectedOutput::Lines { text: "1\n".into() }.accepts("1\n\n\n"));
+
+        assert!(DeterministicExpectedOutput::Lines {
+            text: "1 2\n3\n".into()
+        }
+        .accepts("1 2 \n3\t\n\n"));
+
+        assert!(!DeterministicExpectedOutput::Lines {
+            text: "1 2\n3\n".into()
+        }
+        .accepts("1 2\n\n3\n"));
+
+        assert!(!DeterministicExpectedOutput::Lines {
+            text: "1 2\n".into()
+        }
+        .accepts(" 1 2\n"));
+
+        assert!(DeterministicExpectedOutput::Unordered {
+            text: "1 2\n1 3\n2 3\n".into()
+        }
+        .accepts("2 3\n1 2 \n1 3\n\n"));
+
+        assert!(!DeterministicExpectedOutput::Unordered {
+            text: "1 2\n1 3\n".into()
+        }
+        .accepts("1 2\n1 2\n"));
+
+        assert!(!DeterministicExpectedOutput::Unordered {
+            text: "1 2\n1 3\n".into()
+        }
+        .accepts("1 3\n1 2\n1 2\n"));
+
+        assert!(!DeterministicExpectedOutput::Unordered {
+            text: "1 2\n".into()
+        }
+        .accepts("2 1\n"));
+
         assert!(DeterministicExpectedOutput::Float {
             text: "10000.0\n".into(),
             relative_error: Some(PositiveFinite(0.01)),
@@ -850,5 +1670,55 @@ extend: []
             absolute_error: None,
         }
         .accepts("0\n"));
+
+        assert!(DeterministicExpectedOutput::Float {
+            text: "1000\n".into(),
+            relative_error: Some(PositiveFinite(0.001)),
+            absolute_error: None,
+        }
+        .accepts("999\n"));
+
+        assert!(!DeterministicExpectedOutput::Float {
+            text: "0\n".into(),
+            relative_error: Some(PositiveFinite(0.001)),
+            absolute_error: None,
+        }
+        .accepts("0.000001\n"));
+
+        assert!(DeterministicExpectedOutput::Float {
+            text: "Yes 1.0\n".into(),
+            relative_error: None,
+            absolute_error: Some(PositiveFinite(1e-6)),
+        }
+        .accepts("Yes 1.0000001\n"));
+
+        assert!(!DeterministicExpectedOutput::Float {
+            text: "Yes 1.0\n".into(),
+            relative_error: None,
+            absolute_error: Some(PositiveFinite(1e-6)),
+        }
+        .accepts("yes 1.0\n"));
+    }
+
+    #[test]
+    fn extra_output_accepts() {
+        assert!(ExtraOutput::Prefix.accepts("1\n2\n", "1\n2\ndebug\n"));
+        assert!(ExtraOutput::Prefix.accepts("1\n2\n", "1 \n2\n"));
+        assert!(!ExtraOutput::Prefix.accepts("1\n2\n", "debug\n1\n2\n"));
+        assert!(!ExtraOutput::Prefix.accepts("1\n2\n", "1\n"));
+
+        assert!(ExtraOutput::Subsequence.accepts("1\n2\n", "debug\n1\ndebug\n2\n"));
+        assert!(ExtraOutput::Subsequence.accepts("", "debug\n"));
+        assert!(!ExtraOutput::Subsequence.accepts("1\n2\n", "2\n1\n"));
+        assert!(!ExtraOutput::Subsequence.accepts("1\n1\n", "1\n"));
+    }
+
+    #[test]
+    fn literal_dir_prefix() {
+        assert_eq!("in", super::literal_dir_prefix("/in/*.txt"));
+        assert_eq!("in", super::literal_dir_prefix("/in/**/*.txt"));
+        assert_eq!("test/in", super::literal_dir_prefix("/test/in/*"));
+        assert_eq!("", super::literal_dir_prefix("/*.in"));
+        assert_eq!("", super::literal_dir_prefix("*/in/*.txt"));
     }
 }
@@ -6,14 +6,15 @@ use crate::{
     web::{
         AnsiColored, CaseConverted, CookieStorage, Exec, Login, LoginOutcome, LowerCase,
         Participate, ParticipateOutcome, Platform, ProblemInContest, ProblemsInContest,
-        ResponseExt as _, RetrieveFullTestCases, RetrieveLanguages, RetrieveLanguagesOutcome,
-        RetrieveSubmissionSummaries, RetrieveTestCases, RetrieveTestCasesOutcome,
-        RetrieveTestCasesOutcomeProblem, RetrieveTestCasesOutcomeProblemContest,
-        RetrieveTestCasesOutcomeProblemTextFiles, Session, SessionMut, Shell, Submit,
-        SubmitOutcome, WatchSubmissions,
+        ResponseExt as _, RetrieveContests, RetrieveContestsOutcome,
+        RetrieveContestsOutcomeContest, RetrieveFullTestCases, RetrieveLanguages,
+        RetrieveLanguagesOutcome, RetrieveSubmissionSummaries, RetrieveTestCases,
+        RetrieveTestCasesOutcome, RetrieveTestCasesOutcomeProblem,
+        RetrieveTestCasesOutcomeProblemContest, RetrieveTestCasesOutcomeProblemTextFiles, Session,
+        SessionMut, Shell, Submit, SubmitOutcome, WatchSubmissions,
     },
 };
-use anyhow::{anyhow, bail, Context as _};
+use anyhow::{anyhow, bail, ensure, Context as _};
 use chrono::{DateTime, FixedOffset, Local, Utc};
 use easy_ext::ext;
 use either::Either;
@@ -28,7 +29,7 @@ use scraper::{ElementRef, Html, Selector};
 use serde::{Deserialize, Serialize, Serializer};
 use serde_json::json;
 use std::{
-    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     convert::Infallible,
     fmt,
     hash::Hash,
@@ -38,7 +39,7 @@ use std::{
     ops::Deref,
     path::Path,
     str::FromStr,
-    time::Duration,
+    time::{Duration, Instant},
 };
 use termcolor::{Color, ColorSpec, WriteColor};
 use tokio::runtime::Runtime;
@@ -58,6 +59,20 @@ pub fn contest_id_from_url(url: &Url) -> anyhow::Result<String> {
         .with_context(|| "Could not extract contest ID of the problem")
 }
 
+/// Extracts the sample test cases from an HTML of `/contests/{contest}/tasks_print`.
+pub fn extract_samples(html: &str) -> Vec<anyhow::Result<AtcoderProblemSamples>> {
+    Html::parse_document(html).extract_samples()
+}
+
+/// Sample test cases of a problem in `/contests/{contest}/tasks_print`.
+#[derive(Debug)]
+pub struct AtcoderProblemSamples {
+    pub index: String,
+    pub display_name: String,
+    /// `Err` if the title was found but the test suite could not be extracted.
+    pub test_suite: anyhow::Result<TestSuite>,
+}
+
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub enum Atcoder<'closures> {
     Infallible(Infallible, PhantomData<fn() -> &'closures ()>),
@@ -77,6 +92,7 @@ impl<'closures> Platform for Atcoder<'closures> {
     type LoginCredentials = AtcoderLoginCredentials<'closures>;
     type ParticipateTarget = AtcoderParticipateTarget;
     type ParticipateCredentials = AtcoderParticipateCredentials<'closures>;
+    type RetrieveContestsTarget = AtcoderRetrieveContestsTarget;
     type RetrieveLanguagesTarget = AtcoderRetrieveLanguagesTarget;
     type RetrieveLanguagesCredentials = AtcoderRetrieveLanguagesCredentials<'closures>;
     type RetrieveTestCasesTargets = ProblemsInContest;
@@ -102,10 +118,22 @@ impl<S: Shell> Exec<Login<Self, S>> for Atcoder<'_> {
                 },
             cookie_storage,
             timeout,
+            connect_timeout,
+            min_interval,
+            proxy,
+            local_address,
             shell,
         } = args;
 
-        let mut sess = Session::new(timeout, Some(cookie_storage), shell)?;
+        let mut sess = Session::new(
+            timeout,
+            connect_timeout,
+            min_interval,
+            proxy.as_ref(),
+            local_address,
+            Some(cookie_storage),
+            shell,
+        )?;
 
         if check_logged_in(&mut sess)? {
             Ok(LoginOutcome::AlreadyLoggedIn)
@@ -128,15 +156,67 @@ impl<S: Shell> Exec<Participate<Self, S>> for Atcoder<'_> {
                 },
             cookie_storage,
             timeout,
+            connect_timeout,
+            min_interval,
+            proxy,
+            local_address,
             shell,
         } = args;
 
         let contest = CaseConverted::new(contest);
-        let sess = Session::new(timeout, Some(cookie_storage), shell)?;
+        let sess = Session::new(
+            timeout,
+            connect_timeout,
+            min_interval,
+            proxy.as_ref(),
+            local_address,
+            Some(cookie_storage),
+            shell,
+        )?;
         participate(sess, username_and_password, &contest, true)
     }
 }
 
+impl<S: Shell> Exec<RetrieveContests<Self, S>> for Atcoder<'_> {
+    type Output = RetrieveContestsOutcome;
+
+    fn exec(args: RetrieveContests<Self, S>) -> anyhow::Result<RetrieveContestsOutcome> {
+        let RetrieveContests {
+            target: AtcoderRetrieveContestsTarget { page },
+            timeout,
+            connect_timeout,
+            min_interval,
+            proxy,
+            local_address,
+            shell,
+        } = args;
+
+        let mut sess = Session::new(
+            timeout,
+            connect_timeout,
+            min_interval,
+            proxy.as_ref(),
+            local_address,
+            None,
+            shell,
+        )?;
+
+        let mut url = url!("/contests/archive");
+        url.query_pairs_mut().append_pair("page", &page.to_string());
+
+        let contests = extract_archived_contests(
+            &sess
+                .get(url)
+                .colorize_status_code(&[200], (), ..)
+                .send()?
+                .ensure_status(&[200])?
+                .html()?,
+        )?;
+
+        Ok(RetrieveContestsOutcome { contests })
+    }
+}
+
 impl<S: Shell> Exec<RetrieveLanguages<Self, S>> for Atcoder<'_> {
     type Output = RetrieveLanguagesOutcome;
 
@@ -149,6 +229,10 @@ impl<S: Shell> Exec<RetrieveLanguages<Self, S>> for Atcoder<'_> {
                 },
             cookie_storage,
             timeout,
+            connect_timeout,
+            min_interval,
+            proxy,
+            local_address,
             shell,
         } = args;
 
@@ -161,7 +245,15 @@ impl<S: Shell> Exec<RetrieveLanguages<Self, S>> for Atcoder<'_> {
             (CaseConverted::<LowerCase>::new("practice"), None)
         };
 
-        let mut sess = Session::new(timeout, Some(cookie_storage), shell)?;
+        let mut sess = Session::new(
+            timeout,
+            connect_timeout,
+            min_interval,
+            proxy.as_ref(),
+            local_address,
+            Some(cookie_storage),
+            shell,
+        )?;
 
         if !check_logged_in(&mut sess)? {
             login(&mut sess, username_and_password)?;
@@ -201,12 +293,25 @@ impl<S: Shell> Exec<RetrieveTestCases<Self, S>> for Atcoder<'_> {
                     username_and_password,
                 },
             full,
+            keep_going: _,
             cookie_storage,
             timeout,
+            connect_timeout,
+            min_interval,
+            proxy,
+            local_address,
             shell,
         } = args;
 
-        let mut sess = Session::new(timeout, Some(cookie_storage), shell)?;
+        let mut sess = Session::new(
+            timeout,
+            connect_timeout,
+            min_interval,
+            proxy.as_ref(),
+            local_address,
+            Some(cookie_storage),
+            shell,
+        )?;
 
         let mut outcome = retrieve_sample_test_cases(&mut sess, username_and_password, &targets)?;
 
@@ -477,12 +582,24 @@ impl<S: Shell> Exec<RetrieveSubmissionSummaries<Self, S>> for Atcoder<'_> {
                 },
             cookie_storage,
             timeout,
+            connect_timeout,
+            min_interval,
+            proxy,
+            local_address,
             shell,
         } = args;
 
         let contest = CaseConverted::<LowerCase>::new(contest);
 
-        let mut sess = Session::new(timeout, Some(cookie_storage), shell)?;
+        let mut sess = Session::new(
+            timeout,
+            connect_timeout,
+            min_interval,
+            proxy.as_ref(),
+            local_address,
+            Some(cookie_storage),
+            shell,
+        )?;
 
         let (mut summaries, num_pages) =
             retrieve_submission_summaries(&mut sess, &contest, 1, username_and_password)?;
@@ -511,12 +628,26 @@ impl<S: Shell> Exec<Submit<Self, S>> for Atcoder<'_> {
             language_id,
             code,
             watch_submission,
+            watch_timeout,
+            dry_run,
             cookie_storage,
             timeout,
+            connect_timeout,
+            min_interval,
+            proxy,
+            local_address,
             shell,
         } = args;
 
-        let mut sess = Session::new(timeout, Some(cookie_storage), shell)?;
+        let mut sess = Session::new(
+            timeout,
+            connect_timeout,
+            min_interval,
+            proxy.as_ref(),
+            local_address,
+            Some(cookie_storage),
+            shell,
+        )?;
 
         let (contest, url) = match target {
             ProblemInContest::Index { contest, problem } => {
@@ -552,8 +683,20 @@ impl<S: Shell> Exec<Submit<Self, S>> for Atcoder<'_> {
             .html()?
             .extract_csrf_token()?;
 
+        let submit_url = url!("/contests/{}/submit", contest);
+        let submissions_url = url!("/contests/{}/submissions/me", contest);
+
+        if dry_run {
+            return Ok(SubmitOutcome {
+                problem_screen_name: Some(problem_screen_name),
+                submit_url,
+                submission_url: None,
+                submissions_url,
+            });
+        }
+
         let res = sess
-            .post(url!("/contests/{}/submit", contest))
+            .post(submit_url.clone())
             .form(&hashmap! {
                 "data.TaskScreenName" => &*problem_screen_name,
                 "data.LanguageId" => language_id.as_ref(),
@@ -575,12 +718,13 @@ impl<S: Shell> Exec<Submit<Self, S>> for Atcoder<'_> {
 
                 let outcome = SubmitOutcome {
                     problem_screen_name: Some(problem_screen_name),
-                    submission_url: submission_summaries[0].detail.clone(),
-                    submissions_url: url!("/contests/{}/submissions/me", contest),
+                    submit_url,
+                    submission_url: Some(submission_summaries[0].detail.clone()),
+                    submissions_url,
                 };
 
                 if watch_submission {
-                    watch_submissions(sess, &contest, &submission_summaries)?;
+                    watch_submissions(sess, &contest, &submission_summaries, watch_timeout)?;
                 }
 
                 Ok(outcome)
@@ -606,12 +750,24 @@ impl<S: Shell> Exec<WatchSubmissions<Self, S>> for Atcoder<'_> {
                 },
             cookie_storage,
             timeout,
+            connect_timeout,
+            min_interval,
+            proxy,
+            local_address,
             mut shell,
         } = args;
 
         let contest = CaseConverted::<LowerCase>::new(contest);
 
-        let mut sess = Session::new(timeout, Some(cookie_storage), &mut shell)?;
+        let mut sess = Session::new(
+            timeout,
+            connect_timeout,
+            min_interval,
+            proxy.as_ref(),
+            local_address,
+            Some(cookie_storage),
+            &mut shell,
+        )?;
 
         let (summaries, _) =
             retrieve_submission_summaries(&mut sess, &contest, 1, username_and_password)?;
@@ -621,7 +777,7 @@ impl<S: Shell> Exec<WatchSubmissions<Self, S>> for Atcoder<'_> {
         });
 
         if any_incomplete {
-            watch_submissions(sess, &contest, &summaries)?;
+            watch_submissions(sess, &contest, &summaries, None)?;
         } else {
             let content = AnsiColored::new(|w| print_submissions(w, &summaries))?;
             shell.print_ansi(content.get())?;
@@ -643,6 +799,12 @@ pub struct AtcoderParticipateCredentials<'closures> {
     pub username_and_password: &'closures mut dyn FnMut() -> anyhow::Result<(String, String)>,
 }
 
+#[derive(Debug)]
+pub struct AtcoderRetrieveContestsTarget {
+    /// 1-based page number of the archive.
+    pub page: u32,
+}
+
 #[derive(Debug)]
 pub struct AtcoderRetrieveLanguagesTarget {
     pub contest_and_problem: Option<(String, String)>,
@@ -680,6 +842,51 @@ impl AtcoderRetrieveSubmissionSummariesOutcome {
     pub fn to_json(&self) -> String {
         serde_json::to_string(self).expect("should not fail")
     }
+
+    /// Returns whether any submission to the task of `index` (e.g. "A") is accepted.
+    ///
+    /// The index is the prefix of the display name of the task (e.g. "A - Welcome to AtCoder").
+    pub fn is_accepted(&self, index: &str) -> bool {
+        self.summaries.iter().any(|summary| {
+            summary.status == Verdict::Ac
+                && summary
+                    .task
+                    .display_name
+                    .split(" - ")
+                    .next()
+                    .map_or(false, |i| i.eq_ignore_ascii_case(index))
+        })
+    }
+
+    /// Keeps only the latest submission not after `at` for each task.
+    pub fn retain_latest_at(&mut self, at: DateTime<FixedOffset>) -> anyhow::Result<()> {
+        let mut latest = IndexMap::<_, SubmissionSummary>::new();
+
+        for summary in mem::take(&mut self.summaries) {
+            if summary.submission_time > at {
+                continue;
+            }
+            match latest.entry(summary.task.url.clone()) {
+                indexmap::map::Entry::Occupied(mut entry) => {
+                    if entry.get().submission_time < summary.submission_time {
+                        entry.insert(summary);
+                    }
+                }
+                indexmap::map::Entry::Vacant(entry) => {
+                    entry.insert(summary);
+                }
+            }
+        }
+
+        ensure!(
+            !latest.is_empty(),
+            "No submissions found at or before {}",
+            at.to_rfc3339(),
+        );
+
+        self.summaries = latest.into_iter().map(|(_, summary)| summary).collect();
+        Ok(())
+    }
 }
 
 #[derive(Debug)]
@@ -712,14 +919,14 @@ fn retrieve_sample_test_cases(
 
             let only = &mut problems
                 .as_ref()
-                .map(|ps| ps.iter().map(|p| p.to_lowercase()).collect::<BTreeSet<_>>());
+                .map(|ps| super::normalize_problem_indexes(ps.iter().map(String::as_str)));
 
             let indexes_and_urls = html
                 .extract_task_indexes_and_urls()?
                 .into_iter()
                 .filter(|(index, _)| {
                     if let Some(only) = only {
-                        only.remove(&index.to_lowercase())
+                        only.remove(&super::normalize_problem_index(index))
                     } else {
                         true
                     }
@@ -772,7 +979,7 @@ fn retrieve_sample_test_cases(
         }
     };
 
-    let mut outcome = RetrieveTestCasesOutcome { problems: vec![] };
+    let mut outcome = RetrieveTestCasesOutcome::new();
 
     for (contest, (contest_display_name, mut indexes_and_urls)) in problems {
         let test_suites = sess
@@ -800,7 +1007,11 @@ fn retrieve_sample_test_cases(
 
         for result in test_suites {
             match result {
-                Ok((index, display_name, test_suite)) => {
+                Ok(AtcoderProblemSamples {
+                    index,
+                    display_name,
+                    test_suite,
+                }) => {
                     if let Some(url) = indexes_and_urls.shift_remove(&*index) {
                         let screen_name = url
                             .path_segments()
@@ -815,6 +1026,7 @@ fn retrieve_sample_test_cases(
 
                                 TestSuite::Batch(BatchTestSuite {
                                     timelimit: None,
+                                    memorylimit: None,
                                     r#match: Match::Lines,
                                     cases: vec![],
                                     extend: vec![],
@@ -853,13 +1065,15 @@ fn login(
     while {
         let (username, password) = username_and_password()?;
 
-        let csrf_token = sess
+        let html = sess
             .get(url!("/login"))
             .colorize_status_code(&[200], (), ..)
             .send()?
             .ensure_status(&[200])?
-            .html()?
-            .extract_csrf_token()?;
+            .html()?;
+
+        ensure_login_page(&html)?;
+        let csrf_token = html.extract_csrf_token()?;
 
         let payload = hashmap!(
             "csrf_token" => csrf_token,
@@ -879,6 +1093,55 @@ fn login(
     Ok(())
 }
 
+/// Fails with the title of the page if `/login` is not the expected login form, so that an error
+/// is not mistaken for wrong credentials.
+fn ensure_login_page(html: &Html) -> anyhow::Result<()> {
+    let title = html.extract_title().map(str::trim).unwrap_or("(no title)");
+
+    let has = |selector: &'static Selector| html.select(selector).next().is_some();
+
+    if has(static_selector!(
+        ".cf-turnstile, .g-recaptcha, [data-sitekey]"
+    )) {
+        bail!(
+            "The login page ({:?}) requires a CAPTCHA. Log in with a browser and import the \
+             cookies instead",
+            title,
+        );
+    }
+
+    if !(has(static_selector!("[name=\"username\"]"))
+        && has(static_selector!("[name=\"password\"]"))
+        && has(static_selector!("[name=\"csrf_token\"]")))
+    {
+        bail!(
+            "Unexpected login page: {:?}. AtCoder may be under maintenance, or the login form may \
+             have changed",
+            title,
+        );
+    }
+
+    Ok(())
+}
+
+/// Extracts the contests from a page of `/contests/archive`.
+fn extract_archived_contests(html: &Html) -> anyhow::Result<Vec<RetrieveContestsOutcomeContest>> {
+    html.select(static_selector!("#main-container table > tbody > tr"))
+        .map(|tr| {
+            let a = tr
+                .select(static_selector!("td > a[href^=\"/contests/\"]"))
+                .next()?;
+            let href = a.value().attr("href")?;
+            Some(RetrieveContestsOutcomeContest {
+                id: href.trim_start_matches("/contests/").to_owned(),
+                display_name: a.text().next()?.to_owned(),
+                url: BASE_URL.join(href).ok()?,
+            })
+        })
+        .collect::<Option<Vec<_>>>()
+        .with_context(|| "Could not extract the contests")
+}
+
 fn check_logged_in(mut sess: impl SessionMut) -> anyhow::Result<bool> {
     let status = sess
         .get(url!("/settings"))
@@ -1061,14 +1324,20 @@ fn print_submissions(mut wtr: impl WriteColor, summaries: &[SubmissionSummary])
     Ok(())
 }
 
+/// Watches the submissions until they are judged or `timeout` elapses.
+///
+/// On timeout, the last-seen status is left on the progress bars.
 fn watch_submissions(
     mut sess: impl SessionMut,
     contest: &CaseConverted<LowerCase>,
     summaries: &[SubmissionSummary],
+    timeout: Option<Duration>,
 ) -> anyhow::Result<()> {
     let rt = Runtime::new()?;
     let mut handles = vec![];
 
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+
     let mp = MultiProgress::with_draw_target(sess.shell().progress_draw_target());
 
     let task_display_max_width = summaries
@@ -1194,6 +1463,11 @@ fn watch_submissions(
                             }
                         });
 
+                        if matches!(deadline, Some(deadline) if Instant::now() >= deadline) {
+                            tokio::task::block_in_place(|| pb.abandon());
+                            break Ok(true);
+                        }
+
                         tokio::time::sleep(Duration::from_millis(interval)).await;
                     } else {
                         let (verdict, time_and_memory) = match &text[..] {
@@ -1212,7 +1486,7 @@ fn watch_submissions(
                         tokio::task::block_in_place(|| {
                             finish(&pb, &verdict, &exec_time, memory);
                         });
-                        break Result::<(), anyhow::Error>::Ok(());
+                        break Result::<_, anyhow::Error>::Ok(false);
                     }
                 }
             }));
@@ -1228,8 +1502,16 @@ fn watch_submissions(
 
     mp.join()?;
 
+    let mut gave_up = false;
     for handle in handles {
-        rt.block_on(handle)??;
+        gave_up |= rt.block_on(handle)??;
+    }
+
+    if let (true, Some(timeout)) = (gave_up, timeout) {
+        sess.shell().warn(format!(
+            "Gave up watching the submission after {}",
+            humantime::format_duration(timeout),
+        ))?;
     }
 
     return Ok(());
@@ -1513,12 +1795,16 @@ impl Html {
             let url = BASE_URL.join(a.value().attr("href")?).ok()?;
             Some((index, url))
         })
-        .collect::<Option<IndexMap<_, _>>>()
+        .collect::<Option<Vec<_>>>()
         .filter(|m| !m.is_empty())
         .with_context(|| "Could not extract task indexes and URLs")
+        .and_then(|indexes_and_urls| {
+            super::ensure_no_duplicated_indexes(indexes_and_urls.iter().map(|(s, _)| &**s))?;
+            Ok(indexes_and_urls.into_iter().collect())
+        })
     }
 
-    fn extract_samples(&self) -> Vec<anyhow::Result<(String, String, anyhow::Result<TestSuite>)>> {
+    fn extract_samples(&self) -> Vec<anyhow::Result<AtcoderProblemSamples>> {
         return self
             .select(static_selector!(
                 "#main-container > div.row div[class=\"col-sm-12\"]",
@@ -1544,7 +1830,7 @@ impl Html {
                     let timelimit = div
                         .select(static_selector!(":scope > p"))
                         .flat_map(|r| r.text())
-                        .flat_map(parse_timelimit)
+                        .flat_map(super::parse_timelimit)
                         .exactly_one()
                         .map_err(|_| "Could not extract the timelimit")?;
 
@@ -1561,6 +1847,7 @@ impl Html {
                     } else if let Samples::Batch(r#match, samples) = samples {
                         TestSuite::Batch(BatchTestSuite {
                             timelimit: Some(timelimit),
+                            memorylimit: None,
                             r#match,
                             cases: samples
                                 .into_iter()
@@ -1578,36 +1865,21 @@ impl Html {
                     } else {
                         TestSuite::Interactive(InteractiveTestSuite {
                             timelimit: Some(timelimit),
+                            tester: None,
+                            each_args: vec![],
                         })
                     })
                 })()
                 .map_err(|e| anyhow!("{}: {}", index, e));
 
-                Ok((index, display_name, test_suite))
+                Ok(AtcoderProblemSamples {
+                    index,
+                    display_name,
+                    test_suite,
+                })
             })
             .collect();
 
-        fn parse_timelimit(text: &str) -> Option<Duration> {
-            let caps =
-                static_regex!(r"\A\D*([0-9]{1,9})(\.[0-9]{1,3})?\s*(m)?sec.*\z").captures(text)?;
-            let (mut b, mut e) = (caps[1].parse::<u64>().unwrap(), 0);
-            if let Some(cap) = caps.get(2) {
-                let n = cap.as_str().len() as u32 - 1;
-                b *= 10u64.pow(n);
-                b += cap.as_str()[1..].parse::<u64>().ok()?;
-                e -= n as i32;
-            }
-            if caps.get(3).is_none() {
-                e += 3;
-            }
-            let timelimit = if e < 0 {
-                b / 10u64.pow(-e as u32)
-            } else {
-                b * 10u64.pow(e as u32)
-            };
-            Some(Duration::from_millis(timelimit))
-        }
-
         fn extract_samples(task_statement: ElementRef<'_>) -> Option<Samples> {
             // TODO:
             // - https://atcoder.jp/contests/arc019/tasks/arc019_4 (interactive)
@@ -1966,3 +2238,169 @@ impl Html {
         .with_context(|| "Could not parse the submissions page")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::testsuite::{BatchTestSuite, Match, PartialBatchTestCase, TestSuite};
+    use scraper::Html;
+    use std::time::Duration;
+
+    #[test]
+    fn extract_archived_contests() -> anyhow::Result<()> {
+        let html = Html::parse_document(
+            r#"<!DOCTYPE html>
+<html>
+<body>
+<div id="main-container">
+<table>
+<thead><tr><th>Start Time</th><th>Contest Name</th><th>Duration</th></tr></thead>
+<tbody>
+<tr>
+<td><a href="http://www.timeanddate.com/"><time>2018-06-16 21:00:00+0900</time></a></td>
+<td><span>Ⓐ</span> <a href="/contests/abc100">AtCoder Beginner Contest 100</a></td>
+<td>01:40</td>
+</tr>
+<tr>
+<td><a href="http://www.timeanddate.com/"><time>2018-06-16 21:00:00+0900</time></a></td>
+<td><span>Ⓐ</span> <a href="/contests/arc099">AtCoder Regular Contest 099</a></td>
+<td>01:40</td>
+</tr>
+</tbody>
+</table>
+</div>
+</body>
+</html>
+"#,
+        );
+
+        let contests = super::extract_archived_contests(&html)?;
+        assert_eq!(
+            [
+                (
+                    "abc100",
+                    "AtCoder Beginner Contest 100",
+                    "https://atcoder.jp/contests/abc100",
+                ),
+                (
+                    "arc099",
+                    "AtCoder Regular Contest 099",
+                    "https://atcoder.jp/contests/arc099",
+                ),
+            ],
+            *contests
+                .iter()
+                .map(|c| (&*c.id, &*c.display_name, c.url.as_str()))
+                .collect::<Vec<_>>(),
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_login_page() {
+        let html = Html::parse_document(
+            r#"<!DOCTYPE html>
+<html>
+<head><title>Login - AtCoder</title></head>
+<body>
+<form action="" method="POST">
+<input type="hidden" name="csrf_token" value="token">
+<input type="text" name="username">
+<input type="password" name="password">
+</form>
+</body>
+</html>
+"#,
+        );
+        assert!(super::ensure_login_page(&html).is_ok());
+
+        let html = Html::parse_document(
+            r#"<!DOCTYPE html>
+<html>
+<head><title>Under Maintenance - AtCoder</title></head>
+<body><p>AtCoder is currently under maintenance.</p></body>
+</html>
+"#,
+        );
+        assert_eq!(
+            "Unexpected login page: \"Under Maintenance - AtCoder\". AtCoder may be under \
+             maintenance, or the login form may have changed",
+            super::ensure_login_page(&html).unwrap_err().to_string(),
+        );
+    }
+
+    #[test]
+    fn extract_samples() {
+        let samples = super::extract_samples(
+            r#"<!DOCTYPE html>
+<html>
+<body>
+<div id="main-container">
+<div class="row">
+<div class="col-sm-12">
+<span class="h2">A - Welcome to AtCoder</span>
+<p>Time Limit: 2 sec / Memory Limit: 1024 MB</p>
+<div id="task-statement">
+<span class="lang">
+<span class="lang-en">
+<div class="part">
+<section>
+<h3>Sample Input 1</h3>
+<pre>1
+2 3
+</pre>
+</section>
+</div>
+<div class="part">
+<section>
+<h3>Sample Output 1</h3>
+<pre>6
+</pre>
+</section>
+</div>
+</span>
+</span>
+</div>
+</div>
+<div class="col-sm-12">
+<span class="h2">B - Broken</span>
+<p>Time Limit: 2 sec / Memory Limit: 1024 MB</p>
+</div>
+</div>
+</div>
+</body>
+</html>
+"#,
+        );
+
+        let samples = samples
+            .into_iter()
+            .collect::<anyhow::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(2, samples.len());
+
+        assert_eq!("A", samples[0].index);
+        assert_eq!("Welcome to AtCoder", samples[0].display_name);
+        assert_eq!(
+            TestSuite::Batch(BatchTestSuite {
+                timelimit: Some(Duration::from_secs(2)),
+                memorylimit: None,
+                r#match: Match::Lines,
+                cases: vec![PartialBatchTestCase {
+                    name: Some("sample1".to_owned()),
+                    r#in: "1\n2 3\n".into(),
+                    out: Some("6\n".into()),
+                    timelimit: None,
+                    r#match: None,
+                }],
+                extend: vec![],
+            }),
+            *samples[0].test_suite.as_ref().unwrap(),
+        );
+
+        assert_eq!("B", samples[1].index);
+        assert_eq!(
+            "B: Could not extract the sample cases",
+            samples[1].test_suite.as_ref().unwrap_err().to_string(),
+        );
+    }
+}
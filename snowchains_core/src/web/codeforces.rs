@@ -3,6 +3,7 @@ use crate::{
     web::{
         codeforces::api::SessionMutExt as _, CookieStorage, Exec, Login, LoginOutcome, Participate,
         ParticipateOutcome, Platform, ProblemInContest, ProblemsInContest, ResponseExt as _,
+        RetrieveContests, RetrieveContestsOutcome, RetrieveContestsOutcomeContest,
         RetrieveLanguages, RetrieveLanguagesOutcome, RetrieveTestCases, RetrieveTestCasesOutcome,
         RetrieveTestCasesOutcomeProblem, RetrieveTestCasesOutcomeProblemContest, Session,
         SessionMut, Shell, Submit, SubmitOutcome,
@@ -19,7 +20,6 @@ use std::{
     collections::{BTreeMap, BTreeSet, HashMap},
     convert::Infallible,
     marker::PhantomData,
-    time::Duration,
 };
 use url::Url;
 
@@ -40,6 +40,7 @@ impl<'closures> Platform for Codeforces<'closures> {
     type LoginCredentials = CodeforcesLoginCredentials<'closures>;
     type ParticipateTarget = CodeforcesParticipateTarget;
     type ParticipateCredentials = CodeforcesParticipateCredentials<'closures>;
+    type RetrieveContestsTarget = ();
     type RetrieveLanguagesTarget = CodeforcesRetrieveLanguagesTarget;
     type RetrieveLanguagesCredentials = CodeforcesRetrieveLanguagesCredentials<'closures>;
     type RetrieveTestCasesTargets = ProblemsInContest;
@@ -73,10 +74,22 @@ impl<S: Shell> Exec<Login<Self, S>> for Codeforces<'_> {
                 },
             cookie_storage,
             timeout,
+            connect_timeout,
+            min_interval,
+            proxy,
+            local_address,
             shell,
         } = args;
 
-        let sess = Session::new(timeout, Some(cookie_storage), shell)?;
+        let sess = Session::new(
+            timeout,
+            connect_timeout,
+            min_interval,
+            proxy.as_ref(),
+            local_address,
+            Some(cookie_storage),
+            shell,
+        )?;
         let (outcome, _) = login(sess, username_and_password)?;
         Ok(outcome)
     }
@@ -94,16 +107,66 @@ impl<S: Shell> Exec<Participate<Self, S>> for Codeforces<'_> {
                 },
             cookie_storage,
             timeout,
+            connect_timeout,
+            min_interval,
+            proxy,
+            local_address,
             shell,
         } = args;
 
         let contest = parse_contest_id(&contest)?;
-        let sess = Session::new(timeout, Some(cookie_storage), shell)?;
+        let sess = Session::new(
+            timeout,
+            connect_timeout,
+            min_interval,
+            proxy.as_ref(),
+            local_address,
+            Some(cookie_storage),
+            shell,
+        )?;
         let (outcome, _, _) = participate(sess, username_and_password, contest)?;
         Ok(outcome)
     }
 }
 
+impl<S: Shell> Exec<RetrieveContests<Self, S>> for Codeforces<'_> {
+    type Output = RetrieveContestsOutcome;
+
+    fn exec(args: RetrieveContests<Self, S>) -> anyhow::Result<RetrieveContestsOutcome> {
+        let RetrieveContests {
+            target: (),
+            timeout,
+            connect_timeout,
+            min_interval,
+            proxy,
+            local_address,
+            shell,
+        } = args;
+
+        let contests = Session::new(
+            timeout,
+            connect_timeout,
+            min_interval,
+            proxy.as_ref(),
+            local_address,
+            None,
+            shell,
+        )?
+        .api_contest_list(false)?
+        .into_iter()
+        .map(
+            |api::Contest { id, name, .. }| RetrieveContestsOutcomeContest {
+                id: id.to_string(),
+                display_name: name,
+                url: url!("/contest/{}", id),
+            },
+        )
+        .collect();
+
+        Ok(RetrieveContestsOutcome { contests })
+    }
+}
+
 impl<S: Shell> Exec<RetrieveLanguages<Self, S>> for Codeforces<'_> {
     type Output = RetrieveLanguagesOutcome;
 
@@ -116,12 +179,24 @@ impl<S: Shell> Exec<RetrieveLanguages<Self, S>> for Codeforces<'_> {
                 },
             cookie_storage,
             timeout,
+            connect_timeout,
+            min_interval,
+            proxy,
+            local_address,
             shell,
         } = args;
 
         let contest = parse_contest_id(&contest)?;
 
-        let mut sess = Session::new(timeout, Some(cookie_storage), shell)?;
+        let mut sess = Session::new(
+            timeout,
+            connect_timeout,
+            min_interval,
+            proxy.as_ref(),
+            local_address,
+            Some(cookie_storage),
+            shell,
+        )?;
 
         participate(&mut sess, username_and_password, contest)?;
 
@@ -148,8 +223,13 @@ impl<S: Shell> Exec<RetrieveTestCases<Self, S>> for Codeforces<'_> {
                     mut username_and_password,
                 },
             full: _,
+            keep_going,
             cookie_storage,
             timeout,
+            connect_timeout,
+            min_interval,
+            proxy,
+            local_address,
             shell,
         } = args;
 
@@ -167,18 +247,22 @@ impl<S: Shell> Exec<RetrieveTestCases<Self, S>> for Codeforces<'_> {
             }
         };
 
-        let mut sess = Session::new(timeout, Some(cookie_storage), shell)?;
-        let mut outcome = RetrieveTestCasesOutcome { problems: vec![] };
+        let mut sess = Session::new(
+            timeout,
+            connect_timeout,
+            min_interval,
+            proxy.as_ref(),
+            local_address,
+            Some(cookie_storage),
+            shell,
+        )?;
+        let mut outcome = RetrieveTestCasesOutcome::new();
 
         for (contest, problems) in targets {
             let (_, contest_name, _) = participate(&mut sess, &mut username_and_password, contest)?;
 
-            let mut problem_indices = problems.map(|ps| {
-                ps.iter()
-                    .map(AsRef::as_ref)
-                    .map(str::to_uppercase)
-                    .collect::<BTreeSet<_>>()
-            });
+            let mut problem_indices =
+                problems.map(|ps| super::normalize_problem_indexes(ps.iter().map(AsRef::as_ref)));
 
             let contest = &RetrieveTestCasesOutcomeProblemContest {
                 id: contest.to_string(),
@@ -187,41 +271,43 @@ impl<S: Shell> Exec<RetrieveTestCases<Self, S>> for Codeforces<'_> {
                 submissions_url: url!("/contest/{}/my", contest),
             };
 
-            outcome.problems.extend(
-                sess.get(url!("/contest/{}", contest.id))
-                    .colorize_status_code(&[200], (), ..)
-                    .send()?
-                    .ensure_status(&[200])?
-                    .html()?
-                    .extract_problems()?
-                    .into_iter()
-                    .map(|(index, display_name, url)| {
-                        if let Some(problem_indices) = &mut problem_indices {
-                            if !problem_indices.remove(&index) {
-                                return Ok(None);
-                            }
-                        }
-
-                        let test_suite = sess
-                            .get(url.clone())
-                            .colorize_status_code(&[200], (), ..)
-                            .send()?
-                            .html()?
-                            .extract_test_cases()?;
-
-                        Ok(Some(RetrieveTestCasesOutcomeProblem {
-                            contest: Some(contest.clone()),
-                            index,
-                            url,
-                            screen_name: None,
-                            display_name,
-                            test_suite,
-                            text_files: indexmap!(),
-                        }))
-                    })
-                    .flat_map(Result::transpose)
-                    .collect::<anyhow::Result<Vec<_>>>()?,
-            );
+            let problems = sess
+                .get(url!("/contest/{}", contest.id))
+                .colorize_status_code(&[200], (), ..)
+                .send()?
+                .ensure_status(&[200])?
+                .html()?
+                .extract_problems()?;
+
+            for (index, display_name, url) in problems {
+                if let Some(problem_indices) = &mut problem_indices {
+                    if !problem_indices.remove(&super::normalize_problem_index(&index)) {
+                        continue;
+                    }
+                }
+
+                let test_suite = (|| -> anyhow::Result<_> {
+                    sess.get(url.clone())
+                        .colorize_status_code(&[200], (), ..)
+                        .send()?
+                        .html()?
+                        .extract_test_cases()
+                })();
+
+                outcome.push_or_record_failure(
+                    || index.clone(),
+                    test_suite.map(|test_suite| RetrieveTestCasesOutcomeProblem {
+                        contest: Some(contest.clone()),
+                        index: index.clone(),
+                        url,
+                        screen_name: None,
+                        display_name,
+                        test_suite,
+                        text_files: indexmap!(),
+                    }),
+                    keep_going,
+                )?;
+            }
 
             if let Some(problem_indices) = problem_indices {
                 if !problem_indices.is_empty() {
@@ -249,12 +335,18 @@ impl<S: Shell> Exec<Submit<Self, S>> for Codeforces<'_> {
             language_id,
             code,
             watch_submission,
+            watch_timeout: _,
+            dry_run,
             cookie_storage,
             timeout,
+            connect_timeout,
+            min_interval,
+            proxy,
+            local_address,
             mut shell,
         } = args;
 
-        if watch_submission {
+        if watch_submission && !dry_run {
             shell.warn("`watch_submissions` in Codeforces is not yet supported")?;
         }
 
@@ -263,7 +355,15 @@ impl<S: Shell> Exec<Submit<Self, S>> for Codeforces<'_> {
             ProblemInContest::Url { url } => parse_problem_url(&url)?,
         };
 
-        let mut sess = Session::new(timeout, Some(cookie_storage), shell)?;
+        let mut sess = Session::new(
+            timeout,
+            connect_timeout,
+            min_interval,
+            proxy.as_ref(),
+            local_address,
+            Some(cookie_storage),
+            shell,
+        )?;
 
         let (_, _, handle) = participate(&mut sess, username_and_password, contest_id)?;
 
@@ -292,8 +392,17 @@ impl<S: Shell> Exec<Submit<Self, S>> for Codeforces<'_> {
         payload.insert("programTypeId".to_owned(), language_id);
         payload.insert("source".to_owned(), code);
 
+        if dry_run {
+            return Ok(SubmitOutcome {
+                problem_screen_name: None,
+                submit_url: url,
+                submission_url: None,
+                submissions_url: url!("/contest/{}/my", contest_id),
+            });
+        }
+
         let res = sess
-            .post(url)
+            .post(url.clone())
             .form(&payload)
             .colorize_status_code(&[302], (), ..)
             .send()?
@@ -315,7 +424,8 @@ impl<S: Shell> Exec<Submit<Self, S>> for Codeforces<'_> {
 
             Ok(SubmitOutcome {
                 problem_screen_name: None,
-                submission_url,
+                submit_url: url,
+                submission_url: Some(submission_url),
                 submissions_url,
             })
         }
@@ -539,21 +649,17 @@ impl Html {
             .collect::<Option<Vec<_>>>()
             .filter(|ss| !ss.is_empty())
             .with_context(|| "Could not extract problem names")
+            .and_then(|problems| {
+                super::ensure_no_duplicated_indexes(problems.iter().map(|(s, _, _)| &**s))?;
+                Ok(problems)
+            })
     }
 
     fn extract_test_cases(&self) -> anyhow::Result<TestSuite> {
         let timelimit = self
             .select(static_selector!("#pageContent div.time-limit"))
             .flat_map(|r| r.text())
-            .flat_map(|text| {
-                let caps = lazy_regex!(r#"\A([0-9]{1,9})(\.[0-9])? seconds?\z"#).captures(text)?;
-                let secs = caps[1].parse::<u64>().unwrap();
-                let nanos = caps
-                    .get(2)
-                    .map(|s| 100_000_000 * u32::from(s.as_str().as_bytes()[1] - b'0'))
-                    .unwrap_or(0);
-                Some(Duration::new(secs, nanos))
-            })
+            .flat_map(super::parse_timelimit)
             .exactly_one()
             .ok()
             .with_context(|| "Could not extract the timelimit")?;
@@ -616,6 +722,7 @@ impl Html {
 
         return Ok(TestSuite::Batch(BatchTestSuite {
             timelimit: Some(timelimit),
+            memorylimit: None,
             r#match,
             cases,
             extend: vec![],
@@ -897,3 +1004,40 @@ mod api {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_problems() {
+        fn html(indexes: &[&str]) -> Html {
+            let rows = indexes
+                .iter()
+                .map(|index| {
+                    format!(
+                        r#"<tr><td class="id"><a href="/contest/1/problem/{index}">{index}</a></td><td><div><div><a href="/contest/1/problem/{index}">Problem {index}</a></div></div></td></tr>"#,
+                        index = index,
+                    )
+                })
+                .join("");
+
+            Html::parse_document(&format!(
+                r#"<html><body><table class="problems"><tbody><tr><th>#</th></tr>{}</tbody></table></body></html>"#,
+                rows,
+            ))
+        }
+
+        let problems = html(&["A", "B"]).extract_problems().unwrap();
+        assert_eq!(
+            ["A", "B"],
+            *problems
+                .iter()
+                .map(|(index, _, _)| &**index)
+                .collect::<Vec<_>>(),
+        );
+
+        let err = html(&["A", "B", "A"]).extract_problems().unwrap_err();
+        assert_eq!(r#"Found duplicated problem index: "A""#, err.to_string());
+    }
+}
@@ -0,0 +1,390 @@
+use crate::{
+    testsuite::{BatchTestSuite, Match, PartialBatchTestCase, TestSuite},
+    web::{
+        Exec, Platform, ResponseExt as _, RetrieveTestCases, RetrieveTestCasesOutcome,
+        RetrieveTestCasesOutcomeProblem, Session, SessionMut, Shell,
+    },
+};
+use anyhow::{anyhow, bail, ensure, Context as _};
+use indexmap::indexmap;
+use regex::Regex;
+use scraper::{Html, Selector};
+use serde::Deserialize;
+use std::convert::Infallible;
+use url::Url;
+
+/// A service that is not supported natively, scraped with a user-defined [`OtherScraper`].
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum Other {}
+
+impl Other {
+    pub fn exec<A>(args: A) -> anyhow::Result<<Self as Exec<A>>::Output>
+    where
+        Self: Exec<A>,
+    {
+        <Self as Exec<_>>::exec(args)
+    }
+}
+
+impl Platform for Other {
+    type CookieStorage = ();
+    type LoginCredentials = Infallible;
+    type ParticipateTarget = Infallible;
+    type ParticipateCredentials = Infallible;
+    type RetrieveContestsTarget = Infallible;
+    type RetrieveLanguagesTarget = Infallible;
+    type RetrieveLanguagesCredentials = Infallible;
+    type RetrieveTestCasesTargets = OtherRetrieveTestCasesTargets;
+    type RetrieveTestCasesCredentials = ();
+    type RetrieveFullTestCasesCredentials = Infallible;
+    type RetrieveSubmissionSummariesTarget = Infallible;
+    type RetrieveSubmissionSummariesCredentials = Infallible;
+    type WatchSubmissionsTarget = Infallible;
+    type WatchSubmissionsCredentials = Infallible;
+    type SubmitTarget = Infallible;
+    type SubmitCredentials = Infallible;
+}
+
+impl<S: Shell> Exec<RetrieveTestCases<Self, S>> for Other {
+    type Output = RetrieveTestCasesOutcome;
+
+    fn exec(args: RetrieveTestCases<Self, S>) -> anyhow::Result<RetrieveTestCasesOutcome> {
+        let RetrieveTestCases {
+            targets:
+                OtherRetrieveTestCasesTargets {
+                    scraper,
+                    contest,
+                    problems,
+                },
+            credentials: (),
+            full,
+            keep_going,
+            cookie_storage: (),
+            timeout,
+            connect_timeout,
+            min_interval,
+            proxy,
+            local_address,
+            shell,
+        } = args;
+
+        if let Some(full) = full {
+            match full.credentials {}
+        }
+
+        let mut sess = Session::new(
+            timeout,
+            connect_timeout,
+            min_interval,
+            proxy.as_ref(),
+            local_address,
+            None,
+            shell,
+        )?;
+
+        let mut outcome = RetrieveTestCasesOutcome::new();
+
+        for problem in problems {
+            let result = (|| -> anyhow::Result<_> {
+                let url = scraper.problem_url(contest.as_deref(), &problem)?;
+
+                let html = sess
+                    .get(url.clone())
+                    .colorize_status_code(&[200], (), ..)
+                    .send()?
+                    .ensure_status(&[200])?
+                    .html()?;
+
+                let test_suite = scraper
+                    .extract_samples(&html)
+                    .with_context(|| format!("Could not extract the samples from {}", url))?;
+
+                let display_name = html
+                    .select(static_selector!("title"))
+                    .next()
+                    .map(|title| title.text().collect::<String>().trim().to_owned())
+                    .filter(|title| !title.is_empty())
+                    .unwrap_or_else(|| problem.clone());
+
+                Ok(RetrieveTestCasesOutcomeProblem {
+                    contest: None,
+                    index: problem.clone(),
+                    url,
+                    screen_name: None,
+                    display_name,
+                    test_suite,
+                    text_files: indexmap!(),
+                })
+            })();
+
+            outcome.push_or_record_failure(|| problem, result, keep_going)?;
+        }
+
+        Ok(outcome)
+    }
+}
+
+pub struct OtherRetrieveTestCasesTargets {
+    pub scraper: OtherScraper,
+    pub contest: Option<String>,
+    pub problems: Vec<String>,
+}
+
+/// A recipe to scrape sample cases from problem pages.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OtherScraperConfig {
+    /// Base URL of the service.
+    pub base_url: Url,
+    /// Path or URL of a problem page, relative to `base_url`. `{contest}` and `{problem}` are
+    /// replaced.
+    pub problem_url: String,
+    /// CSS selector for the sample inputs.
+    pub sample_input: String,
+    /// CSS selector for the sample outputs.
+    pub sample_output: String,
+    /// How to find the timelimit.
+    #[serde(default)]
+    pub timelimit: Option<OtherScraperTimelimitConfig>,
+    /// How to compare outputs.
+    #[serde(default = "match_lines")]
+    pub r#match: Match,
+}
+
+fn match_lines() -> Match {
+    Match::Lines
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OtherScraperTimelimitConfig {
+    /// CSS selector for the element that contains the timelimit.
+    pub selector: String,
+    /// Regex for the timelimit in the text of the element. If it has a capture group, the first
+    /// one is used.
+    #[serde(default)]
+    pub regex: Option<String>,
+}
+
+/// Validated [`OtherScraperConfig`].
+#[derive(Debug, Clone)]
+pub struct OtherScraper {
+    base_url: Url,
+    problem_url: String,
+    sample_input: Selector,
+    sample_output: Selector,
+    timelimit: Option<(Selector, Option<Regex>)>,
+    r#match: Match,
+}
+
+impl OtherScraper {
+    pub fn new(config: OtherScraperConfig) -> anyhow::Result<Self> {
+        let OtherScraperConfig {
+            base_url,
+            problem_url,
+            sample_input,
+            sample_output,
+            timelimit,
+            r#match,
+        } = config;
+
+        ensure!(
+            problem_url.contains("{problem}"),
+            "`problem_url` must contain `{{problem}}`: {:?}",
+            problem_url,
+        );
+
+        for caps in static_regex!(r"\{([^{}]*)\}").captures_iter(&problem_url) {
+            if !["contest", "problem"].contains(&&caps[1]) {
+                bail!(
+                    "`problem_url`: unknown placeholder `{}` (expected `{{contest}}` or \
+                     `{{problem}}`)",
+                    &caps[0],
+                );
+            }
+        }
+
+        base_url
+            .join(
+                &problem_url
+                    .replace("{contest}", "_")
+                    .replace("{problem}", "_"),
+            )
+            .with_context(|| format!("`problem_url`: invalid URL: {:?}", problem_url))?;
+
+        let timelimit = timelimit
+            .map(
+                |OtherScraperTimelimitConfig { selector, regex }| -> anyhow::Result<_> {
+                    let selector = parse_selector("timelimit.selector", &selector)?;
+                    let regex = regex
+                        .map(|regex| {
+                            Regex::new(&regex).with_context(|| "`timelimit.regex`: invalid regex")
+                        })
+                        .transpose()?;
+                    Ok((selector, regex))
+                },
+            )
+            .transpose()?;
+
+        Ok(Self {
+            base_url,
+            problem_url,
+            sample_input: parse_selector("sample_input", &sample_input)?,
+            sample_output: parse_selector("sample_output", &sample_output)?,
+            timelimit,
+            r#match,
+        })
+    }
+
+    fn problem_url(&self, contest: Option<&str>, problem: &str) -> anyhow::Result<Url> {
+        let encode = |s: &str| {
+            percent_encoding::utf8_percent_encode(s, percent_encoding::NON_ALPHANUMERIC).to_string()
+        };
+
+        let mut url = self.problem_url.replace("{problem}", &encode(problem));
+
+        if url.contains("{contest}") {
+            let contest = contest.with_context(|| "`contest` is required for this service")?;
+            url = url.replace("{contest}", &encode(contest));
+        }
+
+        self.base_url.join(&url).map_err(Into::into)
+    }
+
+    fn extract_samples(&self, html: &Html) -> anyhow::Result<TestSuite> {
+        let texts = |selector| {
+            html.select(selector)
+                .map(|elem| {
+                    let mut text = elem.text().collect::<String>();
+                    if !text.is_empty() && !text.ends_with('\n') {
+                        text.push('\n');
+                    }
+                    text
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let inputs = texts(&self.sample_input);
+        let outputs = texts(&self.sample_output);
+
+        ensure!(!inputs.is_empty(), "No elements matched `sample_input`");
+        ensure!(
+            inputs.len() == outputs.len(),
+            "`sample_input` matched {} element(s) but `sample_output` matched {}",
+            inputs.len(),
+            outputs.len(),
+        );
+
+        let timelimit = self
+            .timelimit
+            .as_ref()
+            .map(|(selector, regex)| {
+                html.select(selector)
+                    .map(|elem| elem.text().collect::<String>())
+                    .find_map(|text| match regex {
+                        Some(regex) => regex.captures(&text).and_then(|caps| {
+                            let m = caps.get(1).or_else(|| caps.get(0))?;
+                            super::parse_timelimit(m.as_str())
+                        }),
+                        None => super::parse_timelimit(&text),
+                    })
+                    .ok_or_else(|| anyhow!("Could not find the timelimit"))
+            })
+            .transpose()?;
+
+        let cases = inputs
+            .into_iter()
+            .zip(outputs)
+            .enumerate()
+            .map(|(i, (input, output))| PartialBatchTestCase {
+                name: Some(format!("sample{}", i + 1)),
+                r#in: input.into(),
+                out: Some(output.into()),
+                timelimit: None,
+                r#match: None,
+            })
+            .collect();
+
+        Ok(TestSuite::Batch(BatchTestSuite {
+            timelimit,
+            memorylimit: None,
+            r#match: self.r#match.clone(),
+            cases,
+            extend: vec![],
+        }))
+    }
+}
+
+fn parse_selector(field: &str, selector: &str) -> anyhow::Result<Selector> {
+    Selector::parse(selector)
+        .map_err(|e| anyhow!("`{}`: invalid CSS selector {:?}: {:?}", field, selector, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OtherScraper, OtherScraperConfig, OtherScraperTimelimitConfig};
+    use crate::testsuite::{Match, TestSuite};
+    use scraper::Html;
+    use std::time::Duration;
+
+    fn config() -> OtherScraperConfig {
+        OtherScraperConfig {
+            base_url: "https://judge.example.com".parse().unwrap(),
+            problem_url: "/contests/{contest}/problems/{problem}".to_owned(),
+            sample_input: "pre.sample-input".to_owned(),
+            sample_output: "pre.sample-output".to_owned(),
+            timelimit: Some(OtherScraperTimelimitConfig {
+                selector: "#limits".to_owned(),
+                regex: Some(r"Time: (\S+)".to_owned()),
+            }),
+            r#match: Match::Lines,
+        }
+    }
+
+    #[test]
+    fn validate() {
+        assert!(OtherScraper::new(config()).is_ok());
+
+        let invalid = |f: fn(&mut OtherScraperConfig)| {
+            let mut config = config();
+            f(&mut config);
+            OtherScraper::new(config).is_err()
+        };
+
+        assert!(invalid(|c| c.problem_url = "/problems".to_owned()));
+        assert!(invalid(|c| c.problem_url = "/{task}/{problem}".to_owned()));
+        assert!(invalid(|c| c.sample_input = "pre[".to_owned()));
+        assert!(invalid(|c| c.sample_output = "".to_owned()));
+        assert!(invalid(
+            |c| c.timelimit.as_mut().unwrap().regex = Some("(".to_owned())
+        ));
+    }
+
+    #[test]
+    fn extract_samples() -> anyhow::Result<()> {
+        let scraper = OtherScraper::new(config())?;
+
+        assert_eq!(
+            "https://judge.example.com/contests/abc/problems/a%2Db",
+            scraper.problem_url(Some("abc"), "a-b")?.as_str(),
+        );
+        assert!(scraper.problem_url(None, "a").is_err());
+
+        let html = Html::parse_document(
+            r#"<p id="limits">Time: 2sec, Memory: 256MB</p>
+<pre class="sample-input">1 2</pre><pre class="sample-output">3
+</pre>
+<pre class="sample-input">3 4</pre><pre class="sample-output">7</pre>"#,
+        );
+
+        let test_suite = match scraper.extract_samples(&html)? {
+            TestSuite::Batch(test_suite) => test_suite,
+            _ => unreachable!(),
+        };
+
+        assert_eq!(Some(Duration::from_secs(2)), test_suite.timelimit);
+        assert_eq!(2, test_suite.cases.len());
+        assert_eq!("1 2\n", &*test_suite.cases[0].r#in);
+        assert_eq!(Some("3\n"), test_suite.cases[0].out.as_deref());
+        assert_eq!(Some("7\n"), test_suite.cases[1].out.as_deref());
+        Ok(())
+    }
+}
@@ -4,11 +4,12 @@ use crate::{
         TestSuite,
     },
     web::{
-        yukicoder::api::SessionMutExt as _, Exec, Platform, ResponseExt as _,
-        RetrieveFullTestCases, RetrieveLanguages, RetrieveLanguagesOutcome, RetrieveTestCases,
-        RetrieveTestCasesOutcome, RetrieveTestCasesOutcomeProblem,
-        RetrieveTestCasesOutcomeProblemContest, RetrieveTestCasesOutcomeProblemTextFiles, Session,
-        SessionMut, Shell, Submit, SubmitOutcome,
+        yukicoder::api::SessionMutExt as _, Exec, Platform, ResponseExt as _, RetrieveContests,
+        RetrieveContestsOutcome, RetrieveContestsOutcomeContest, RetrieveFullTestCases,
+        RetrieveLanguages, RetrieveLanguagesOutcome, RetrieveTestCases, RetrieveTestCasesOutcome,
+        RetrieveTestCasesOutcomeProblem, RetrieveTestCasesOutcomeProblemContest,
+        RetrieveTestCasesOutcomeProblemTextFiles, Session, SessionMut, Shell, Submit,
+        SubmitOutcome,
     },
 };
 use anyhow::{bail, Context as _};
@@ -18,7 +19,7 @@ use indexmap::indexmap;
 use itertools::Itertools as _;
 use once_cell::sync::Lazy;
 use scraper::{ElementRef, Html, Node};
-use std::{collections::BTreeSet, convert::Infallible, hash::Hash, time::Duration};
+use std::{collections::BTreeSet, convert::Infallible, hash::Hash};
 use url::Url;
 
 static BASE_URL: Lazy<Url> = lazy_url!("https://yukicoder.me");
@@ -40,6 +41,7 @@ impl Platform for Yukicoder {
     type LoginCredentials = Infallible;
     type ParticipateTarget = Infallible;
     type ParticipateCredentials = Infallible;
+    type RetrieveContestsTarget = ();
     type RetrieveLanguagesTarget = ();
     type RetrieveLanguagesCredentials = ();
     type RetrieveTestCasesTargets = YukicoderRetrieveTestCasesTargets;
@@ -53,6 +55,44 @@ impl Platform for Yukicoder {
     type SubmitCredentials = YukicoderSubmitCredentials;
 }
 
+impl<S: Shell> Exec<RetrieveContests<Self, S>> for Yukicoder {
+    type Output = RetrieveContestsOutcome;
+
+    fn exec(args: RetrieveContests<Self, S>) -> anyhow::Result<RetrieveContestsOutcome> {
+        let RetrieveContests {
+            target: (),
+            timeout,
+            connect_timeout,
+            min_interval,
+            proxy,
+            local_address,
+            shell,
+        } = args;
+
+        let contests = Session::new(
+            timeout,
+            connect_timeout,
+            min_interval,
+            proxy.as_ref(),
+            local_address,
+            None,
+            shell,
+        )?
+        .get_all_contests()?
+        .into_iter()
+        .map(
+            |api::Contest { id, name, .. }| RetrieveContestsOutcomeContest {
+                id: id.to_string(),
+                display_name: name,
+                url: url!("/contests/{}", id),
+            },
+        )
+        .collect();
+
+        Ok(RetrieveContestsOutcome { contests })
+    }
+}
+
 impl<S: Shell> Exec<RetrieveLanguages<Self, S>> for Yukicoder {
     type Output = RetrieveLanguagesOutcome;
 
@@ -62,14 +102,26 @@ impl<S: Shell> Exec<RetrieveLanguages<Self, S>> for Yukicoder {
             credentials: (),
             cookie_storage: (),
             timeout,
+            connect_timeout,
+            min_interval,
+            proxy,
+            local_address,
             shell,
         } = args;
 
-        let names_by_id = Session::new(timeout, None, shell)?
-            .get_available_language()?
-            .into_iter()
-            .map(|api::Language { id, name, ver }| (id, format!("{} ({})", name, ver)))
-            .collect();
+        let names_by_id = Session::new(
+            timeout,
+            connect_timeout,
+            min_interval,
+            proxy.as_ref(),
+            local_address,
+            None,
+            shell,
+        )?
+        .get_available_language()?
+        .into_iter()
+        .map(|api::Language { id, name, ver }| (id, format!("{} ({})", name, ver)))
+        .collect();
 
         Ok(RetrieveLanguagesOutcome { names_by_id })
     }
@@ -83,14 +135,27 @@ impl<S: Shell> Exec<RetrieveTestCases<Self, S>> for Yukicoder {
             targets,
             credentials: (),
             full,
+            keep_going,
             cookie_storage: (),
             timeout,
+            connect_timeout,
+            min_interval,
+            proxy,
+            local_address,
             shell,
         } = args;
 
-        let mut sess = Session::new(timeout, None, shell)?;
+        let mut sess = Session::new(
+            timeout,
+            connect_timeout,
+            min_interval,
+            proxy.as_ref(),
+            local_address,
+            None,
+            shell,
+        )?;
 
-        let mut outcome = retrieve_samples(&mut sess, targets)?;
+        let mut outcome = retrieve_samples(&mut sess, targets, keep_going)?;
 
         if let Some(RetrieveFullTestCases {
             credentials: YukicoderRetrieveFullTestCasesCredentials { api_key },
@@ -171,27 +236,39 @@ impl<S: Shell> Exec<Submit<Self, S>> for Yukicoder {
             language_id,
             code,
             watch_submission,
+            watch_timeout: _,
+            dry_run,
             cookie_storage: (),
             timeout,
+            connect_timeout,
+            min_interval,
+            proxy,
+            local_address,
             mut shell,
         } = args;
 
-        if watch_submission {
+        if watch_submission && !dry_run {
             shell.warn("`watch_submissions` in yukicoder is not yet supported")?;
         }
 
-        let mut sess = Session::new(timeout, None, shell)?;
+        let mut sess = Session::new(
+            timeout,
+            connect_timeout,
+            min_interval,
+            proxy.as_ref(),
+            local_address,
+            None,
+            shell,
+        )?;
 
-        let problem_id = match target.parse()? {
+        let problem_id = match target.parse() {
             Either::Left(url) => match parse_problem_url(&url)? {
                 Either::Left(problem_no) => sess.get_problem_by_problem_no(problem_no)?.problem_id,
                 Either::Right(problem_id) => problem_id,
             },
-            Either::Right((contest_id, problem_index)) => {
-                let problem_index = match *problem_index.to_ascii_uppercase().into_bytes() {
-                    [problem_index @ b'A'..=b'Z'] => problem_index,
-                    _ => bail!("problem indexes for yukicoder must be `[a-zA-Z]`"),
-                };
+            Either::Right((contest, problem_index)) => {
+                let problem_index = parse_problem_index(&problem_index)?;
+                let contest_id = resolve_contest_id(&mut sess, &contest)?;
 
                 let api::Contest {
                     problem_id_list, ..
@@ -212,6 +289,18 @@ impl<S: Shell> Exec<Submit<Self, S>> for Yukicoder {
             }
         };
 
+        let submit_url = api::submit_url(problem_id);
+        let submissions_url = url!("/problems/{}/submissions?my_submission=enabled", problem_id);
+
+        if dry_run {
+            return Ok(SubmitOutcome {
+                problem_screen_name: Some(problem_id.to_string()),
+                submit_url,
+                submission_url: None,
+                submissions_url,
+            });
+        }
+
         match sess.submit_problem_by_problem_id(
             &api_key,
             problem_id,
@@ -220,8 +309,9 @@ impl<S: Shell> Exec<Submit<Self, S>> for Yukicoder {
         )? {
             Ok(submission_id) => Ok(SubmitOutcome {
                 problem_screen_name: Some(problem_id.to_string()),
-                submission_url: url!("/submissions/{}", submission_id),
-                submissions_url: url!("/problems/{}/submissions?my_submission=enabled", problem_id),
+                submit_url,
+                submission_url: Some(url!("/submissions/{}", submission_id)),
+                submissions_url,
             }),
             Err((status_code, message)) => {
                 bail!("Submission rejected: ({}, {:?})", status_code, message);
@@ -260,12 +350,11 @@ impl YukicoderSubmitTarget {
         )
     }
 
-    fn parse(&self) -> anyhow::Result<Either<Url, (u64, String)>> {
+    fn parse(&self) -> Either<Url, (String, String)> {
         match self {
-            Self::Url(url) => Ok(Either::Left(url.clone())),
-            Self::Contest(contest_id, problem_index) => {
-                let contest_id = parse_contest_id(contest_id)?;
-                Ok(Either::Right((contest_id, problem_index.clone())))
+            Self::Url(url) => Either::Left(url.clone()),
+            Self::Contest(contest, problem_index) => {
+                Either::Right((contest.clone(), problem_index.clone()))
             }
         }
     }
@@ -285,13 +374,46 @@ fn parse_problem_no(s: &str) -> anyhow::Result<u64> {
     })
 }
 
-fn parse_contest_id(s: &str) -> anyhow::Result<u64> {
-    s.parse().with_context(|| {
-        format!(
-            "A contest ID for yukicoder must be unsigned integer: {:?}",
-            s,
-        )
-    })
+/// Resolves `s` as a contest ID, or as a contest name if it is not numeric.
+fn resolve_contest_id(sess: &mut impl SessionMut, s: &str) -> anyhow::Result<u64> {
+    if let Ok(contest_id) = s.parse() {
+        return Ok(contest_id);
+    }
+    find_contest_id(&sess.get_all_contests()?, s)
+}
+
+/// Finds the contest whose name is `name` case-insensitively, or the only contest whose name
+/// contains `name`.
+fn find_contest_id(contests: &[api::Contest], name: &str) -> anyhow::Result<u64> {
+    let name = name.trim().to_lowercase();
+
+    if let Some(contest) = contests.iter().find(|c| c.name.to_lowercase() == name) {
+        return Ok(contest.id);
+    }
+
+    match *contests
+        .iter()
+        .filter(|c| c.name.to_lowercase().contains(&name))
+        .collect::<Vec<_>>()
+    {
+        [] => bail!("No contest in yukicoder matched {:?}", name),
+        [contest] => Ok(contest.id),
+        ref contests => bail!(
+            "Multiple contests in yukicoder matched {:?}: [{}]",
+            name,
+            contests
+                .iter()
+                .format_with(", ", |c, f| f(&format_args!("{:?} ({})", c.name, c.id))),
+        ),
+    }
+}
+
+/// Parses a problem index in a contest (`a`, `B`, ...) case-insensitively.
+fn parse_problem_index(s: &str) -> anyhow::Result<u8> {
+    match *s.trim().to_ascii_uppercase().into_bytes() {
+        [problem_index @ b'A'..=b'Z'] => Ok(problem_index),
+        _ => bail!("A problem index for yukicoder must be `[a-zA-Z]`: {:?}", s),
+    }
 }
 
 fn parse_problem_url(url: &Url) -> anyhow::Result<Either<u64, u64>> {
@@ -315,47 +437,43 @@ fn parse_problem_url(url: &Url) -> anyhow::Result<Either<u64, u64>> {
 fn retrieve_samples(
     mut sess: impl SessionMut,
     targets: YukicoderRetrieveTestCasesTargets,
+    keep_going: bool,
 ) -> anyhow::Result<RetrieveTestCasesOutcome> {
-    let mut outcome = RetrieveTestCasesOutcome { problems: vec![] };
+    let mut outcome = RetrieveTestCasesOutcome::new();
 
     match targets {
         YukicoderRetrieveTestCasesTargets::ProblemNos(problem_nos) => {
             for problem_no in &problem_nos {
-                let problem_no = parse_problem_no(problem_no)?;
-
-                let (url, test_suite) = retrieve_samples(&mut sess, problem_no)?;
-                let api::Problem {
-                    problem_id, title, ..
-                } = sess.get_problem_by_problem_no(problem_no)?;
-
-                outcome.problems.push(RetrieveTestCasesOutcomeProblem {
-                    contest: None,
-                    index: problem_no.to_string(),
-                    url,
-                    screen_name: Some(problem_id.to_string()),
-                    display_name: title.clone(),
-                    test_suite,
-                    text_files: indexmap!(),
-                });
+                let problem = (|| -> anyhow::Result<_> {
+                    let problem_no = parse_problem_no(problem_no)?;
+
+                    let (url, test_suite) = retrieve_samples(&mut sess, problem_no)?;
+                    let api::Problem {
+                        problem_id, title, ..
+                    } = sess.get_problem_by_problem_no(problem_no)?;
+
+                    Ok(RetrieveTestCasesOutcomeProblem {
+                        contest: None,
+                        index: problem_no.to_string(),
+                        url,
+                        screen_name: Some(problem_id.to_string()),
+                        display_name: title,
+                        test_suite,
+                        text_files: indexmap!(),
+                    })
+                })();
+
+                outcome.push_or_record_failure(|| problem_no.to_string(), problem, keep_going)?;
             }
         }
         YukicoderRetrieveTestCasesTargets::Contest(contest_id, problem_indexes) => {
-            let contest_id = parse_contest_id(&contest_id)?;
+            let contest_id = resolve_contest_id(&mut sess, &contest_id)?;
 
             let problem_indexes = problem_indexes
                 .map(|problem_indexes| {
                     problem_indexes
                         .into_iter()
-                        .map(|problem_index| {
-                            match *problem_index
-                                .to_ascii_uppercase()
-                                .chars()
-                                .collect::<Vec<_>>()
-                            {
-                                [problem_index @ 'A'..='Z'] => Ok(problem_index),
-                                _ => bail!("problem indexes for yukicoder must be `[a-zA-Z]`"),
-                            }
-                        })
+                        .map(|problem_index| parse_problem_index(&problem_index).map(char::from))
                         .collect::<anyhow::Result<BTreeSet<_>>>()
                 })
                 .transpose()?;
@@ -388,18 +506,23 @@ fn retrieve_samples(
                     }
                 }
 
-                let api::Problem { no, title, .. } = sess.get_problem_by_problem_id(problem_id)?;
-                let (url, test_suite) = retrieve_samples(&mut sess, no)?;
-
-                outcome.problems.push(RetrieveTestCasesOutcomeProblem {
-                    contest: Some(contest.clone()),
-                    index: index.to_string(),
-                    url,
-                    screen_name: Some(problem_id.to_string()),
-                    display_name: title,
-                    test_suite,
-                    text_files: indexmap!(),
-                });
+                let problem = (|| -> anyhow::Result<_> {
+                    let api::Problem { no, title, .. } =
+                        sess.get_problem_by_problem_id(problem_id)?;
+                    let (url, test_suite) = retrieve_samples(&mut sess, no)?;
+
+                    Ok(RetrieveTestCasesOutcomeProblem {
+                        contest: Some(contest.clone()),
+                        index: index.to_string(),
+                        url,
+                        screen_name: Some(problem_id.to_string()),
+                        display_name: title,
+                        test_suite,
+                        text_files: indexmap!(),
+                    })
+                })();
+
+                outcome.push_or_record_failure(|| index.to_string(), problem, keep_going)?;
             }
 
             if let Some(not_found) = not_found {
@@ -410,26 +533,30 @@ fn retrieve_samples(
         }
         YukicoderRetrieveTestCasesTargets::Urls(urls) => {
             for url in urls {
-                let api::Problem {
-                    no,
-                    problem_id,
-                    title,
-                } = match parse_problem_url(&url)? {
-                    Either::Left(problem_no) => sess.get_problem_by_problem_no(problem_no)?,
-                    Either::Right(problem_id) => sess.get_problem_by_problem_id(problem_id)?,
-                };
+                let problem = (|| -> anyhow::Result<_> {
+                    let api::Problem {
+                        no,
+                        problem_id,
+                        title,
+                    } = match parse_problem_url(&url)? {
+                        Either::Left(problem_no) => sess.get_problem_by_problem_no(problem_no)?,
+                        Either::Right(problem_id) => sess.get_problem_by_problem_id(problem_id)?,
+                    };
+
+                    let (_, test_suite) = retrieve_samples(&mut sess, no)?;
 
-                let (_, test_suite) = retrieve_samples(&mut sess, no)?;
-
-                outcome.problems.push(RetrieveTestCasesOutcomeProblem {
-                    contest: None,
-                    index: no.to_string(),
-                    url,
-                    screen_name: Some(problem_id.to_string()),
-                    display_name: title.clone(),
-                    test_suite,
-                    text_files: indexmap!(),
-                });
+                    Ok(RetrieveTestCasesOutcomeProblem {
+                        contest: None,
+                        index: no.to_string(),
+                        url: url.clone(),
+                        screen_name: Some(problem_id.to_string()),
+                        display_name: title,
+                        test_suite,
+                        text_files: indexmap!(),
+                    })
+                })();
+
+                outcome.push_or_record_failure(|| url.to_string(), problem, keep_going)?;
             }
         }
     }
@@ -454,6 +581,11 @@ fn retrieve_samples(
     }
 }
 
+/// Extracts the sample test cases from an HTML of a problem page.
+pub fn extract_samples(html: &str) -> anyhow::Result<TestSuite> {
+    Html::parse_document(html).extract_samples()
+}
+
 #[ext]
 impl Html {
     fn extract_samples(&self) -> anyhow::Result<TestSuite> {
@@ -461,14 +593,7 @@ impl Html {
             .select(static_selector!("#content > div"))
             .flat_map(|r| r.text())
             .find_map(|text| {
-                let timelimit = {
-                    let caps = static_regex!(r"([0-9]{1,3})\.([0-9]{3})秒").captures(text)?;
-
-                    let secs = caps[1].parse::<u64>().unwrap();
-                    let millis = caps[2].parse::<u64>().unwrap();
-
-                    Duration::from_millis(1000 * secs + millis)
-                };
+                let timelimit = super::parse_timelimit(text)?;
 
                 let kind = if text.contains("標準ジャッジ問題") {
                     Kind::Regular
@@ -515,6 +640,7 @@ impl Html {
 
                 let mut test_suite = BatchTestSuite {
                     timelimit: Some(timelimit),
+                    memorylimit: None,
                     r#match,
                     cases: vec![],
                     extend: vec![],
@@ -551,6 +677,8 @@ impl Html {
             }
             Kind::Reactive => TestSuite::Interactive(InteractiveTestSuite {
                 timelimit: Some(timelimit),
+                tester: None,
+                each_args: vec![],
             }),
         };
 
@@ -596,6 +724,12 @@ mod api {
 
     static BASE_URL: Lazy<Url> = lazy_url!("https://yukicoder.me/api/v1/");
 
+    pub(super) fn submit_url(problem_id: u64) -> Url {
+        BASE_URL
+            .join(&format!("problems/{}/submit", problem_id))
+            .unwrap()
+    }
+
     pub(super) trait SessionMutExt: SessionMut {
         /// > Get TestCaseFiles by ProblemId
         ///
@@ -705,6 +839,25 @@ mod api {
                 .map_err(Into::into)
         }
 
+        /// > 過去のコンテスト一覧、現在開催中のコンテスト一覧、未来のコンテスト一覧を取得します。
+        fn get_all_contests(&mut self) -> anyhow::Result<Vec<Contest>> {
+            let mut contests = vec![];
+
+            for kind in &["past", "current", "future"] {
+                let url = BASE_URL.join(&format!("contest/{}", kind)).unwrap();
+
+                contests.extend(
+                    self.get(url)
+                        .colorize_status_code(&[200], (), ..)
+                        .send()?
+                        .ensure_status(&[200])?
+                        .json::<Vec<Contest>>()?,
+                );
+            }
+
+            Ok(contests)
+        }
+
         /// > コンテストIDからコンテスト情報を取得します。
         fn get_contest_by_contest_id(&mut self, contest_id: u64) -> anyhow::Result<Contest> {
             let url = BASE_URL
@@ -733,10 +886,8 @@ mod api {
             lang: &str,
             source: &str,
         ) -> anyhow::Result<std::result::Result<u64, (StatusCode, String)>> {
-            let url = BASE_URL.join(&format!("problems/{}/submit", problem_id))?;
-
             let res = self
-                .post(url)
+                .post(submit_url(problem_id))
                 .form(&hashmap!("lang" => lang, "source" => source))
                 .bearer_auth(token)
                 .colorize_status_code(&[200], (), ..)
@@ -825,10 +976,96 @@ mod api {
     #[derive(Debug, Deserialize)]
     #[serde(rename_all = "PascalCase")]
     pub(super) struct Contest {
-        //pub(super) id: u64,
+        pub(super) id: u64,
         pub(super) name: String,
         //pub(super) date: String,
         //pub(super) end_date: String,
         pub(super) problem_id_list: Vec<u64>,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::testsuite::{BatchTestSuite, Match, PartialBatchTestCase, TestSuite};
+    use std::time::Duration;
+
+    #[test]
+    fn parse_problem_index() {
+        assert_eq!(b'A', super::parse_problem_index("a").unwrap());
+        assert_eq!(b'A', super::parse_problem_index("A").unwrap());
+        assert_eq!(b'B', super::parse_problem_index(" b\n").unwrap());
+        assert!(super::parse_problem_index("ex").is_err());
+        assert!(super::parse_problem_index("").is_err());
+        assert!(super::parse_problem_index("1").is_err());
+    }
+
+    #[test]
+    fn find_contest_id() {
+        let contest = |id, name: &str| super::api::Contest {
+            id,
+            name: name.to_owned(),
+            problem_id_list: vec![],
+        };
+
+        let contests = [
+            contest(1, "yukicoder contest 300"),
+            contest(2, "yukicoder contest 300 (Div.2)"),
+            contest(3, "Advent Calendar Contest 2020"),
+        ];
+
+        assert_eq!(
+            1,
+            super::find_contest_id(&contests, "YUKICODER CONTEST 300").unwrap()
+        );
+        assert_eq!(3, super::find_contest_id(&contests, "advent").unwrap());
+        assert!(super::find_contest_id(&contests, "contest").is_err());
+        assert!(super::find_contest_id(&contests, "nothing").is_err());
+    }
+
+    #[test]
+    fn extract_samples() {
+        let test_suite = super::extract_samples(
+            r#"<!DOCTYPE html>
+<html>
+<body>
+<div id="content">
+<div>実行時間制限 : 1ケース 2.000秒 / メモリ制限 : 512 MB / 標準ジャッジ問題</div>
+<div class="block">
+<div class="sample">
+<div class="paragraph">
+<h6>入力</h6>
+<pre>1 2
+</pre>
+<h6>出力</h6>
+<pre>3
+</pre>
+</div>
+</div>
+</div>
+</div>
+</body>
+</html>
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            TestSuite::Batch(BatchTestSuite {
+                timelimit: Some(Duration::from_secs(2)),
+                memorylimit: None,
+                r#match: Match::Lines,
+                cases: vec![PartialBatchTestCase {
+                    name: Some("sample1".to_owned()),
+                    r#in: "1 2\n".into(),
+                    out: Some("3\n".into()),
+                    timelimit: None,
+                    r#match: None,
+                }],
+                extend: vec![],
+            }),
+            test_suite,
+        );
+
+        assert!(super::extract_samples("<html><body></body></html>").is_err());
+    }
+}
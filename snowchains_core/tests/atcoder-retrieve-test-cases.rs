@@ -131,11 +131,16 @@ fn test(
             username_and_password: &mut { username_and_password },
         },
         full: None,
+        keep_going: false,
         cookie_storage: CookieStorage {
             cookie_store: CookieStore::default(),
             on_update: Box::new(|_| Ok(())),
         },
         timeout: TIMEOUT,
+        connect_timeout: None,
+        min_interval: None,
+        proxy: None,
+        local_address: None,
         shell: Shell(&mut messages),
     })?;
 
@@ -81,8 +81,13 @@ fn test(target: Target) -> anyhow::Result<()> {
         },
         credentials: (),
         full: None,
+        keep_going: false,
         cookie_storage: (),
         timeout: TIMEOUT,
+        connect_timeout: None,
+        min_interval: None,
+        proxy: None,
+        local_address: None,
         shell: Shell(&mut messages),
     })?;
 
@@ -0,0 +1,245 @@
+use crate::{commands::CommonOpt, judge::SuiteFormat, web::CaseConversions};
+use anyhow::{bail, Context as _};
+use snowchains_core::{
+    color_spec,
+    testsuite::{BatchTestSuite, PartialBatchTestCase, TestSuite},
+    web::PlatformKind,
+};
+use std::{
+    ffi::OsStr,
+    io::BufRead,
+    path::{Path, PathBuf},
+};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::{Color, WriteColor};
+
+#[derive(StructOpt, Debug)]
+pub struct OptAddCase {
+    /// Name of the test case
+    #[structopt(long, value_name("STRING"))]
+    pub name: Option<String>,
+
+    /// Reads the input from the file instead of the standard input
+    #[structopt(long("in"), value_name("PATH"))]
+    pub in_file: Option<PathBuf>,
+
+    /// Reads the expected output from the file instead of the standard input
+    #[structopt(long("out"), value_name("PATH"))]
+    pub out_file: Option<PathBuf>,
+
+    /// Path to `snowchains.dhall` [default: `$SNOWCHAINS_CONFIG` or the one in the ancestors]
+    #[structopt(long)]
+    pub config: Option<PathBuf>,
+
+    #[structopt(flatten)]
+    pub common: CommonOpt,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+
+    /// Platform
+    #[structopt(
+        short,
+        long,
+        value_name("SERVICE"),
+        possible_values(PlatformKind::KEBAB_CASE_VARIANTS)
+    )]
+    pub service: Option<PlatformKind>,
+
+    /// Contest ID
+    #[structopt(short, long, value_name("STRING"))]
+    pub contest: Option<String>,
+
+    /// Problem index (e.g. "a", "b", "c")
+    pub problem: Option<String>,
+}
+
+pub(crate) fn run(
+    opt: OptAddCase,
+    ctx: crate::Context<impl BufRead, impl WriteColor, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptAddCase {
+        name,
+        in_file,
+        out_file,
+        config,
+        common: _,
+        color: _,
+        service,
+        contest,
+        problem,
+    } = opt;
+
+    let crate::Context { cwd, mut shell } = ctx;
+
+    let (detected_target, workspace) = crate::config::detect_target(&cwd, config.as_deref())?;
+
+    let service = service
+        .map(Ok)
+        .or_else(|| detected_target.parse_service().transpose())
+        .with_context(|| {
+            "`service` was not detected. To specify it, add `--service` to the arguments"
+        })??;
+
+    let contest = contest.or(detected_target.contest);
+
+    let problem = problem
+        .or(detected_target.problem)
+        .with_context(|| "`problem` was not detected. To specify it, add it to the arguments")?;
+
+    let path = crate::judge::find_test_suite(
+        &workspace
+            .join(".snowchains")
+            .join("tests")
+            .join(service.to_kebab_case_str())
+            .join(contest.as_deref().unwrap_or("")),
+        &CaseConversions::new(&problem).kebab,
+    )?;
+
+    if !path.exists() {
+        bail!("`{}` does not exist", path.display());
+    }
+
+    let mut test_suite = match crate::judge::read_test_suite(&path)? {
+        TestSuite::Batch(test_suite) => test_suite,
+        TestSuite::Interactive(_) | TestSuite::Unsubmittable => {
+            bail!("`{}` is not a batch test suite", path.display())
+        }
+    };
+
+    let mut read = |file: Option<PathBuf>, prompt: &str| -> anyhow::Result<String> {
+        if let Some(file) = file {
+            return crate::fs::read_to_string(cwd.join(file.strip_prefix(".").unwrap_or(&file)));
+        }
+
+        writeln!(shell.stderr, "{} (end with a line of \".\"):", prompt)?;
+        shell.stderr.flush()?;
+
+        shell
+            .stdin
+            .read_lines_until_dot()
+            .with_context(|| "Could not read the standard input")
+    };
+
+    let r#in = read(in_file, "Input")?;
+    let out = read(out_file, "Expected output")?;
+
+    push_case(&mut test_suite, name.clone(), r#in, out)?;
+
+    crate::fs::write(
+        &path,
+        suite_format(&path).serialize(&TestSuite::Batch(test_suite)),
+        false,
+    )?;
+
+    shell.stderr.set_color(color_spec!(Bold))?;
+    write!(shell.stderr, "{}:", problem)?;
+    shell.stderr.reset()?;
+    write!(shell.stderr, " Added ")?;
+    if let Some(name) = &name {
+        write!(shell.stderr, "{:?} ", name)?;
+    }
+    write!(shell.stderr, "to ")?;
+    shell.stderr.set_color(color_spec!(Fg(Color::Cyan)))?;
+    write!(shell.stderr, "{}", path.display())?;
+    shell.stderr.reset()?;
+    writeln!(shell.stderr)?;
+    shell.stderr.flush()?;
+
+    Ok(())
+}
+
+/// Appends a test case after the existing ones, rejecting a duplicate name.
+fn push_case(
+    test_suite: &mut BatchTestSuite,
+    name: Option<String>,
+    r#in: String,
+    out: String,
+) -> anyhow::Result<()> {
+    if let Some(name) = &name {
+        if test_suite
+            .cases
+            .iter()
+            .any(|c| c.name.as_ref() == Some(name))
+        {
+            bail!("A test case named {:?} already exists", name);
+        }
+    }
+
+    test_suite.cases.push(PartialBatchTestCase {
+        name,
+        r#in: r#in.into(),
+        out: Some(out.into()),
+        timelimit: None,
+        r#match: None,
+    });
+    Ok(())
+}
+
+/// Returns the format of the test suite file at `path`.
+fn suite_format(path: &Path) -> SuiteFormat {
+    path.extension()
+        .and_then(OsStr::to_str)
+        .and_then(|ext| ext.parse().ok())
+        .unwrap_or(SuiteFormat::Yml)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::judge::SuiteFormat;
+    use snowchains_core::testsuite::{BatchTestSuite, Match};
+    use std::path::Path;
+
+    #[test]
+    fn push_case() -> anyhow::Result<()> {
+        let mut test_suite = BatchTestSuite {
+            timelimit: None,
+            memorylimit: None,
+            r#match: Match::Lines,
+            cases: vec![],
+            extend: vec![],
+        };
+
+        super::push_case(
+            &mut test_suite,
+            Some("corner".to_owned()),
+            "1\n".to_owned(),
+            "2\n".to_owned(),
+        )?;
+        super::push_case(&mut test_suite, None, "3\n".to_owned(), "4\n".to_owned())?;
+
+        assert_eq!(2, test_suite.cases.len());
+        assert_eq!(Some("corner"), test_suite.cases[0].name.as_deref());
+        assert_eq!("3\n", &*test_suite.cases[1].r#in);
+        assert_eq!(Some("4\n"), test_suite.cases[1].out.as_deref());
+
+        let err = super::push_case(
+            &mut test_suite,
+            Some("corner".to_owned()),
+            "".to_owned(),
+            "".to_owned(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            "A test case named \"corner\" already exists",
+            err.to_string()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn suite_format() {
+        let format = |path: &str| super::suite_format(Path::new(path)).to_string();
+
+        assert_eq!("yml", format("a.yml"));
+        assert_eq!("json", format("a.json"));
+        assert_eq!("toml", format("a.toml"));
+        assert_eq!("yml", format("a"));
+    }
+}
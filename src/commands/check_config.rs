@@ -0,0 +1,61 @@
+use crate::commands::CommonOpt;
+use anyhow::bail;
+use snowchains_core::color_spec;
+use std::path::PathBuf;
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::{Color, WriteColor};
+
+#[derive(StructOpt, Debug)]
+pub struct OptCheckConfig {
+    /// Path to `snowchains.dhall` [default: `$SNOWCHAINS_CONFIG` or the one in the ancestors]
+    #[structopt(long)]
+    pub config: Option<PathBuf>,
+
+    #[structopt(flatten)]
+    pub common: CommonOpt,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+}
+
+pub(crate) fn run(
+    opt: OptCheckConfig,
+    ctx: crate::Context<impl Sized, impl Sized, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptCheckConfig {
+        config,
+        common: _,
+        color: _,
+    } = opt;
+
+    let crate::Context { cwd, mut shell } = ctx;
+
+    let (path, problems) = crate::config::check(&cwd, config.as_deref())?;
+
+    for problem in &problems {
+        shell.stderr.set_color(color_spec!(Bold, Fg(Color::Red)))?;
+        write!(shell.stderr, "error:")?;
+        shell.stderr.reset()?;
+        writeln!(shell.stderr, " {}", problem)?;
+    }
+    shell.stderr.flush()?;
+
+    if !problems.is_empty() {
+        bail!("Found {} problem(s) in `{}`", problems.len(), path);
+    }
+
+    write!(shell.stderr, "No problems found in ")?;
+    shell.stderr.set_color(color_spec!(Fg(Color::Cyan)))?;
+    write!(shell.stderr, "{}", path)?;
+    shell.stderr.reset()?;
+    writeln!(shell.stderr)?;
+    shell.stderr.flush()?;
+
+    Ok(())
+}
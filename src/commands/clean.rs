@@ -0,0 +1,192 @@
+use crate::{commands::CommonOpt, config};
+use anyhow::{bail, ensure};
+use snowchains_core::{color_spec, web::PlatformKind};
+use std::{
+    io::BufRead,
+    path::{Component, Path, PathBuf},
+};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::{Color, WriteColor};
+
+#[derive(StructOpt, Debug)]
+pub struct OptClean {
+    /// Also removes the test suites of the contest
+    #[structopt(long)]
+    pub suites: bool,
+
+    /// Prints the paths without removing them
+    #[structopt(long)]
+    pub dry_run: bool,
+
+    /// Path to `snowchains.dhall` [default: `$SNOWCHAINS_CONFIG` or the one in the ancestors]
+    #[structopt(long)]
+    pub config: Option<PathBuf>,
+
+    #[structopt(flatten)]
+    pub common: CommonOpt,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+
+    /// Platform
+    #[structopt(
+        short,
+        long,
+        value_name("SERVICE"),
+        possible_values(PlatformKind::KEBAB_CASE_VARIANTS)
+    )]
+    pub service: Option<PlatformKind>,
+
+    /// Contest ID
+    #[structopt(short, long, value_name("STRING"))]
+    pub contest: Option<String>,
+
+    /// Language name
+    #[structopt(short, long, value_name("STRING"))]
+    pub language: Option<String>,
+
+    /// Problem index (e.g. "a", "b", "c")
+    pub problem: Option<String>,
+}
+
+pub(crate) fn run(
+    opt: OptClean,
+    ctx: crate::Context<impl BufRead, impl WriteColor, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptClean {
+        suites,
+        dry_run,
+        config,
+        common: _,
+        color: _,
+        service,
+        contest,
+        language,
+        problem,
+    } = opt;
+
+    let crate::Context { cwd, mut shell } = ctx;
+
+    let mut paths = vec![];
+    let mut suite_dir = None;
+
+    for &mode in &[config::Mode::Debug, config::Mode::Release] {
+        let (
+            target,
+            config::Language {
+                transpile, compile, ..
+            },
+            _,
+            base_dir,
+        ) = config::target_and_language(
+            &cwd,
+            config.as_deref(),
+            service,
+            contest.as_deref(),
+            problem.as_deref(),
+            language.as_deref(),
+            mode,
+        )?;
+
+        for config::Compile { output, .. } in transpile.iter().chain(&compile) {
+            let path = resolve_under(&base_dir, output)?;
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+
+        if suites && suite_dir.is_none() {
+            let contest = match &target.contest {
+                Some(contest) => contest,
+                None => bail!("`--suites` requires `contest`"),
+            };
+
+            suite_dir = Some(resolve_under(
+                &base_dir,
+                &Path::new(".snowchains")
+                    .join("tests")
+                    .join(target.service.to_kebab_case_str())
+                    .join(contest)
+                    .to_string_lossy(),
+            )?);
+        }
+    }
+
+    paths.extend(suite_dir);
+
+    for path in paths.into_iter().filter(|p| p.exists()) {
+        if !dry_run {
+            if path.is_dir() {
+                crate::fs::remove_dir_all(&path)?;
+            } else {
+                crate::fs::remove_file(&path)?;
+            }
+        }
+
+        write!(
+            shell.stderr,
+            "{}",
+            if dry_run { "Would remove " } else { "Removed " },
+        )?;
+        shell.stderr.set_color(color_spec!(Fg(Color::Cyan)))?;
+        write!(shell.stderr, "{}", path.display())?;
+        shell.stderr.reset()?;
+        writeln!(shell.stderr)?;
+        shell.stderr.flush()?;
+    }
+
+    Ok(())
+}
+
+/// Resolves `path` against `base_dir`, ensuring that it stays within `base_dir`.
+fn resolve_under(base_dir: &Path, path: &str) -> anyhow::Result<PathBuf> {
+    let path = Path::new(path);
+    let joined = base_dir.join(path.strip_prefix(".").unwrap_or(path));
+
+    let mut resolved = PathBuf::new();
+    for component in joined.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            component => resolved.push(component),
+        }
+    }
+
+    ensure!(
+        resolved.starts_with(base_dir) && resolved != base_dir,
+        "`{}` is not under `{}`",
+        resolved.display(),
+        base_dir.display(),
+    );
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    #[test]
+    fn resolve_under() {
+        let base_dir = Path::new("/project");
+
+        assert_eq!(
+            Path::new("/project/target/a"),
+            super::resolve_under(base_dir, "./target/a").unwrap(),
+        );
+        assert_eq!(
+            Path::new("/project/b"),
+            super::resolve_under(base_dir, "./target/../b").unwrap(),
+        );
+        assert!(super::resolve_under(base_dir, "../other/a").is_err());
+        assert!(super::resolve_under(base_dir, "/tmp/a").is_err());
+        assert!(super::resolve_under(base_dir, ".").is_err());
+    }
+}
@@ -0,0 +1,162 @@
+use crate::{commands::CommonOpt, config};
+use anyhow::{bail, Context as _};
+use snowchains_core::{color_spec, web::PlatformKind};
+use std::{
+    io::BufRead,
+    path::{Path, PathBuf},
+};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::{Color, WriteColor};
+
+#[derive(StructOpt, Debug)]
+pub struct OptGenerate {
+    /// Overwrites the source file if it already exists
+    #[structopt(long)]
+    pub force: bool,
+
+    /// Path to `snowchains.dhall` [default: `$SNOWCHAINS_CONFIG` or the one in the ancestors]
+    #[structopt(long)]
+    pub config: Option<PathBuf>,
+
+    #[structopt(flatten)]
+    pub common: CommonOpt,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+
+    /// Platform
+    #[structopt(
+        short,
+        long,
+        value_name("SERVICE"),
+        possible_values(PlatformKind::KEBAB_CASE_VARIANTS)
+    )]
+    pub service: Option<PlatformKind>,
+
+    /// Contest ID
+    #[structopt(short, long, value_name("STRING"))]
+    pub contest: Option<String>,
+
+    /// Language name
+    #[structopt(short, long, value_name("STRING"))]
+    pub language: Option<String>,
+
+    /// Problem index (e.g. "a", "b", "c")
+    pub problem: Option<String>,
+}
+
+pub(crate) fn run(
+    opt: OptGenerate,
+    ctx: crate::Context<impl BufRead, impl WriteColor, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptGenerate {
+        force,
+        config,
+        common: _,
+        color: _,
+        service,
+        contest,
+        language,
+        problem,
+    } = opt;
+
+    let crate::Context { cwd, mut shell } = ctx;
+
+    let (
+        config::Target {
+            service,
+            contest,
+            problem,
+            ..
+        },
+        config::Language { src, template, .. },
+        _,
+        base_dir,
+    ) = config::target_and_language(
+        &cwd,
+        config.as_deref(),
+        service,
+        contest.as_deref(),
+        problem.as_deref(),
+        language.as_deref(),
+        config::Mode::Debug,
+    )?;
+
+    let template = template.with_context(|| "`template` is not set for the language")?;
+    let template = crate::fs::read_to_string(resolve(&base_dir, &template))?;
+
+    let src_path = resolve(&base_dir, &src);
+
+    if src_path.exists() && !force {
+        bail!(
+            "`{}` already exists. To overwrite it, add `--force` to the arguments",
+            src_path.display(),
+        );
+    }
+
+    let code = expand_template(&template, service, contest.as_deref(), &problem);
+    crate::fs::write(&src_path, code, true)?;
+
+    shell.stderr.set_color(color_spec!(Bold))?;
+    write!(shell.stderr, "{}:", problem)?;
+    shell.stderr.reset()?;
+    write!(shell.stderr, " Generated ")?;
+    shell.stderr.set_color(color_spec!(Fg(Color::Cyan)))?;
+    write!(shell.stderr, "{}", src_path.display())?;
+    shell.stderr.reset()?;
+    writeln!(shell.stderr)?;
+    shell.stderr.flush()?;
+
+    Ok(())
+}
+
+fn resolve(base_dir: &Path, path: &str) -> PathBuf {
+    let path = Path::new(path);
+    base_dir.join(path.strip_prefix(".").unwrap_or(path))
+}
+
+/// Replaces `$service`, `$contest`, and `$problem` in `template`.
+fn expand_template(
+    template: &str,
+    service: PlatformKind,
+    contest: Option<&str>,
+    problem: &str,
+) -> String {
+    template
+        .replace("$service", service.to_kebab_case_str())
+        .replace("$contest", contest.unwrap_or(""))
+        .replace("$problem", problem)
+}
+
+#[cfg(test)]
+mod tests {
+    use snowchains_core::web::PlatformKind;
+
+    #[test]
+    fn expand_template() {
+        assert_eq!(
+            "// atcoder/abc100/a\nfn main() {}\n",
+            super::expand_template(
+                "// $service/$contest/$problem\nfn main() {}\n",
+                PlatformKind::Atcoder,
+                Some("abc100"),
+                "a",
+            ),
+        );
+        assert_eq!(
+            "// yukicoder//1\n",
+            super::expand_template(
+                "// $service/$contest/$problem\n",
+                PlatformKind::Yukicoder,
+                None,
+                "1",
+            ),
+        );
+    }
+}
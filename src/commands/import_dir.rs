@@ -0,0 +1,372 @@
+use crate::{
+    commands::{
+        retrieve_testcases::{write_test_suite, OnConflict},
+        CommonOpt,
+    },
+    judge::SuiteFormat,
+    web::CaseConversions,
+};
+use anyhow::{bail, ensure, Context as _};
+use serde::Deserialize;
+use snowchains_core::{
+    color_spec,
+    testsuite::{BatchTestSuite, CheckerShell, InOutPairs, Match, PartialBatchTestCase, TestSuite},
+    web::PlatformKind,
+};
+use std::{
+    io::BufRead,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use structopt::StructOpt;
+use strum::{EnumString, EnumVariantNames, VariantNames as _};
+use termcolor::{Color, WriteColor};
+
+#[derive(StructOpt, Debug)]
+pub struct OptImportDir {
+    /// Timelimit for the test cases
+    #[structopt(long, value_name("DURATION"), parse(try_from_str = humantime::parse_duration))]
+    pub timelimit: Option<Duration>,
+
+    /// How to compare outputs
+    #[structopt(
+        long,
+        value_name("MATCH"),
+        possible_values(ImportMatch::VARIANTS),
+        default_value("lines")
+    )]
+    pub r#match: ImportMatch,
+
+    /// Judges with a testlib-style checker instead of `--match`. It is run as `<PATH> <input>
+    /// <actual output> <expected output>` [default: `checker` in `<DIR>` if it has `info.toml`]
+    #[structopt(long, value_name("PATH"))]
+    pub checker: Option<PathBuf>,
+
+    /// What to do with an existing test suite
+    #[structopt(
+        long,
+        value_name("ACTION"),
+        possible_values(OnConflict::VARIANTS),
+        default_value("overwrite")
+    )]
+    pub on_conflict: OnConflict,
+
+    /// Path to `snowchains.dhall` [default: `$SNOWCHAINS_CONFIG` or the one in the ancestors]
+    #[structopt(long)]
+    pub config: Option<PathBuf>,
+
+    #[structopt(flatten)]
+    pub common: CommonOpt,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+
+    /// Platform
+    #[structopt(
+        short,
+        long,
+        value_name("SERVICE"),
+        possible_values(PlatformKind::KEBAB_CASE_VARIANTS)
+    )]
+    pub service: Option<PlatformKind>,
+
+    /// Contest ID
+    #[structopt(short, long, value_name("STRING"))]
+    pub contest: Option<String>,
+
+    /// Problem index (e.g. "a", "b", "c")
+    #[structopt(short, long, value_name("STRING"))]
+    pub problem: Option<String>,
+
+    /// Directory that contains `*.in` and `*.out` files, or a problem directory of Library Checker
+    /// (https://github.com/yosupo06/library-checker-problems) after `generate.py`
+    pub dir: PathBuf,
+}
+
+#[derive(EnumVariantNames, EnumString, strum::Display, Debug, Clone, Copy)]
+#[strum(serialize_all = "kebab-case")]
+pub enum ImportMatch {
+    Exact,
+    SplitWhitespace,
+    Lines,
+    Unordered,
+    Any,
+    NonEmpty,
+}
+
+impl ImportMatch {
+    fn to_match(self) -> Match {
+        match self {
+            Self::Exact => Match::Exact,
+            Self::SplitWhitespace => Match::SplitWhitespace,
+            Self::Lines => Match::Lines,
+            Self::Unordered => Match::Unordered,
+            Self::Any => Match::Any,
+            Self::NonEmpty => Match::NonEmpty,
+        }
+    }
+}
+
+/// `info.toml` of a problem of Library Checker.
+#[derive(Deserialize)]
+struct LibraryCheckerInfo {
+    /// In seconds.
+    timelimit: Option<f64>,
+}
+
+pub(crate) fn run(
+    opt: OptImportDir,
+    ctx: crate::Context<impl BufRead, impl WriteColor, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptImportDir {
+        timelimit,
+        r#match,
+        checker,
+        on_conflict,
+        config,
+        common: _,
+        color: _,
+        service,
+        contest,
+        problem,
+        dir,
+    } = opt;
+
+    let crate::Context { cwd, mut shell } = ctx;
+
+    let (detected_target, workspace) = crate::config::detect_target(&cwd, config.as_deref())?;
+
+    let service = service
+        .map(Ok)
+        .or_else(|| detected_target.parse_service().transpose())
+        .with_context(|| {
+            "`service` was not detected. To specify it, add `--service` to the arguments"
+        })??;
+
+    let contest = contest.or(detected_target.contest);
+
+    let problem = problem.or(detected_target.problem).with_context(|| {
+        "`problem` was not detected. To specify it, add `--problem` to the arguments"
+    })?;
+
+    let dir = cwd.join(dir.strip_prefix(".").unwrap_or(&dir));
+
+    let info_toml = dir.join("info.toml");
+
+    let library_checker_info = if info_toml.exists() {
+        Some(crate::fs::read_toml::<LibraryCheckerInfo, _>(&info_toml)?)
+    } else {
+        None
+    };
+
+    let checker = match (checker, &library_checker_info) {
+        (Some(checker), _) => Some(cwd.join(checker.strip_prefix(".").unwrap_or(&checker))),
+        (None, Some(_)) => Some(dir.join("checker")),
+        (None, None) => None,
+    };
+
+    let timelimit = timelimit.or_else(|| {
+        library_checker_info
+            .and_then(|LibraryCheckerInfo { timelimit }| timelimit)
+            .map(Duration::from_secs_f64)
+    });
+
+    let r#match = if let Some(checker) = checker {
+        ensure!(
+            checker.exists(),
+            "`{}` does not exist. Build the checker first",
+            checker.display(),
+        );
+
+        let checker = checker
+            .to_str()
+            .with_context(|| format!("The path must be valid UTF-8: {:?}", checker))?;
+
+        Match::Checker {
+            cmd: format!(
+                r#"{} "$1" "$2" "$3""#,
+                shell_escape::unix::escape(checker.into())
+            ),
+            shell: CheckerShell::Bash,
+            working_dir: None,
+        }
+    } else {
+        r#match.to_match()
+    };
+    let requires_output = !matches!(r#match, Match::Any | Match::NonEmpty);
+
+    let mut files = InOutPairs::new();
+    collect_files(&dir, &mut files)?;
+
+    let cases = files
+        .into_iter()
+        .map(|(name, (r#in, out))| {
+            let r#in = r#in.with_context(|| format!("No input file for {:?}", name))?;
+
+            if out.is_none() && requires_output {
+                bail!(
+                    "No output file for {:?}. Specify `--match any` or `--match non-empty` to \
+                     accept any output",
+                    name,
+                );
+            }
+
+            Ok(PartialBatchTestCase {
+                name: Some(name),
+                r#in: crate::fs::read_to_string(r#in)?.into(),
+                out: out
+                    .map(crate::fs::read_to_string)
+                    .transpose()?
+                    .map(Into::into),
+                timelimit: None,
+                r#match: None,
+            })
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    ensure!(
+        !cases.is_empty(),
+        "No `*.in` files found in `{}`",
+        dir.display()
+    );
+
+    let num_cases = cases.len();
+
+    let test_suite = TestSuite::Batch(BatchTestSuite {
+        timelimit,
+        memorylimit: None,
+        r#match,
+        cases,
+        extend: vec![],
+    });
+
+    let index = CaseConversions::new(problem);
+
+    let path = workspace
+        .join(".snowchains")
+        .join("tests")
+        .join(service.to_kebab_case_str())
+        .join(contest.as_deref().unwrap_or(""))
+        .join(&index.kebab)
+        .with_extension("yml");
+
+    let skipped = write_test_suite(
+        &mut shell.stderr,
+        &index.original,
+        &path,
+        SuiteFormat::Yml,
+        &[],
+        on_conflict,
+        || crate::fs::write(&path, SuiteFormat::Yml.serialize(&test_suite), true),
+    )?;
+
+    if skipped.is_some() {
+        return Ok(());
+    }
+
+    shell.stderr.set_color(color_spec!(Bold))?;
+    write!(shell.stderr, "{}:", index.original)?;
+    shell.stderr.reset()?;
+    write!(shell.stderr, " Saved to ")?;
+    shell.stderr.set_color(color_spec!(Fg(Color::Cyan)))?;
+    write!(shell.stderr, "{}", path.display())?;
+    shell.stderr.reset()?;
+    write!(shell.stderr, " (")?;
+    shell.stderr.set_color(color_spec!(Fg(Color::Green)))?;
+    if num_cases == 1 {
+        write!(shell.stderr, "1 test case")?;
+    } else {
+        write!(shell.stderr, "{} test cases", num_cases)?;
+    }
+    shell.stderr.reset()?;
+    writeln!(shell.stderr, ")")?;
+    shell.stderr.flush()?;
+
+    Ok(())
+}
+
+fn collect_files(dir: &Path, files: &mut InOutPairs<PathBuf>) -> anyhow::Result<()> {
+    let entries = std::fs::read_dir(dir)
+        .with_context(|| format!("Could not read the directory `{}`", dir.display()))?;
+
+    for entry in entries {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            collect_files(&path, files)?;
+            continue;
+        }
+
+        let is_in = match path.extension().and_then(|s| s.to_str()) {
+            Some("in") => true,
+            Some("out") => false,
+            _ => continue,
+        };
+
+        let name = path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned();
+
+        let (r#in, out) = files.entry(name.clone()).or_default();
+        let slot = if is_in { r#in } else { out };
+
+        ensure!(slot.is_none(), "Duplicated name: {:?}", name);
+        *slot = Some(path);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        path::{Path, PathBuf},
+    };
+
+    #[test]
+    fn collect_files() -> anyhow::Result<()> {
+        let tempdir = tempfile::Builder::new()
+            .prefix("snowchains-import-dir-test-")
+            .tempdir()?;
+
+        for (file_name, content) in &[
+            ("1.in", "1\n"),
+            ("1.out", "2\n"),
+            ("sub/2.in", "3\n"),
+            ("README.md", ""),
+        ] {
+            crate::fs::write(tempdir.path().join(file_name), content, true)?;
+        }
+
+        let mut files = BTreeMap::new();
+        super::collect_files(tempdir.path(), &mut files)?;
+
+        let relative = |p: &Option<PathBuf>| {
+            p.as_ref()
+                .map(|p| p.strip_prefix(tempdir.path()).unwrap().to_owned())
+        };
+        assert_eq!(
+            vec![
+                ("1", Some("1.in".into()), Some("1.out".into())),
+                ("2", Some(Path::new("sub").join("2.in")), None),
+            ],
+            files
+                .iter()
+                .map(|(n, (i, o))| (&**n, relative(i), relative(o)))
+                .collect::<Vec<_>>(),
+        );
+
+        crate::fs::write(tempdir.path().join("sub").join("1.in"), "", false)?;
+        let err = super::collect_files(tempdir.path(), &mut BTreeMap::new()).unwrap_err();
+        assert_eq!("Duplicated name: \"1\"", err.to_string());
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+use crate::commands::CommonOpt;
 use anyhow::{bail, Context as _};
 use std::{fs, path::PathBuf};
 use structopt::StructOpt;
@@ -10,6 +11,9 @@ pub struct OptInit {
     #[structopt(short, long)]
     pub force: bool,
 
+    #[structopt(flatten)]
+    pub common: CommonOpt,
+
     /// Coloring
     #[structopt(
         long,
@@ -29,6 +33,7 @@ pub(crate) fn run(
 ) -> anyhow::Result<()> {
     let OptInit {
         force,
+        common: _,
         color: _,
         directory,
     } = opt;
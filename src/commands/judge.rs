@@ -1,10 +1,18 @@
-use crate::config;
+use crate::{
+    commands::CommonOpt,
+    config,
+    judge::{OutputFormat, TimelimitOverride},
+};
 use human_size::Size;
-use snowchains_core::web::PlatformKind;
-use std::path::PathBuf;
+use indicatif::ProgressDrawTarget;
+use snowchains_core::{color_spec, testsuite::ExtraOutput, web::PlatformKind};
+use std::{
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+};
 use structopt::StructOpt;
 use strum::VariantNames as _;
-use termcolor::WriteColor;
+use termcolor::{Color, WriteColor};
 
 #[derive(StructOpt, Debug)]
 pub struct OptJudge {
@@ -20,10 +28,82 @@ pub struct OptJudge {
     #[structopt(long, value_name("SIZE"), default_value("4KiB"))]
     pub display_limit: Size,
 
-    /// Path to `snowchains.dhall`
+    /// Prints only the failed test cases and the number of accepted ones
+    #[structopt(long)]
+    pub diff_only: bool,
+
+    /// Runs the test cases that failed last time first
+    #[structopt(long)]
+    pub failed_first: bool,
+
+    /// Runs only the test cases that failed last time
+    #[structopt(long)]
+    pub only_failing: bool,
+
+    /// Prints the stderr of the solution also for accepted and wrong answers
+    #[structopt(long)]
+    pub show_stderr: bool,
+
+    /// Prints the slowest test cases first in the result
+    #[structopt(long)]
+    pub sort_by_time: bool,
+
+    /// Saves the stdout of the solution for each test case as `<DIR>/<test case>.out`
+    #[structopt(long, value_name("DIR"))]
+    pub save_output: Option<PathBuf>,
+
+    /// Number of test cases to run at once [default: config `jobs` or the number of logical CPUs]
+    #[structopt(short, long, value_name("N"))]
+    pub jobs: Option<NonZeroUsize>,
+
+    /// Runs fewer test cases at once so that their total memory usage stays within the size
+    #[structopt(long, value_name("SIZE"))]
+    pub memory_budget: Option<Size>,
+
+    /// Estimated memory usage of one test case [default: the memory limit of the cases or 256MiB]
+    #[structopt(long, value_name("SIZE"))]
+    pub memory_per_case: Option<Size>,
+
+    /// Runs each test case the number of times and reports the worst result
+    #[structopt(long, value_name("N"))]
+    pub repeat: Option<NonZeroUsize>,
+
+    /// Accepts outputs with extra lines if the expected lines are their prefix or subsequence
+    #[structopt(long, value_name("MODE"), possible_values(ExtraOutput::VARIANTS))]
+    pub tolerate_extra_output: Option<ExtraOutput>,
+
+    /// Overrides the timelimit of the test cases (e.g. "3000", "5s"). "0" or "none" disables it
+    #[structopt(long, value_name("MILLIS"))]
+    pub timelimit: Option<TimelimitOverride>,
+
+    /// Overrides the memory limit of the test cases in megabytes. Enforced only on Linux
+    #[structopt(long, value_name("MB"))]
+    pub memory_limit: Option<u64>,
+
+    /// Format of the result
+    #[structopt(
+        long,
+        value_name("FORMAT"),
+        possible_values(OutputFormat::VARIANTS),
+        default_value("pretty")
+    )]
+    pub format: OutputFormat,
+
+    /// Writes the result to the file instead of stdout
+    #[structopt(long, value_name("PATH"))]
+    pub output: Option<PathBuf>,
+
+    /// Watches the source file and re-runs the tests on change
+    #[structopt(long)]
+    pub watch_files: bool,
+
+    /// Path to `snowchains.dhall` [default: `$SNOWCHAINS_CONFIG` or the one in the ancestors]
     #[structopt(long)]
     pub config: Option<PathBuf>,
 
+    #[structopt(flatten)]
+    pub common: CommonOpt,
+
     /// Coloring
     #[structopt(
         long,
@@ -61,7 +141,24 @@ pub(crate) fn run(
         release,
         testcases,
         display_limit,
+        diff_only,
+        failed_first,
+        only_failing,
+        show_stderr,
+        sort_by_time,
+        save_output,
+        jobs,
+        memory_budget,
+        memory_per_case,
+        repeat,
+        tolerate_extra_output,
+        timelimit,
+        memory_limit,
+        format,
+        output,
+        watch_files,
         config,
+        common: _,
         color: _,
         service,
         contest,
@@ -71,11 +168,14 @@ pub(crate) fn run(
 
     let crate::Context { cwd, shell } = ctx;
 
-    let progress_draw_target = shell.progress_draw_target();
+    let save_output = save_output.map(|p| cwd.join(p.strip_prefix(".").unwrap_or(&p)));
+    let output = output.map(|p| cwd.join(p.strip_prefix(".").unwrap_or(&p)));
 
     let crate::shell::Shell {
-        stdout,
-        stderr,
+        mut stdout,
+        mut stderr,
+        stderr_tty,
+        strict,
         stdin_process_redirection,
         stdout_process_redirection,
         stderr_process_redirection,
@@ -95,7 +195,12 @@ pub(crate) fn run(
             compile,
             run,
             languageId: _,
+            env,
+            template: _,
+            timelimitMultiplier: timelimit_multiplier,
+            bundle: _,
         },
+        top_level,
         base_dir,
     ) = config::target_and_language(
         &cwd,
@@ -111,24 +216,129 @@ pub(crate) fn run(
         },
     )?;
 
+    let jobs = match jobs {
+        Some(jobs) => Some(jobs),
+        None => top_level.jobs(),
+    };
+
     let test_case_names = testcases.map(|ss| ss.into_iter().collect());
 
-    crate::judge::judge(crate::judge::Args {
-        stdout,
-        stderr,
-        stdin_process_redirection,
-        stdout_process_redirection,
-        stderr_process_redirection,
-        progress_draw_target,
-        base_dir,
-        service,
-        contest,
-        problem,
-        src,
-        transpile,
-        compile,
-        run,
-        test_case_names,
-        display_limit,
-    })
+    if !watch_files {
+        return crate::judge::judge(crate::judge::Args {
+            stdout,
+            stderr,
+            stdin_process_redirection,
+            stdout_process_redirection,
+            stderr_process_redirection,
+            progress_draw_target: if stderr_tty {
+                ProgressDrawTarget::stderr
+            } else {
+                ProgressDrawTarget::hidden
+            },
+            strict,
+            base_dir,
+            service,
+            contest,
+            problem,
+            src,
+            transpile,
+            compile,
+            run,
+            env,
+            test_case_names,
+            display_limit,
+            diff_only,
+            failed_first,
+            only_failing,
+            show_stderr,
+            sort_by_time,
+            save_output,
+            memory_budget,
+            memory_per_case,
+            repeat,
+            tolerate_extra_output,
+            jobs,
+            timelimit,
+            timelimit_multiplier,
+            memory_limit,
+            format,
+            output,
+        });
+    }
+
+    let src_path = {
+        let src = Path::new(&src);
+        base_dir.join(src.strip_prefix(".").unwrap_or(src))
+    };
+    let mut watcher = crate::judge::SourceWatcher::new(&src_path)?;
+
+    loop {
+        if stderr_tty {
+            write!(stderr, "\x1b[2J\x1b[H")?;
+            stderr.flush()?;
+        }
+
+        let result = crate::judge::judge(crate::judge::Args {
+            stdout: &mut stdout,
+            stderr: &mut stderr,
+            stdin_process_redirection,
+            stdout_process_redirection,
+            stderr_process_redirection,
+            progress_draw_target: if stderr_tty {
+                ProgressDrawTarget::stderr
+            } else {
+                ProgressDrawTarget::hidden
+            },
+            strict,
+            base_dir: base_dir.clone(),
+            service,
+            contest: contest.clone(),
+            problem: problem.clone(),
+            src: src.clone(),
+            transpile: transpile.clone(),
+            compile: compile.clone(),
+            run: run.clone(),
+            env: env.clone(),
+            test_case_names: test_case_names.clone(),
+            display_limit,
+            diff_only,
+            failed_first,
+            only_failing,
+            show_stderr,
+            sort_by_time,
+            save_output: save_output.clone(),
+            memory_budget,
+            memory_per_case,
+            repeat,
+            tolerate_extra_output,
+            jobs,
+            timelimit,
+            timelimit_multiplier,
+            memory_limit,
+            format,
+            output: output.clone(),
+        });
+
+        if let Err(err) = result {
+            if crate::judge::ctrl_c_received() {
+                return Err(err);
+            }
+
+            stderr.set_color(color_spec!(Bold, Fg(Color::Red)))?;
+            write!(stderr, "Error:")?;
+            stderr.reset()?;
+            writeln!(stderr, " {:?}", err)?;
+        }
+
+        writeln!(stderr)?;
+        stderr.set_color(color_spec!(Bold))?;
+        write!(stderr, "Watching {} for changes...", src_path.display())?;
+        stderr.reset()?;
+        writeln!(stderr, " (Ctrl-c to quit)")?;
+        stderr.flush()?;
+
+        if !watcher.wait()? {
+            break Ok(());
+        }
+    }
 }
@@ -1,3 +1,4 @@
+use crate::commands::{CommonOpt, WebOpt};
 use serde::Serialize;
 use snowchains_core::web::{
     Atcoder, AtcoderLoginCredentials, Codeforces, CodeforcesLoginCredentials, CookieStorage, Login,
@@ -17,6 +18,16 @@ pub struct OptLogin {
     #[structopt(long)]
     pub json: bool,
 
+    /// Uses the cookies and the tokens of the profile instead of the default ones
+    #[structopt(long, value_name("NAME"))]
+    pub profile: Option<String>,
+
+    #[structopt(flatten)]
+    pub web: WebOpt,
+
+    #[structopt(flatten)]
+    pub common: CommonOpt,
+
     /// Coloring
     #[structopt(
         long,
@@ -47,49 +58,85 @@ pub(crate) fn run(
 ) -> anyhow::Result<()> {
     let OptLogin {
         json,
+        profile,
+        web:
+            WebOpt {
+                proxy,
+                bind_address,
+                timeout,
+                connect_timeout,
+                min_interval,
+            },
+        common: _,
         color: _,
         service,
     } = opt;
 
     let crate::Context { cwd: _, mut shell } = ctx;
 
-    let cookie_storage = CookieStorage::with_jsonl(crate::web::credentials::cookie_store_path()?)?;
+    let cookie_storage = CookieStorage::with_jsonl(crate::web::credentials::cookie_store_path(
+        service,
+        profile.as_deref(),
+    )?)?;
+
+    let (timeout, connect_timeout) = crate::web::timeouts(timeout, connect_timeout);
 
-    let timeout = Some(crate::web::SESSION_TIMEOUT);
+    let min_interval = crate::web::min_interval(min_interval);
+    let proxy = crate::web::proxy(proxy)?;
 
     let outcome = match service {
         PlatformKind::Atcoder => {
             let shell = RefCell::new(&mut shell);
 
+            let mut username_and_password =
+                crate::web::credentials::atcoder_username_and_password(&shell, profile.as_deref());
+
             let credentials = AtcoderLoginCredentials {
-                username_and_password: &mut crate::web::credentials::atcoder_username_and_password(
-                    &shell,
-                ),
+                username_and_password: &mut || username_and_password.get(),
             };
 
-            Atcoder::exec(Login {
+            let outcome = Atcoder::exec(Login {
                 credentials,
                 cookie_storage,
                 timeout,
+                connect_timeout,
+                min_interval,
+                proxy,
+                local_address: bind_address,
                 shell: &shell,
-            })
+            })?;
+            username_and_password.save()?;
+            Ok(outcome)
         }
         PlatformKind::Codeforces => {
             let shell = RefCell::new(&mut shell);
 
+            let mut username_and_password =
+                crate::web::credentials::codeforces_username_and_password(
+                    &shell,
+                    profile.as_deref(),
+                );
+
             let credentials = CodeforcesLoginCredentials {
-                username_and_password:
-                    &mut crate::web::credentials::codeforces_username_and_password(&shell),
+                username_and_password: &mut || username_and_password.get(),
             };
 
-            Codeforces::exec(Login {
+            let outcome = Codeforces::exec(Login {
                 credentials,
                 cookie_storage,
                 timeout,
+                connect_timeout,
+                min_interval,
+                proxy,
+                local_address: bind_address,
                 shell: &shell,
-            })
+            })?;
+            username_and_password.save()?;
+            Ok(outcome)
+        }
+        PlatformKind::Yukicoder | PlatformKind::Other => {
+            unreachable!("should be filtered by `possible_values`")
         }
-        PlatformKind::Yukicoder => unreachable!("should be filtered by `possible_values`"),
     }?;
 
     let message = if json {
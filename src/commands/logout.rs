@@ -0,0 +1,128 @@
+use crate::commands::CommonOpt;
+use serde::Serialize;
+use snowchains_core::web::{CookieStorage, PlatformKind};
+use std::io::{BufRead, Write};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::WriteColor;
+use url::Url;
+
+#[derive(StructOpt, Debug)]
+pub struct OptLogout {
+    /// Prints the output as a JSON value
+    #[structopt(long)]
+    pub json: bool,
+
+    /// Uses the cookies and the tokens of the profile instead of the default ones
+    #[structopt(long, value_name("NAME"))]
+    pub profile: Option<String>,
+
+    #[structopt(flatten)]
+    pub common: CommonOpt,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+
+    /// Target platform
+    #[structopt(possible_values(&["atcoder", "codeforces"]))]
+    pub service: PlatformKind,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+struct Outcome {
+    kind: OutcomeKind,
+}
+
+impl Outcome {
+    fn to_json(self) -> String {
+        serde_json::to_string(&self).expect("should not fail")
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+enum OutcomeKind {
+    Success,
+    NotLoggedIn,
+}
+
+pub(crate) fn run(
+    opt: OptLogout,
+    ctx: crate::Context<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptLogout {
+        json,
+        profile,
+        common: _,
+        color: _,
+        service,
+    } = opt;
+
+    let crate::Context { cwd: _, mut shell } = ctx;
+
+    let url = match service {
+        PlatformKind::Atcoder => "https://atcoder.jp/",
+        PlatformKind::Codeforces => "https://codeforces.com/",
+        PlatformKind::Yukicoder | PlatformKind::Other => {
+            unreachable!("should be filtered by `possible_values`")
+        }
+    }
+    .parse::<Url>()
+    .expect("should be valid");
+
+    let path = crate::web::credentials::cookie_store_path(service, profile.as_deref())?;
+
+    let kind = if path.exists() {
+        let CookieStorage {
+            mut cookie_store,
+            on_update,
+        } = CookieStorage::with_jsonl(&path)?;
+
+        let cookies = cookie_store
+            .iter_any()
+            .filter(|cookie| cookie.domain.matches(&url))
+            .map(|cookie| {
+                (
+                    String::from(&cookie.domain),
+                    String::from(&cookie.path),
+                    cookie.name().to_owned(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        for (domain, cookie_path, name) in &cookies {
+            cookie_store.remove(domain, cookie_path, name);
+        }
+
+        if cookie_store.iter_any().next().is_none() {
+            crate::fs::remove_file(&path)?;
+        } else {
+            on_update(&cookie_store)?;
+        }
+
+        if cookies.is_empty() {
+            OutcomeKind::NotLoggedIn
+        } else {
+            OutcomeKind::Success
+        }
+    } else {
+        OutcomeKind::NotLoggedIn
+    };
+
+    let message = if json {
+        Outcome { kind }.to_json()
+    } else {
+        match kind {
+            OutcomeKind::Success => "Successfully logged out.",
+            OutcomeKind::NotLoggedIn => "Not logged in.",
+        }
+        .to_owned()
+    };
+
+    writeln!(shell.stdout, "{}", message)?;
+    shell.stdout.flush().map_err(Into::into)
+}
@@ -1,10 +1,59 @@
+pub(crate) mod add_case;
+pub(crate) mod check_config;
+pub(crate) mod clean;
+pub(crate) mod generate;
+pub(crate) mod import_dir;
 pub(crate) mod init;
 pub(crate) mod judge;
 pub(crate) mod login;
+pub(crate) mod logout;
 pub(crate) mod participate;
+pub(crate) mod retrieve_contests;
 pub(crate) mod retrieve_languages;
 pub(crate) mod retrieve_submission_summaries;
 pub(crate) mod retrieve_testcases;
 pub(crate) mod submit;
 pub(crate) mod watch_submissions;
 pub(crate) mod xtask;
+
+use std::net::IpAddr;
+use structopt::StructOpt;
+use url::Url;
+
+/// Options for all of the commands.
+#[derive(StructOpt, Debug, Default, Clone, Copy)]
+pub struct CommonOpt {
+    /// Treats warnings as errors
+    #[structopt(long)]
+    pub strict: bool,
+
+    /// Increases the verbosity of the logs (`-v`: info, `-vv`: debug, `-vvv`: trace)
+    #[structopt(short, long, parse(from_occurrences))]
+    pub verbose: u8,
+}
+
+/// Options for the connections to the services.
+#[derive(StructOpt, Debug)]
+pub struct WebOpt {
+    /// Proxy for the requests [default: `$HTTPS_PROXY` or `$ALL_PROXY`]
+    #[structopt(long, value_name("URL"))]
+    pub proxy: Option<Url>,
+
+    /// Local address to connect from. "0.0.0.0" makes the requests use only IPv4
+    #[structopt(long, value_name("IP"))]
+    pub bind_address: Option<IpAddr>,
+
+    /// Timeout of each request (e.g. "30s", "1m"), which also applies to connecting unless
+    /// `--connect-timeout` is given [default: 30s]
+    #[structopt(long, value_name("DURATION"))]
+    pub timeout: Option<humantime::Duration>,
+
+    /// Timeout of connecting to the server (e.g. "5s") [default: `--timeout`]
+    #[structopt(long, value_name("DURATION"))]
+    pub connect_timeout: Option<humantime::Duration>,
+
+    /// Minimum interval between requests to the server (e.g. "1s"). "0s" disables it
+    /// [default: 300ms]
+    #[structopt(long, value_name("DURATION"))]
+    pub min_interval: Option<humantime::Duration>,
+}
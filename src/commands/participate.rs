@@ -1,3 +1,4 @@
+use crate::commands::{CommonOpt, WebOpt};
 use serde::Serialize;
 use snowchains_core::web::{
     Atcoder, AtcoderParticipateCredentials, AtcoderParticipateTarget, CookieStorage, Participate,
@@ -17,6 +18,16 @@ pub struct OptParticipate {
     #[structopt(long)]
     pub json: bool,
 
+    /// Uses the cookies and the tokens of the profile instead of the default ones
+    #[structopt(long, value_name("NAME"))]
+    pub profile: Option<String>,
+
+    #[structopt(flatten)]
+    pub web: WebOpt,
+
+    #[structopt(flatten)]
+    pub common: CommonOpt,
+
     /// Coloring
     #[structopt(
         long,
@@ -54,34 +65,56 @@ pub(crate) fn run(
 ) -> anyhow::Result<()> {
     let OptParticipate {
         json,
+        profile,
+        web:
+            WebOpt {
+                proxy,
+                bind_address,
+                timeout,
+                connect_timeout,
+                min_interval,
+            },
+        common: _,
         color: _,
-        service: _,
+        service,
         contest,
     } = opt;
 
     let crate::Context { cwd: _, mut shell } = ctx;
 
-    let cookie_storage = CookieStorage::with_jsonl(crate::web::credentials::cookie_store_path()?)?;
-    let timeout = Some(crate::web::SESSION_TIMEOUT);
+    let cookie_storage = CookieStorage::with_jsonl(crate::web::credentials::cookie_store_path(
+        service,
+        profile.as_deref(),
+    )?)?;
+    let (timeout, connect_timeout) = crate::web::timeouts(timeout, connect_timeout);
+    let min_interval = crate::web::min_interval(min_interval);
+    let proxy = crate::web::proxy(proxy)?;
 
     let kind = {
         let shell = RefCell::new(&mut shell);
 
         let target = AtcoderParticipateTarget { contest };
 
+        let mut username_and_password =
+            crate::web::credentials::atcoder_username_and_password(&shell, profile.as_deref());
+
         let credentials = AtcoderParticipateCredentials {
-            username_and_password: &mut crate::web::credentials::atcoder_username_and_password(
-                &shell,
-            ),
+            username_and_password: &mut || username_and_password.get(),
         };
 
-        Atcoder::exec(Participate {
+        let outcome = Atcoder::exec(Participate {
             target,
             credentials,
             cookie_storage,
             timeout,
+            connect_timeout,
+            min_interval,
+            proxy,
+            local_address: bind_address,
             shell: &shell,
-        })?
+        })?;
+        username_and_password.save()?;
+        outcome
     };
 
     let outcome = Outcome { kind };
@@ -0,0 +1,135 @@
+use crate::commands::{CommonOpt, WebOpt};
+use anyhow::{bail, Context as _};
+use snowchains_core::web::{
+    Atcoder, AtcoderRetrieveContestsTarget, Codeforces, PlatformKind, RetrieveContests, Yukicoder,
+};
+use std::{
+    io::{BufRead, Write},
+    path::PathBuf,
+};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::WriteColor;
+
+#[derive(StructOpt, Debug)]
+pub struct OptRetrieveContests {
+    /// Prints the result as JSON
+    #[structopt(long)]
+    pub json: bool,
+
+    /// Page of the contest archive, for AtCoder [default: 1]
+    #[structopt(long, value_name("N"))]
+    pub page: Option<u32>,
+
+    /// Path to `snowchains.dhall` [default: `$SNOWCHAINS_CONFIG` or the one in the ancestors]
+    #[structopt(long)]
+    pub config: Option<PathBuf>,
+
+    #[structopt(flatten)]
+    pub web: WebOpt,
+
+    #[structopt(flatten)]
+    pub common: CommonOpt,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+
+    /// Platform
+    #[structopt(
+        short,
+        long,
+        value_name("SERVICE"),
+        possible_values(PlatformKind::KEBAB_CASE_VARIANTS)
+    )]
+    pub service: Option<PlatformKind>,
+}
+
+pub(crate) fn run(
+    opt: OptRetrieveContests,
+    ctx: crate::Context<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptRetrieveContests {
+        json,
+        page,
+        config,
+        web:
+            WebOpt {
+                proxy,
+                bind_address,
+                timeout,
+                connect_timeout,
+                min_interval,
+            },
+        common: _,
+        color: _,
+        service,
+    } = opt;
+
+    let crate::Context { cwd, mut shell } = ctx;
+
+    let service = match service {
+        Some(service) => service,
+        None => crate::config::detect_target(&cwd, config.as_deref())?
+            .0
+            .parse_service()?
+            .with_context(|| {
+                "`service` was not detected. To specify it, add `--service` to the arguments"
+            })?,
+    };
+
+    if page.is_some() && service != PlatformKind::Atcoder {
+        bail!("`--page` is only available for AtCoder");
+    }
+
+    let (timeout, connect_timeout) = crate::web::timeouts(timeout, connect_timeout);
+
+    let min_interval = crate::web::min_interval(min_interval);
+    let proxy = crate::web::proxy(proxy)?;
+
+    let outcome = match service {
+        PlatformKind::Atcoder => Atcoder::exec(RetrieveContests {
+            target: AtcoderRetrieveContestsTarget {
+                page: page.unwrap_or(1),
+            },
+            timeout,
+            connect_timeout,
+            min_interval,
+            proxy,
+            local_address: bind_address,
+            shell: &mut shell,
+        }),
+        PlatformKind::Codeforces => Codeforces::exec(RetrieveContests {
+            target: (),
+            timeout,
+            connect_timeout,
+            min_interval,
+            proxy,
+            local_address: bind_address,
+            shell: &mut shell,
+        }),
+        PlatformKind::Yukicoder => Yukicoder::exec(RetrieveContests {
+            target: (),
+            timeout,
+            connect_timeout,
+            min_interval,
+            proxy,
+            local_address: bind_address,
+            shell: &mut shell,
+        }),
+        PlatformKind::Other => bail!("`other` does not support `retrieve contests`"),
+    }?;
+
+    if json {
+        writeln!(shell.stdout, "{}", outcome.to_json())
+    } else {
+        write!(shell.stdout, "{}", outcome.to_table())
+    }?;
+
+    shell.stdout.flush()?;
+    Ok(())
+}
@@ -1,4 +1,5 @@
-use anyhow::Context as _;
+use crate::commands::{CommonOpt, WebOpt};
+use anyhow::{bail, Context as _};
 use snowchains_core::web::{
     Atcoder, AtcoderRetrieveLanguagesCredentials, AtcoderRetrieveLanguagesTarget, Codeforces,
     CodeforcesRetrieveLanguagesCredentials, CodeforcesRetrieveLanguagesTarget, CookieStorage,
@@ -19,10 +20,20 @@ pub struct OptRetrieveLanguages {
     #[structopt(long)]
     pub json: bool,
 
-    /// Path to `snowchains.dhall`
+    /// Path to `snowchains.dhall` [default: `$SNOWCHAINS_CONFIG` or the one in the ancestors]
     #[structopt(long)]
     pub config: Option<PathBuf>,
 
+    /// Uses the cookies and the tokens of the profile instead of the default ones
+    #[structopt(long, value_name("NAME"))]
+    pub profile: Option<String>,
+
+    #[structopt(flatten)]
+    pub web: WebOpt,
+
+    #[structopt(flatten)]
+    pub common: CommonOpt,
+
     /// Coloring
     #[structopt(
         long,
@@ -56,6 +67,16 @@ pub(crate) fn run(
     let OptRetrieveLanguages {
         json,
         config,
+        profile,
+        web:
+            WebOpt {
+                proxy,
+                bind_address,
+                timeout,
+                connect_timeout,
+                min_interval,
+            },
+        common: _,
         color: _,
         service,
         contest,
@@ -76,60 +97,89 @@ pub(crate) fn run(
     let contest = contest.or(detected_target.contest);
     let problem = problem.or(detected_target.problem);
 
-    let cookie_storage = CookieStorage::with_jsonl(crate::web::credentials::cookie_store_path()?)?;
-
-    let timeout = Some(crate::web::SESSION_TIMEOUT);
-
-    let outcome =
-        match service {
-            PlatformKind::Atcoder => {
-                let shell = RefCell::new(&mut shell);
-
-                let target = AtcoderRetrieveLanguagesTarget {
-                    contest_and_problem: contest.and_then(|c| problem.map(|p| (c, p))),
-                };
-
-                let credentials = AtcoderRetrieveLanguagesCredentials {
-                    username_and_password:
-                        &mut crate::web::credentials::atcoder_username_and_password(&shell),
-                };
-
-                Atcoder::exec(RetrieveLanguages {
-                    target,
-                    credentials,
-                    cookie_storage,
-                    timeout,
-                    shell: &shell,
-                })
-            }
-            PlatformKind::Codeforces => {
-                let shell = RefCell::new(&mut shell);
-
-                let target = CodeforcesRetrieveLanguagesTarget {
-                    contest: contest.with_context(|| "`contest` is required for Codeforces")?,
-                };
-
-                let credentials = CodeforcesRetrieveLanguagesCredentials {
-                    username_and_password:
-                        &mut crate::web::credentials::atcoder_username_and_password(&shell),
-                };
-
-                Codeforces::exec(RetrieveLanguages {
-                    target,
-                    credentials,
-                    cookie_storage,
-                    timeout,
-                    shell: &shell,
-                })
-            }
-            PlatformKind::Yukicoder => Yukicoder::exec(RetrieveLanguages {
-                target: (),
-                credentials: (),
-                cookie_storage: (),
+    let cookie_storage = CookieStorage::with_jsonl(crate::web::credentials::cookie_store_path(
+        service,
+        profile.as_deref(),
+    )?)?;
+
+    let (timeout, connect_timeout) = crate::web::timeouts(timeout, connect_timeout);
+
+    let min_interval = crate::web::min_interval(min_interval);
+    let proxy = crate::web::proxy(proxy)?;
+
+    let outcome = match service {
+        PlatformKind::Atcoder => {
+            let shell = RefCell::new(&mut shell);
+
+            let target = AtcoderRetrieveLanguagesTarget {
+                contest_and_problem: contest.and_then(|c| problem.map(|p| (c, p))),
+            };
+
+            let mut username_and_password =
+                crate::web::credentials::atcoder_username_and_password(&shell, profile.as_deref());
+
+            let credentials = AtcoderRetrieveLanguagesCredentials {
+                username_and_password: &mut || username_and_password.get(),
+            };
+
+            let outcome = Atcoder::exec(RetrieveLanguages {
+                target,
+                credentials,
+                cookie_storage,
+                timeout,
+                connect_timeout,
+                min_interval,
+                proxy,
+                local_address: bind_address,
+                shell: &shell,
+            })?;
+            username_and_password.save()?;
+            Ok(outcome)
+        }
+        PlatformKind::Codeforces => {
+            let shell = RefCell::new(&mut shell);
+
+            let target = CodeforcesRetrieveLanguagesTarget {
+                contest: contest.with_context(|| "`contest` is required for Codeforces")?,
+            };
+
+            let mut username_and_password =
+                crate::web::credentials::codeforces_username_and_password(
+                    &shell,
+                    profile.as_deref(),
+                );
+
+            let credentials = CodeforcesRetrieveLanguagesCredentials {
+                username_and_password: &mut || username_and_password.get(),
+            };
+
+            let outcome = Codeforces::exec(RetrieveLanguages {
+                target,
+                credentials,
+                cookie_storage,
                 timeout,
-                shell: &mut shell,
-            }),
-        }?;
+                connect_timeout,
+                min_interval,
+                proxy,
+                local_address: bind_address,
+                shell: &shell,
+            })?;
+            username_and_password.save()?;
+            Ok(outcome)
+        }
+        PlatformKind::Yukicoder => Yukicoder::exec(RetrieveLanguages {
+            target: (),
+            credentials: (),
+            cookie_storage: (),
+            timeout,
+            connect_timeout,
+            min_interval,
+            proxy,
+            local_address: bind_address,
+            shell: &mut shell,
+        }),
+        PlatformKind::Other => bail!("`other` does not support `retrieve languages`"),
+    }?;
 
     if json {
         writeln!(shell.stdout, "{}", outcome.to_json())
@@ -1,4 +1,6 @@
-use anyhow::Context as _;
+use crate::commands::{CommonOpt, WebOpt};
+use anyhow::{bail, Context as _};
+use chrono::{DateTime, FixedOffset};
 use snowchains_core::web::{
     Atcoder, AtcoderRetrieveSubmissionSummariesCredentials,
     AtcoderRetrieveSubmissionSummariesTarget, CookieStorage, PlatformKind,
@@ -15,10 +17,24 @@ use termcolor::WriteColor;
 
 #[derive(StructOpt, Debug)]
 pub struct OptRetrieveSubmissionSummaries {
-    /// Path to `snowchains.dhall`
+    /// Picks the latest submission at or before the time for each problem
+    #[structopt(long, value_name("TIMESTAMP"), parse(try_from_str = DateTime::parse_from_rfc3339))]
+    pub at: Option<DateTime<FixedOffset>>,
+
+    /// Path to `snowchains.dhall` [default: `$SNOWCHAINS_CONFIG` or the one in the ancestors]
     #[structopt(long)]
     pub config: Option<PathBuf>,
 
+    /// Uses the cookies and the tokens of the profile instead of the default ones
+    #[structopt(long, value_name("NAME"))]
+    pub profile: Option<String>,
+
+    #[structopt(flatten)]
+    pub web: WebOpt,
+
+    #[structopt(flatten)]
+    pub common: CommonOpt,
+
     /// Coloring
     #[structopt(
         long,
@@ -41,7 +57,18 @@ pub(crate) fn run(
     ctx: crate::Context<impl BufRead, impl Write, impl WriteColor>,
 ) -> anyhow::Result<()> {
     let OptRetrieveSubmissionSummaries {
+        at,
         config,
+        profile,
+        web:
+            WebOpt {
+                proxy,
+                bind_address,
+                timeout,
+                connect_timeout,
+                min_interval,
+            },
+        common: _,
         color: _,
         service,
         contest,
@@ -60,32 +87,52 @@ pub(crate) fn run(
 
     let contest = contest.or(detected_target.contest);
 
-    let cookie_storage = CookieStorage::with_jsonl(crate::web::credentials::cookie_store_path()?)?;
-    let timeout = Some(crate::web::SESSION_TIMEOUT);
+    let cookie_storage = CookieStorage::with_jsonl(crate::web::credentials::cookie_store_path(
+        service,
+        profile.as_deref(),
+    )?)?;
+    let (timeout, connect_timeout) = crate::web::timeouts(timeout, connect_timeout);
+    let min_interval = crate::web::min_interval(min_interval);
+    let proxy = crate::web::proxy(proxy)?;
 
     match service {
         PlatformKind::Atcoder => {
-            let outcome = {
+            let mut outcome = {
                 let shell = RefCell::new(&mut shell);
 
                 let target = AtcoderRetrieveSubmissionSummariesTarget {
                     contest: contest.with_context(|| "`contest` is required for AtCoder")?,
                 };
 
+                let mut username_and_password =
+                    crate::web::credentials::atcoder_username_and_password(
+                        &shell,
+                        profile.as_deref(),
+                    );
+
                 let credentials = AtcoderRetrieveSubmissionSummariesCredentials {
-                    username_and_password:
-                        &mut crate::web::credentials::atcoder_username_and_password(&shell),
+                    username_and_password: &mut || username_and_password.get(),
                 };
 
-                Atcoder::exec(RetrieveSubmissionSummaries {
+                let outcome = Atcoder::exec(RetrieveSubmissionSummaries {
                     target,
                     credentials,
                     cookie_storage,
                     timeout,
+                    connect_timeout,
+                    min_interval,
+                    proxy,
+                    local_address: bind_address,
                     shell: &shell,
-                })?
+                })?;
+                username_and_password.save()?;
+                outcome
             };
 
+            if let Some(at) = at {
+                outcome.retain_latest_at(at)?;
+            }
+
             writeln!(shell.stdout, "{}", outcome.to_json())?;
             shell.stdout.flush()?;
             Ok(())
@@ -96,5 +143,6 @@ pub(crate) fn run(
         PlatformKind::Yukicoder => {
             todo!("`retrieve submission-summaries` for yukicoder is not implemented");
         }
+        PlatformKind::Other => bail!("`other` does not support `retrieve submission-summaries`"),
     }
 }
@@ -1,6 +1,17 @@
-use crate::web::CaseConversions;
-use anyhow::Context as _;
+use crate::{
+    commands::{CommonOpt, WebOpt},
+    judge::SuiteFormat,
+    web::{atcoder_problems::DifficultyRange, CaseConversions},
+};
+use anyhow::{bail, ensure, Context as _};
+use indexmap::IndexMap;
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
 use maplit::btreeset;
+use prettytable::{
+    cell,
+    format::{FormatBuilder, LinePosition, LineSeparator},
+    row, Table,
+};
 use serde::Serialize;
 use snowchains_core::{
     color_spec,
@@ -8,20 +19,24 @@ use snowchains_core::{
     web::{
         Atcoder, AtcoderRetrieveFullTestCasesCredentials,
         AtcoderRetrieveSampleTestCasesCredentials, Codeforces,
-        CodeforcesRetrieveSampleTestCasesCredentials, CookieStorage, PlatformKind,
-        ProblemsInContest, RetrieveFullTestCases, RetrieveTestCases, Yukicoder,
-        YukicoderRetrieveFullTestCasesCredentials, YukicoderRetrieveTestCasesTargets,
+        CodeforcesRetrieveSampleTestCasesCredentials, CookieStorage, Other,
+        OtherRetrieveTestCasesTargets, PlatformKind, ProblemsInContest, RetrieveFullTestCases,
+        RetrieveTestCases, Yukicoder, YukicoderRetrieveFullTestCasesCredentials,
+        YukicoderRetrieveTestCasesTargets,
     },
 };
 use std::{
     cell::RefCell,
-    io::{BufRead, Write},
-    path::PathBuf,
+    collections::{BTreeSet, HashMap},
+    io::{self, BufRead, Write},
+    iter,
+    path::{Path, PathBuf},
 };
 use structopt::StructOpt;
-use strum::VariantNames as _;
+use strum::{EnumString, EnumVariantNames, VariantNames as _};
 use termcolor::{Color, WriteColor};
 use url::Url;
+use zip::{write::FileOptions, CompressionMethod, ZipWriter};
 
 #[derive(StructOpt, Debug)]
 pub struct OptRetrieveTestcases {
@@ -33,10 +48,89 @@ pub struct OptRetrieveTestcases {
     #[structopt(long)]
     pub json: bool,
 
-    /// Path to `snowchains.dhall`
+    /// Writes the JSON data to the file ("-" for stdout)
+    #[structopt(long, value_name("PATH"))]
+    pub output_json: Option<PathBuf>,
+
+    /// Writes JSON data for each problem and `index.json` to the directory
+    #[structopt(long, value_name("DIR"))]
+    pub output_dir: Option<PathBuf>,
+
+    /// What to do with existing test files
+    #[structopt(
+        long,
+        value_name("ACTION"),
+        possible_values(OnConflict::VARIANTS),
+        default_value("overwrite")
+    )]
+    pub on_conflict: OnConflict,
+
+    /// Format of the test suite files
+    #[structopt(
+        long,
+        value_name("FORMAT"),
+        possible_values(SuiteFormat::VARIANTS),
+        default_value("yml")
+    )]
+    pub suite_format: SuiteFormat,
+
+    /// Stores the full test cases in a Zip archive per problem instead of text files
+    #[structopt(long)]
+    pub store_zip: bool,
+
+    /// Prints the first few lines of the first sample of each problem
+    #[structopt(long)]
+    pub preview: bool,
+
+    /// Keeps retrieving the other problems when one of them fails, and reports the failures at
+    /// the end
+    #[structopt(long)]
+    pub keep_going: bool,
+
+    /// Reads problem indexes from the file, one per line, in addition to `--problems`. Blank
+    /// lines and lines starting with "#" are ignored
+    #[structopt(long, value_name("PATH"))]
+    pub problems_file: Option<PathBuf>,
+
+    /// Retrieves the problems of the URLs instead. The service is detected from the host
+    #[structopt(
+        long,
+        value_name("URL"),
+        conflicts_with_all(&["service", "contest", "problems", "problems-file", "difficulty"])
+    )]
+    pub url: Vec<Url>,
+
+    /// Retrieves only the problems whose difficulties on AtCoder Problems are in the range (e.g.
+    /// "400-800", "1200-")
+    #[structopt(long, value_name("RANGE"))]
+    pub difficulty: Option<DifficultyRange>,
+
+    /// Shows the difficulty of each problem on AtCoder Problems, colored by the rating color
+    #[structopt(long)]
+    pub with_difficulty: bool,
+
+    /// Opens the problems in the browser
+    #[structopt(long)]
+    pub open: bool,
+
+    /// Command to open the problems with [default: config `browser`, `$BROWSER`, or the OS default]
+    #[structopt(long, value_name("COMMAND"), requires("open"))]
+    pub browser: Option<String>,
+
+    /// Path to `snowchains.dhall` [default: `$SNOWCHAINS_CONFIG` or the one in the ancestors]
     #[structopt(long)]
     pub config: Option<PathBuf>,
 
+    /// Uses the cookies and the tokens of the profile instead of the default ones
+    #[structopt(long, value_name("NAME"))]
+    pub profile: Option<String>,
+
+    #[structopt(flatten)]
+    pub web: WebOpt,
+
+    #[structopt(flatten)]
+    pub common: CommonOpt,
+
     /// Coloring
     #[structopt(
         long,
@@ -58,11 +152,20 @@ pub struct OptRetrieveTestcases {
     #[structopt(short, long, value_name("STRING"))]
     pub contest: Option<String>,
 
-    /// Problem indexes (e.g. "a", "b", "c")
+    /// Problem indexes (e.g. "a", "b", "c"). Ranges of single letters such as "a-d" and
+    /// comma-separated lists such as "a-c,f" are also accepted
     #[structopt(short, long, value_name("STRING"))]
     pub problems: Option<Vec<String>>,
 }
 
+#[derive(EnumVariantNames, EnumString, strum::Display, Debug, Clone, Copy)]
+#[strum(serialize_all = "lowercase")]
+pub enum OnConflict {
+    Overwrite,
+    Skip,
+    Backup,
+}
+
 #[derive(Debug, Serialize)]
 struct Outcome {
     contest: Option<OutcomeContest>,
@@ -105,7 +208,30 @@ pub(crate) fn run(
     let OptRetrieveTestcases {
         full,
         json,
+        output_json,
+        output_dir,
+        on_conflict,
+        suite_format,
+        store_zip,
+        preview,
+        keep_going,
+        problems_file,
+        url: urls,
+        difficulty,
+        with_difficulty,
+        open,
+        browser,
         config,
+        profile,
+        web:
+            WebOpt {
+                proxy,
+                bind_address,
+                timeout,
+                connect_timeout,
+                min_interval,
+            },
+        common: _,
         color: _,
         service,
         contest,
@@ -116,40 +242,175 @@ pub(crate) fn run(
 
     let (detected_target, workspace) = crate::config::detect_target(&cwd, config.as_deref())?;
 
-    let service = service
-        .map(Ok)
-        .or_else(|| detected_target.parse_service().transpose())
-        .with_context(|| {
-            "`service` was not detected. To specify it, add `--service` to the arguments"
-        })??;
+    let service = if let Some(service) = service_from_urls(&urls)? {
+        service
+    } else {
+        service
+            .map(Ok)
+            .or_else(|| detected_target.parse_service().transpose())
+            .with_context(|| {
+                "`service` was not detected. To specify it, add `--service` to the arguments"
+            })??
+    };
+
+    let urls = if urls.is_empty() {
+        None
+    } else {
+        Some(urls.into_iter().collect::<BTreeSet<_>>())
+    };
+
+    let from_urls = urls.is_some();
+
+    let (contest, problems) = if from_urls {
+        (None, None)
+    } else {
+        let problems = match problems_file {
+            Some(path) => {
+                let path = cwd.join(path.strip_prefix(".").unwrap_or(&path));
+                let file_problems = parse_problems_file(&crate::fs::read_to_string(&path)?);
+                ensure!(
+                    !file_problems.is_empty(),
+                    "No problems in {}",
+                    path.display()
+                );
+                let mut problems = problems.unwrap_or_default();
+                problems.extend(file_problems);
+                Some(problems)
+            }
+            None => problems,
+        };
+
+        let problems = problems.map(|ps| expand_problem_ranges(&ps)).transpose()?;
 
-    let contest = contest.or(detected_target.contest);
+        let problems = match (problems.as_deref().unwrap_or(&[]), &detected_target.problem) {
+            ([], None) => None,
+            ([], Some(problem)) => Some(btreeset!(problem.clone())),
+            (problems, _) => Some(problems.iter().cloned().collect()),
+        };
 
-    let problems = match (problems.as_deref().unwrap_or(&[]), &detected_target.problem) {
-        ([], None) => None,
-        ([], Some(problem)) => Some(btreeset!(problem.clone())),
-        (problems, _) => Some(problems.iter().cloned().collect()),
+        (contest.or(detected_target.contest), problems)
     };
 
-    let cookie_storage = CookieStorage::with_jsonl(crate::web::credentials::cookie_store_path()?)?;
+    let proxy = crate::web::proxy(proxy)?;
+    let (timeout, connect_timeout) = crate::web::timeouts(timeout, connect_timeout);
+    let min_interval = crate::web::min_interval(min_interval);
+
+    let problems = if let Some(difficulty) = difficulty {
+        if service != PlatformKind::Atcoder {
+            bail!("`--difficulty` is only available for AtCoder");
+        }
+
+        let contest = contest
+            .as_deref()
+            .with_context(|| "`contest` is required for AtCoder")?;
+
+        let mut matched = crate::web::atcoder_problems::problem_indexes_by_difficulty(
+            contest,
+            difficulty,
+            timeout,
+            connect_timeout,
+            proxy.as_ref(),
+            bind_address,
+        )?;
+
+        if let Some(problems) = &problems {
+            let problems = problems
+                .iter()
+                .map(|p| p.to_lowercase())
+                .collect::<BTreeSet<_>>();
+            matched.retain(|p| problems.contains(&p.to_lowercase()));
+        }
+
+        if matched.is_empty() {
+            bail!("No problems in {:?} matched the difficulty range", contest);
+        }
 
-    let timeout = Some(crate::web::SESSION_TIMEOUT);
+        Some(matched)
+    } else {
+        problems
+    };
+
+    // With `--on-conflict skip`, skips the existing test suites before retrieving the problems so
+    // that re-running an interrupted download is cheap. The problems are still retrieved if their
+    // data is to be written out.
+    let problems = match problems {
+        Some(problems)
+            if matches!(on_conflict, OnConflict::Skip)
+                && !(json || output_json.is_some() || output_dir.is_some() || open) =>
+        {
+            let mut rest = BTreeSet::new();
+
+            for problem in problems {
+                let path = test_suite_path(
+                    &workspace,
+                    service,
+                    contest.as_deref(),
+                    &CaseConversions::new(&problem).kebab,
+                    suite_format,
+                );
+
+                if is_non_empty_file(&path) {
+                    write_skipped(&mut shell.stderr, &problem, &path)?;
+                } else {
+                    rest.insert(problem);
+                }
+            }
+
+            if rest.is_empty() {
+                return Ok(());
+            }
+            Some(rest)
+        }
+        problems => problems,
+    };
+
+    // The annotations are not necessary for the command itself, so failing to fetch them is a
+    // warning.
+    let difficulties = if with_difficulty {
+        if service != PlatformKind::Atcoder {
+            bail!("`--with-difficulty` is only available for AtCoder");
+        }
+
+        crate::web::atcoder_problems::difficulties(
+            timeout,
+            connect_timeout,
+            proxy.as_ref(),
+            bind_address,
+        )
+        .or_else(|err| -> anyhow::Result<_> {
+            shell.warn(format!(
+                "Could not retrieve the difficulties from AtCoder Problems: {:#}",
+                err,
+            ))?;
+            Ok(HashMap::new())
+        })?
+    } else {
+        HashMap::new()
+    };
+
+    let cookie_storage = CookieStorage::with_jsonl(crate::web::credentials::cookie_store_path(
+        service,
+        profile.as_deref(),
+    )?)?;
 
     let outcome = match service {
         PlatformKind::Atcoder => {
             let shell = RefCell::new(&mut shell);
 
-            let targets = {
+            let targets = if let Some(urls) = urls {
+                ProblemsInContest::Urls { urls }
+            } else {
                 let contest = contest
                     .clone()
                     .with_context(|| "`contest` is required for AtCoder")?;
                 ProblemsInContest::Indexes { contest, problems }
             };
 
+            let mut username_and_password =
+                crate::web::credentials::atcoder_username_and_password(&shell, profile.as_deref());
+
             let credentials = AtcoderRetrieveSampleTestCasesCredentials {
-                username_and_password: &mut crate::web::credentials::atcoder_username_and_password(
-                    &shell,
-                ),
+                username_and_password: &mut || username_and_password.get(),
             };
 
             let full = if full {
@@ -162,41 +423,64 @@ pub(crate) fn run(
                 None
             };
 
-            Atcoder::exec(RetrieveTestCases {
+            let outcome = Atcoder::exec(RetrieveTestCases {
                 targets,
                 credentials,
                 full,
+                keep_going,
                 cookie_storage,
                 timeout,
+                connect_timeout,
+                min_interval,
+                proxy,
+                local_address: bind_address,
                 shell: &shell,
-            })
+            })?;
+            username_and_password.save()?;
+            Ok(outcome)
         }
         PlatformKind::Codeforces => {
             let shell = RefCell::new(&mut shell);
 
-            let targets = {
+            let targets = if let Some(urls) = urls {
+                ProblemsInContest::Urls { urls }
+            } else {
                 let contest = contest
                     .clone()
                     .with_context(|| "`contest` is required for Codeforces")?;
                 ProblemsInContest::Indexes { contest, problems }
             };
 
+            let mut username_and_password =
+                crate::web::credentials::codeforces_username_and_password(
+                    &shell,
+                    profile.as_deref(),
+                );
+
             let credentials = CodeforcesRetrieveSampleTestCasesCredentials {
-                username_and_password:
-                    &mut crate::web::credentials::codeforces_username_and_password(&shell),
+                username_and_password: &mut || username_and_password.get(),
             };
 
-            Codeforces::exec(RetrieveTestCases {
+            let outcome = Codeforces::exec(RetrieveTestCases {
                 targets,
                 credentials,
                 full: None,
+                keep_going,
                 cookie_storage,
                 timeout,
+                connect_timeout,
+                min_interval,
+                proxy,
+                local_address: bind_address,
                 shell: &shell,
-            })
+            })?;
+            username_and_password.save()?;
+            Ok(outcome)
         }
         PlatformKind::Yukicoder => {
-            let targets = if let Some(contest) = &contest {
+            let targets = if let Some(urls) = urls {
+                YukicoderRetrieveTestCasesTargets::Urls(urls)
+            } else if let Some(contest) = &contest {
                 YukicoderRetrieveTestCasesTargets::Contest(contest.clone(), problems)
             } else {
                 let nos = problems
@@ -211,7 +495,10 @@ pub(crate) fn run(
             let full = if full {
                 Some(RetrieveFullTestCases {
                     credentials: YukicoderRetrieveFullTestCasesCredentials {
-                        api_key: crate::web::credentials::yukicoder_api_key(&mut shell)?,
+                        api_key: crate::web::credentials::yukicoder_api_key(
+                            &mut shell,
+                            profile.as_deref(),
+                        )?,
                     },
                 })
             } else {
@@ -224,8 +511,43 @@ pub(crate) fn run(
                 targets,
                 credentials: (),
                 full,
+                keep_going,
                 cookie_storage: (),
                 timeout,
+                connect_timeout,
+                min_interval,
+                proxy,
+                local_address: bind_address,
+                shell,
+            })
+        }
+        PlatformKind::Other => {
+            if full {
+                bail!("`--full` is not supported for `other`");
+            }
+
+            let targets = OtherRetrieveTestCasesTargets {
+                scraper: crate::config::other_service(&workspace)?.scraper,
+                contest: contest.clone(),
+                problems: problems
+                    .with_context(|| "`problem`s are required for `other`")?
+                    .into_iter()
+                    .collect(),
+            };
+
+            let shell = RefCell::new(&mut shell);
+
+            Other::exec(RetrieveTestCases {
+                targets,
+                credentials: (),
+                full: None,
+                keep_going,
+                cookie_storage: (),
+                timeout,
+                connect_timeout,
+                min_interval,
+                proxy,
+                local_address: bind_address,
                 shell,
             })
         }
@@ -257,7 +579,11 @@ pub(crate) fn run(
         problems: vec![],
     };
 
+    let mut summary = vec![];
+    let last_failures = crate::judge::read_last_failures(&workspace)?;
+
     for snowchains_core::web::RetrieveTestCasesOutcomeProblem {
+        contest: problem_contest,
         index,
         url,
         screen_name,
@@ -269,45 +595,118 @@ pub(crate) fn run(
     {
         let index = CaseConversions::new(index);
 
-        let path = workspace
-            .join(".snowchains")
-            .join("tests")
-            .join(service.to_kebab_case_str())
-            .join(contest.as_deref().unwrap_or(""))
-            .join(&index.kebab)
-            .with_extension("yml");
+        // The problems of the URLs may belong to different contests.
+        let contest = if from_urls {
+            problem_contest.map(|c| c.id)
+        } else {
+            contest.clone()
+        };
+
+        let path = test_suite_path(
+            &workspace,
+            service,
+            contest.as_deref(),
+            &index.kebab,
+            suite_format,
+        );
+
+        let txt_dir = path.with_file_name(&index.kebab);
+        let zip_path = path.with_file_name(&index.kebab).with_extension("zip");
 
         let txt_path = |dir_file_name: &str, txt_file_name: &str| -> _ {
-            path.with_file_name(&index.kebab)
+            txt_dir
                 .join(dir_file_name)
                 .join(txt_file_name)
                 .with_extension("txt")
         };
 
-        for (name, snowchains_core::web::RetrieveTestCasesOutcomeProblemTextFiles { r#in, out }) in
-            &text_files
-        {
-            crate::fs::write(txt_path("in", name), &r#in, true)?;
-            if let Some(out) = out {
-                crate::fs::write(txt_path("out", name), out, true)?;
-            }
-        }
+        let num_samples = match &test_suite {
+            TestSuite::Batch(BatchTestSuite { cases, .. }) => cases.len(),
+            TestSuite::Interactive(_) | TestSuite::Unsubmittable => 0,
+        };
 
-        if !text_files.is_empty() {
-            if let TestSuite::Batch(BatchTestSuite { cases, extend, .. }) = &mut test_suite {
-                cases.clear();
+        // Taken before the samples are replaced with the text files.
+        let first_sample = match &test_suite {
+            TestSuite::Batch(BatchTestSuite { cases, .. }) => cases
+                .first()
+                .map(|case| (case.r#in.clone(), case.out.clone())),
+            TestSuite::Interactive(_) | TestSuite::Unsubmittable => None,
+        };
 
-                extend.push(Additional::Text {
-                    path: format!("./{}", index.kebab).into(),
-                    r#in: "/in/*.txt".to_owned(),
-                    out: "/out/*.txt".to_owned(),
-                    timelimit: None,
-                    r#match: None,
-                })
-            }
-        }
+        let progress_draw_target = shell.progress_draw_target();
+
+        let skipped = write_test_suite(
+            &mut shell.stderr,
+            &index.original,
+            &path,
+            suite_format,
+            &[&txt_dir, &zip_path],
+            on_conflict,
+            || {
+                if store_zip && !text_files.is_empty() {
+                    write_zip(&zip_path, &text_files, progress_draw_target)?;
+                } else {
+                    for (
+                        name,
+                        snowchains_core::web::RetrieveTestCasesOutcomeProblemTextFiles {
+                            r#in,
+                            out,
+                        },
+                    ) in &text_files
+                    {
+                        crate::fs::write(txt_path("in", name), &r#in, true)?;
+                        if let Some(out) = out {
+                            crate::fs::write(txt_path("out", name), out, true)?;
+                        }
+                    }
+                }
+
+                if !text_files.is_empty() {
+                    if let TestSuite::Batch(BatchTestSuite { cases, extend, .. }) = &mut test_suite
+                    {
+                        cases.clear();
+
+                        extend.push(if store_zip {
+                            Additional::Zip {
+                                path: format!("./{}.zip", index.kebab).into(),
+                                r#in: r"^in/(.+)\.txt$".to_owned(),
+                                out: r"^out/(.+)\.txt$".to_owned(),
+                                timelimit: None,
+                                r#match: None,
+                            }
+                        } else {
+                            Additional::Text {
+                                path: format!("./{}", index.kebab).into(),
+                                r#in: "/in/*.txt".to_owned(),
+                                out: "/out/*.txt".to_owned(),
+                                timelimit: None,
+                                r#match: None,
+                                group_by_dir: false,
+                            }
+                        })
+                    }
+                }
+
+                crate::fs::write(&path, suite_format.serialize(&test_suite), true)
+            },
+        )?;
 
-        crate::fs::write(&path, test_suite.to_yaml_pretty(), true)?;
+        if let Some(existing) = skipped {
+            acc.problems.push(OutcomeProblem {
+                index,
+                url,
+                screen_name,
+                display_name,
+                test_suite: OutcomeProblemTestSuite {
+                    content: crate::judge::read_test_suite(&existing)?,
+                    path: existing
+                        .into_os_string()
+                        .into_string()
+                        .expect("should be UTF-8"),
+                },
+            });
+            continue;
+        }
 
         shell.stderr.set_color(color_spec!(Bold))?;
         write!(shell.stderr, "{}:", index.original)?;
@@ -322,8 +721,13 @@ pub(crate) fn run(
             write!(
                 shell.stderr,
                 "{}",
-                path.with_file_name(format!("{{{index}.yml, {index}/}}", index = index.kebab))
-                    .display(),
+                path.with_file_name(format!(
+                    "{{{index}.{ext}, {index}{dir_or_zip}}}",
+                    index = index.kebab,
+                    ext = suite_format,
+                    dir_or_zip = if store_zip { ".zip" } else { "/" },
+                ))
+                .display(),
             )
         }?;
         shell.stderr.reset()?;
@@ -346,9 +750,44 @@ pub(crate) fn run(
         write!(shell.stderr, "{}", msg)?;
         shell.stderr.reset()?;
 
-        writeln!(shell.stderr, ")")?;
+        write!(shell.stderr, ")")?;
+
+        if let Some(&difficulty) = screen_name.as_ref().and_then(|s| difficulties.get(s)) {
+            let color = crate::web::atcoder_problems::difficulty_color(difficulty);
+            write!(shell.stderr, " ")?;
+            shell.stderr.set_color(color_spec!(Fg(color)))?;
+            write!(shell.stderr, "[difficulty: {:.0}]", difficulty)?;
+            shell.stderr.reset()?;
+        }
+
+        writeln!(shell.stderr)?;
+
+        if preview {
+            if let Some((r#in, out)) = &first_sample {
+                write_preview(&mut shell.stderr, "Input", r#in)?;
+                if let Some(out) = out {
+                    write_preview(&mut shell.stderr, "Expected output", out)?;
+                }
+            }
+        }
+
         shell.stderr.flush()?;
 
+        summary.push((
+            index.original.clone(),
+            num_samples,
+            text_files.len(),
+            match &test_suite {
+                TestSuite::Batch(_) => "Batch",
+                TestSuite::Interactive(_) => "Interactive",
+                TestSuite::Unsubmittable => "Unsubmittable",
+            },
+            crate::judge::local_verdict(
+                &last_failures,
+                &crate::judge::problem_key(service, contest.as_deref(), &index.kebab),
+            ),
+        ));
+
         acc.problems.push(OutcomeProblem {
             index,
             url,
@@ -364,10 +803,557 @@ pub(crate) fn run(
         });
     }
 
+    if summary.len() > 1 {
+        writeln!(shell.stderr)?;
+        write!(shell.stderr, "{}", summary_table(&summary))?;
+        shell.stderr.flush()?;
+    }
+
+    if !outcome.failures.is_empty() {
+        writeln!(shell.stderr)?;
+
+        for snowchains_core::web::RetrieveTestCasesOutcomeFailure { index, message, .. } in
+            &outcome.failures
+        {
+            shell.stderr.set_color(color_spec!(Bold))?;
+            write!(shell.stderr, "{}:", index)?;
+            shell.stderr.reset()?;
+            write!(shell.stderr, " ")?;
+            shell.stderr.set_color(color_spec!(Fg(Color::Red)))?;
+            write!(shell.stderr, "{}", message)?;
+            shell.stderr.reset()?;
+            writeln!(shell.stderr)?;
+        }
+
+        shell.stderr.flush()?;
+    }
+
+    if let Some(output_dir) = output_dir {
+        let output_dir = cwd.join(output_dir.strip_prefix(".").unwrap_or(&output_dir));
+        write_output_dir(&output_dir, &acc)?;
+
+        write!(shell.stderr, "Wrote the JSON data to ")?;
+        shell.stderr.set_color(color_spec!(Fg(Color::Cyan)))?;
+        write!(shell.stderr, "{}", output_dir.display())?;
+        shell.stderr.reset()?;
+        writeln!(shell.stderr)?;
+        shell.stderr.flush()?;
+    }
+
+    match output_json {
+        Some(path) if path == Path::new("-") => {
+            writeln!(shell.stdout, "{}", acc.to_json())?;
+            shell.stdout.flush()?;
+        }
+        Some(path) => {
+            let path = cwd.join(path.strip_prefix(".").unwrap_or(&path));
+            crate::fs::write(&path, acc.to_json(), true)?;
+
+            write!(shell.stderr, "Wrote the JSON data to ")?;
+            shell.stderr.set_color(color_spec!(Fg(Color::Cyan)))?;
+            write!(shell.stderr, "{}", path.display())?;
+            shell.stderr.reset()?;
+            writeln!(shell.stderr)?;
+            shell.stderr.flush()?;
+        }
+        None => {}
+    }
+
     if json {
         writeln!(shell.stdout, "{}", acc.to_json())?;
         shell.stdout.flush()?;
     }
 
+    if open {
+        let browser = match browser {
+            Some(browser) => Some(browser),
+            None => crate::config::top_level(&cwd, config.as_deref())?.browser,
+        };
+
+        for OutcomeProblem { url, .. } in &acc.problems {
+            crate::web::open_in_browser(&mut shell, browser.as_deref(), url)?;
+        }
+    }
+
+    if !outcome.failures.is_empty() {
+        bail!("Failed to retrieve {} problem(s)", outcome.failures.len());
+    }
     Ok(())
 }
+
+fn write_output_dir(dir: &Path, outcome: &Outcome) -> anyhow::Result<()> {
+    #[derive(Serialize)]
+    struct Index<'a> {
+        contest: &'a Option<OutcomeContest>,
+        problems: Vec<IndexProblem<'a>>,
+    }
+
+    #[derive(Serialize)]
+    struct IndexProblem<'a> {
+        index: &'a CaseConversions,
+        display_name: &'a str,
+        file: String,
+    }
+
+    let mut index = Index {
+        contest: &outcome.contest,
+        problems: vec![],
+    };
+
+    for problem in &outcome.problems {
+        let file = format!("{}.json", problem.index.kebab);
+        ensure!(
+            file != "index.json",
+            "Could not write `{}` since the name is reserved",
+            file,
+        );
+
+        let json = serde_json::to_string_pretty(problem)?;
+        crate::fs::write(dir.join(&file), json, true)?;
+
+        index.problems.push(IndexProblem {
+            index: &problem.index,
+            display_name: &problem.display_name,
+            file,
+        });
+    }
+
+    let json = serde_json::to_string_pretty(&index)?;
+    crate::fs::write(dir.join("index.json"), json, true)
+}
+
+/// Parses the content of `--problems-file`.
+fn parse_problems_file(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !(line.is_empty() || line.starts_with('#')))
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+/// Splits each argument by commas and expands ranges like `a-d` into `A`, `B`, `C`, `D`.
+fn expand_problem_ranges(problems: &[String]) -> anyhow::Result<Vec<String>> {
+    let mut acc = vec![];
+
+    for problem in problems.iter().flat_map(|s| s.split(',')) {
+        let problem = problem.trim();
+
+        if problem.is_empty() {
+            continue;
+        }
+
+        let (start, end) = match *problem.chars().collect::<Vec<_>>() {
+            [start, '-', end] => (start, end),
+            _ => {
+                acc.push(problem.to_owned());
+                continue;
+            }
+        };
+
+        ensure!(
+            start.is_ascii_alphabetic() && end.is_ascii_alphabetic(),
+            "Invalid problem range {:?}: ranges are only available for single letters",
+            problem,
+        );
+
+        let (start, end) = (start.to_ascii_uppercase(), end.to_ascii_uppercase());
+
+        ensure!(
+            start <= end,
+            "Invalid problem range {:?}: `{}` comes after `{}`",
+            problem,
+            start,
+            end,
+        );
+
+        acc.extend((start..=end).map(|c| c.to_string()));
+    }
+
+    Ok(acc)
+}
+
+/// Detects the service from the hosts of `urls`, which must be the same.
+fn service_from_urls(urls: &[Url]) -> anyhow::Result<Option<PlatformKind>> {
+    let services = urls
+        .iter()
+        .map(PlatformKind::from_url)
+        .collect::<anyhow::Result<BTreeSet<_>>>()?;
+
+    ensure!(
+        services.len() <= 1,
+        "The URLs must be of one service: {:?}",
+        services,
+    );
+
+    Ok(services.into_iter().next())
+}
+
+fn write_zip(
+    path: &Path,
+    text_files: &IndexMap<String, snowchains_core::web::RetrieveTestCasesOutcomeProblemTextFiles>,
+    draw_target: ProgressDrawTarget,
+) -> anyhow::Result<()> {
+    let progress = ProgressBar::with_draw_target(text_files.len() as _, draw_target);
+    progress.set_style(
+        ProgressStyle::default_bar().template("{prefix:.bold} {pos}/{len} {wide_bar} {percent}%"),
+    );
+    progress.set_prefix("Zipping");
+
+    let result = (|| -> anyhow::Result<_> {
+        if let Some(parent) = path.parent() {
+            crate::fs::create_dir_all(parent)?;
+        }
+
+        let mut zip = ZipWriter::new(std::fs::File::create(path)?);
+        let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+
+        for (name, snowchains_core::web::RetrieveTestCasesOutcomeProblemTextFiles { r#in, out }) in
+            text_files
+        {
+            zip.start_file(format!("in/{}.txt", name), options)?;
+            zip.write_all(r#in.as_bytes())?;
+            if let Some(out) = out {
+                zip.start_file(format!("out/{}.txt", name), options)?;
+                zip.write_all(out.as_bytes())?;
+            }
+            progress.inc(1);
+        }
+
+        zip.finish()?;
+        Ok(())
+    })()
+    .with_context(|| format!("Could not write `{}`", path.display()));
+
+    progress.finish_and_clear();
+    result
+}
+
+/// Writes `text` indented under `label`, truncated to `PREVIEW_LINES` lines of `PREVIEW_WIDTH`
+/// characters.
+fn write_preview(mut wtr: impl WriteColor, label: &str, text: &str) -> io::Result<()> {
+    const PREVIEW_LINES: usize = 5;
+    const PREVIEW_WIDTH: usize = 80;
+
+    wtr.set_color(color_spec!(Bold))?;
+    writeln!(wtr, "  {}:", label)?;
+    wtr.reset()?;
+
+    for line in text.lines().take(PREVIEW_LINES) {
+        let num_rest = line.chars().count().saturating_sub(PREVIEW_WIDTH);
+        if num_rest > 0 {
+            let line = line.chars().take(PREVIEW_WIDTH).collect::<String>();
+            write!(wtr, "    {}", line)?;
+            wtr.set_color(color_spec!(Fg(Color::Yellow)))?;
+            writeln!(wtr, " ... ({} more characters)", num_rest)?;
+            wtr.reset()?;
+        } else {
+            writeln!(wtr, "    {}", line)?;
+        }
+    }
+
+    let num_rest = text.lines().count().saturating_sub(PREVIEW_LINES);
+    if num_rest > 0 {
+        wtr.set_color(color_spec!(Fg(Color::Yellow)))?;
+        writeln!(wtr, "    ... ({} more lines)", num_rest)?;
+        wtr.reset()?;
+    }
+
+    Ok(())
+}
+
+fn test_suite_path(
+    workspace: &Path,
+    service: PlatformKind,
+    contest: Option<&str>,
+    index_kebab: &str,
+    suite_format: SuiteFormat,
+) -> PathBuf {
+    workspace
+        .join(".snowchains")
+        .join("tests")
+        .join(service.to_kebab_case_str())
+        .join(contest.unwrap_or(""))
+        .join(index_kebab)
+        .with_extension(suite_format.to_string())
+}
+
+/// Whether `path` is a file with any content. An empty file is left by an interrupted write.
+/// Writes a test suite to `path` with `write` following `--on-conflict`. Returns the existing test
+/// suite if it is skipped.
+///
+/// `accompanying` are the files that `write` writes along with the test suite.
+pub(crate) fn write_test_suite(
+    mut stderr: impl WriteColor,
+    index: &str,
+    path: &Path,
+    suite_format: SuiteFormat,
+    accompanying: &[&Path],
+    on_conflict: OnConflict,
+    write: impl FnOnce() -> anyhow::Result<()>,
+) -> anyhow::Result<Option<PathBuf>> {
+    // The ones in the other formats conflict as well.
+    let other_suites = crate::judge::other_test_suites(path, suite_format);
+
+    let existing = if path.exists() {
+        Some(path.to_owned())
+    } else {
+        other_suites.first().cloned()
+    };
+
+    if let Some(existing) = existing {
+        match on_conflict {
+            OnConflict::Overwrite => {}
+            OnConflict::Skip if is_non_empty_file(&existing) => {
+                write_skipped(stderr, index, &existing)?;
+                return Ok(Some(existing));
+            }
+            OnConflict::Skip => {}
+            OnConflict::Backup => {
+                let timestamp = chrono::Local::now().format("%Y%m%d%H%M%S").to_string();
+
+                for from in iter::once(path)
+                    .chain(accompanying.iter().copied())
+                    .chain(other_suites.iter().map(|p| &**p))
+                {
+                    if from.exists() {
+                        let to = backup_path(from, &timestamp);
+                        crate::fs::rename(from, &to)?;
+                        write_backed_up(&mut stderr, from, &to)?;
+                    }
+                }
+            }
+        }
+    }
+
+    write()?;
+
+    // Otherwise `judge` could not tell which one to use.
+    for other_suite in &other_suites {
+        if other_suite.exists() {
+            crate::fs::remove_file(other_suite)?;
+        }
+    }
+
+    Ok(None)
+}
+
+fn is_non_empty_file(path: &Path) -> bool {
+    std::fs::metadata(path).map_or(false, |m| m.is_file() && m.len() > 0)
+}
+
+fn write_skipped(mut wtr: impl WriteColor, index: &str, path: &Path) -> io::Result<()> {
+    wtr.set_color(color_spec!(Bold))?;
+    write!(wtr, "{}:", index)?;
+    wtr.reset()?;
+    write!(wtr, " Skipped since ")?;
+    wtr.set_color(color_spec!(Fg(Color::Cyan)))?;
+    write!(wtr, "{}", path.display())?;
+    wtr.reset()?;
+    writeln!(wtr, " already exists")?;
+    wtr.flush()
+}
+
+fn write_backed_up(mut wtr: impl WriteColor, from: &Path, to: &Path) -> io::Result<()> {
+    write!(wtr, "Backed up ")?;
+    wtr.set_color(color_spec!(Fg(Color::Cyan)))?;
+    write!(wtr, "{}", from.display())?;
+    wtr.reset()?;
+    write!(wtr, " to ")?;
+    wtr.set_color(color_spec!(Fg(Color::Cyan)))?;
+    write!(wtr, "{}", to.display())?;
+    wtr.reset()?;
+    writeln!(wtr)?;
+    wtr.flush()
+}
+
+fn backup_path(path: &Path, timestamp: &str) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_owned();
+    file_name.push(format!(".{}.bak", timestamp));
+    path.with_file_name(file_name)
+}
+
+fn summary_table(rows: &[(String, usize, usize, &str, String)]) -> Table {
+    let mut table = Table::new();
+
+    *table.get_format() = FormatBuilder::new()
+        .padding(1, 1)
+        .column_separator('│')
+        .borders('│')
+        .separator(LinePosition::Top, LineSeparator::new('─', '┬', '┌', '┐'))
+        .separator(LinePosition::Title, LineSeparator::new('─', '┼', '├', '┤'))
+        .separator(LinePosition::Bottom, LineSeparator::new('─', '┴', '└', '┘'))
+        .build();
+
+    table.set_titles(row!["Problem", "Samples", "Full", "Type", "Local"]);
+
+    for (index, num_samples, num_full, kind, local_verdict) in rows {
+        let num_full = if *num_full == 0 {
+            "-".to_owned()
+        } else {
+            num_full.to_string()
+        };
+        table.add_row(row![index, r->num_samples, r->num_full, kind, local_verdict]);
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OnConflict;
+    use crate::judge::SuiteFormat;
+    use snowchains_core::{testsuite::TestSuite, web::PlatformKind};
+    use std::{fs, io};
+    use termcolor::NoColor;
+
+    #[test]
+    fn write_test_suite() -> anyhow::Result<()> {
+        let tempdir = tempfile::Builder::new()
+            .prefix("snowchains-retrieve-testcases-test-")
+            .tempdir()?;
+
+        let path = &tempdir.path().join("a.yml");
+        let txt_dir = &tempdir.path().join("a");
+        let write = |on_conflict| -> anyhow::Result<_> {
+            let mut stderr = NoColor::new(vec![]);
+            let skipped = super::write_test_suite(
+                &mut stderr,
+                "a",
+                path,
+                SuiteFormat::Yml,
+                &[txt_dir],
+                on_conflict,
+                || {
+                    crate::fs::write(txt_dir.join("in").join("1.txt"), "1\n", true)?;
+                    crate::fs::write(
+                        path,
+                        SuiteFormat::Yml.serialize(&TestSuite::Unsubmittable),
+                        true,
+                    )
+                },
+            )?;
+            Ok((skipped.is_none(), String::from_utf8(stderr.into_inner())?))
+        };
+        let file_names = || -> anyhow::Result<_> {
+            let mut file_names = fs::read_dir(tempdir.path())?
+                .map(|e| e.map(|e| e.file_name().into_string().unwrap()))
+                .collect::<io::Result<Vec<_>>>()?;
+            file_names.sort();
+            Ok(file_names)
+        };
+
+        assert_eq!((true, "".to_owned()), write(OnConflict::Skip)?);
+        let content = fs::read_to_string(path)?;
+
+        fs::write(path, "---\ntype: Batch\n")?;
+        let (written, stderr) = write(OnConflict::Skip)?;
+        assert!(!written);
+        assert!(stderr.starts_with("a: Skipped since "));
+        assert_eq!("---\ntype: Batch\n", fs::read_to_string(path)?);
+
+        fs::write(tempdir.path().join("a.json"), "{}")?;
+        assert_eq!((true, "".to_owned()), write(OnConflict::Overwrite)?);
+        assert_eq!(content, fs::read_to_string(path)?);
+        assert_eq!(["a", "a.yml"], *file_names()?);
+
+        let (written, stderr) = write(OnConflict::Backup)?;
+        assert!(written);
+        assert!(stderr.starts_with("Backed up "));
+        let file_names = file_names()?;
+        assert_eq!(4, file_names.len());
+        assert_eq!("a", file_names[0]);
+        assert!(file_names[1].starts_with("a.2") && file_names[1].ends_with(".bak"));
+        assert_eq!("a.yml", file_names[2]);
+        assert!(file_names[3].starts_with("a.yml.") && file_names[3].ends_with(".bak"));
+        Ok(())
+    }
+
+    #[test]
+    fn is_non_empty_file() -> anyhow::Result<()> {
+        let tempdir = tempfile::Builder::new()
+            .prefix("snowchains-retrieve-testcases-test-")
+            .tempdir()?;
+
+        std::fs::write(tempdir.path().join("a.yml"), "---\n")?;
+        std::fs::write(tempdir.path().join("b.yml"), "")?;
+
+        assert!(super::is_non_empty_file(&tempdir.path().join("a.yml")));
+        assert!(!super::is_non_empty_file(&tempdir.path().join("b.yml")));
+        assert!(!super::is_non_empty_file(&tempdir.path().join("c.yml")));
+        assert!(!super::is_non_empty_file(tempdir.path()));
+        Ok(())
+    }
+
+    #[test]
+    fn parse_problems_file() {
+        assert_eq!(
+            ["a", "b-d", "ex-1"],
+            *super::parse_problems_file("# practice\na\n\n  b-d  \r\n#c\nex-1"),
+        );
+        assert!(super::parse_problems_file("").is_empty());
+    }
+
+    #[test]
+    fn expand_problem_ranges() {
+        let expand = |ss: &[&str]| {
+            super::expand_problem_ranges(&ss.iter().map(|&s| s.to_owned()).collect::<Vec<_>>())
+        };
+
+        assert_eq!(["A", "B", "C", "D"], *expand(&["a-d"]).unwrap());
+        assert_eq!(["A", "B", "C", "f"], *expand(&["a-c,f"]).unwrap());
+        assert_eq!(["e", "A", "B", "x"], *expand(&["e", "A-b", "x"]).unwrap());
+        assert_eq!(["C"], *expand(&["c-c"]).unwrap());
+        assert_eq!(["ex-1"], *expand(&["ex-1"]).unwrap());
+        assert!(expand(&["d-a"]).is_err());
+        assert!(expand(&["1-3"]).is_err());
+    }
+
+    #[test]
+    fn service_from_urls() {
+        let service_from_urls = |urls: &[&str]| {
+            super::service_from_urls(&urls.iter().map(|s| s.parse().unwrap()).collect::<Vec<_>>())
+        };
+
+        assert_eq!(None, service_from_urls(&[]).unwrap());
+        assert_eq!(
+            Some(PlatformKind::Atcoder),
+            service_from_urls(&[
+                "https://atcoder.jp/contests/abc100/tasks/abc100_a",
+                "https://atcoder.jp/contests/arc100/tasks/arc100_a",
+            ])
+            .unwrap(),
+        );
+        assert!(service_from_urls(&[
+            "https://atcoder.jp/contests/abc100/tasks/abc100_a",
+            "https://codeforces.com/contest/1000/problem/A",
+        ])
+        .is_err());
+        assert!(service_from_urls(&["https://example.com/problems/a"]).is_err());
+    }
+
+    #[test]
+    fn write_preview() -> anyhow::Result<()> {
+        let mut wtr = termcolor::NoColor::new(vec![]);
+
+        super::write_preview(&mut wtr, "Input", "1\n2\n3\n4\n5\n6\n7\n")?;
+        super::write_preview(&mut wtr, "Expected output", "Yes\n")?;
+        super::write_preview(&mut wtr, "Input", &format!("{}\n", "1 ".repeat(50)))?;
+
+        assert_eq!(
+            concat!(
+                "  Input:\n",
+                "    1\n    2\n    3\n    4\n    5\n",
+                "    ... (2 more lines)\n",
+                "  Expected output:\n",
+                "    Yes\n",
+                "  Input:\n",
+                "    1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 ",
+                "1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 ",
+                " ... (20 more characters)\n",
+            ),
+            String::from_utf8(wtr.into_inner())?,
+        );
+        Ok(())
+    }
+}
@@ -1,12 +1,37 @@
-use crate::config;
+use crate::{
+    commands::{CommonOpt, WebOpt},
+    config,
+    judge::{SuiteFormat, TimelimitOverride},
+};
 use anyhow::{bail, Context as _};
 use human_size::Size;
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use maplit::btreeset;
+use prettytable::{
+    cell,
+    format::{FormatBuilder, LinePosition, LineSeparator},
+    row, Table,
+};
+use rand::Rng as _;
 use snowchains_core::web::{
-    Atcoder, AtcoderSubmitCredentials, Codeforces, CodeforcesSubmitCredentials, CookieStorage,
-    PlatformKind, ProblemInContest, Submit, Yukicoder, YukicoderSubmitCredentials,
+    Atcoder, AtcoderRetrieveSubmissionSummariesCredentials,
+    AtcoderRetrieveSubmissionSummariesTarget, AtcoderSubmitCredentials, Codeforces,
+    CodeforcesSubmitCredentials, CookieStorage, PlatformKind, ProblemInContest,
+    RetrieveSubmissionSummaries, Submit, Yukicoder, YukicoderSubmitCredentials,
     YukicoderSubmitTarget,
 };
-use std::{cell::RefCell, env, io::BufRead, iter, path::PathBuf};
+use std::{
+    cell::RefCell,
+    collections::BTreeSet,
+    env,
+    ffi::OsStr,
+    io::BufRead,
+    iter,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant},
+};
 use structopt::StructOpt;
 use strum::VariantNames as _;
 use termcolor::WriteColor;
@@ -17,10 +42,46 @@ pub struct OptSubmit {
     #[structopt(long)]
     pub no_watch: bool,
 
+    /// Gives up watching the submission after the duration (e.g. "30s", "5m")
+    #[structopt(long, value_name("DURATION"), conflicts_with("no-watch"))]
+    pub watch_timeout: Option<humantime::Duration>,
+
     /// Do not `compile` the code
     #[structopt(long)]
     pub no_judge: bool,
 
+    /// Does everything except actually submitting the code, and prints what would be submitted
+    #[structopt(long)]
+    pub dry_run: bool,
+
+    /// Submits the content of the clipboard instead of the source file
+    #[structopt(long, requires("language"))]
+    pub from_clipboard: bool,
+
+    /// Submits the content of the standard input instead of the source file
+    #[structopt(long, requires("language"), conflicts_with("from-clipboard"))]
+    pub stdin: bool,
+
+    /// Submits every problem of the contest that has the test suite and the source file
+    #[structopt(
+        long,
+        conflicts_with_all(&["problem", "from-clipboard", "stdin", "testcases", "json"])
+    )]
+    pub all: bool,
+
+    /// With `--all`, also submits the problems already accepted on AtCoder
+    #[structopt(long, requires("all"))]
+    pub force: bool,
+
+    /// With `--all`, waits for the duration between the submissions (e.g. "30s", "1m")
+    /// [default: 10s]
+    #[structopt(long, value_name("DURATION"), requires("all"))]
+    pub interval: Option<humantime::Duration>,
+
+    /// With `--all`, adds a random delay of up to the duration to `--interval`
+    #[structopt(long, value_name("DURATION"), requires("all"))]
+    pub jitter: Option<humantime::Duration>,
+
     /// Tests code in `Debug` mode
     #[structopt(long)]
     pub debug: bool,
@@ -37,10 +98,28 @@ pub struct OptSubmit {
     #[structopt(long, value_name("SIZE"), default_value("4KiB"))]
     pub display_limit: Size,
 
-    /// Path to `snowchains.dhall`
+    /// Number of test cases to run at once [default: the number of logical CPUs]
+    #[structopt(short, long, value_name("N"))]
+    pub jobs: Option<NonZeroUsize>,
+
+    /// Overrides the timelimit of the test cases (e.g. "3000", "5s"). "0" or "none" disables it
+    #[structopt(long, value_name("MILLIS"))]
+    pub timelimit: Option<TimelimitOverride>,
+
+    /// Path to `snowchains.dhall` [default: `$SNOWCHAINS_CONFIG` or the one in the ancestors]
     #[structopt(long)]
     pub config: Option<PathBuf>,
 
+    /// Uses the cookies and the tokens of the profile instead of the default ones
+    #[structopt(long, value_name("NAME"))]
+    pub profile: Option<String>,
+
+    #[structopt(flatten)]
+    pub web: WebOpt,
+
+    #[structopt(flatten)]
+    pub common: CommonOpt,
+
     /// Coloring
     #[structopt(
         long,
@@ -74,14 +153,38 @@ pub(crate) fn run(
     opt: OptSubmit,
     ctx: crate::Context<impl BufRead, impl WriteColor, impl WriteColor>,
 ) -> anyhow::Result<()> {
+    if opt.all {
+        return submit_all(opt, ctx);
+    }
+
     let OptSubmit {
         no_watch,
+        watch_timeout,
         no_judge,
+        dry_run,
+        from_clipboard,
+        stdin,
+        all: _,
+        force: _,
+        interval: _,
+        jitter: _,
         debug,
         json,
         testcases,
         display_limit,
+        jobs,
+        timelimit,
         config,
+        profile,
+        web:
+            WebOpt {
+                proxy,
+                bind_address,
+                timeout,
+                connect_timeout,
+                min_interval,
+            },
+        common: CommonOpt { strict, verbose },
         color,
         service,
         contest,
@@ -104,7 +207,12 @@ pub(crate) fn run(
             compile: _,
             run: _,
             languageId: language_id,
+            env,
+            template: _,
+            timelimitMultiplier: _,
+            bundle,
         },
+        top_level,
         base_dir,
     ) = config::target_and_language(
         &cwd,
@@ -120,22 +228,56 @@ pub(crate) fn run(
         },
     )?;
 
-    let code = crate::fs::read_to_string(base_dir.join(&src))?;
+    if service == PlatformKind::Other {
+        bail!("`other` does not support `submit`");
+    }
+
+    let code = if from_clipboard {
+        read_clipboard()?
+    } else if stdin {
+        let code = shell
+            .stdin
+            .read_to_string()
+            .with_context(|| "Could not read the standard input")?;
+
+        if code.trim().is_empty() {
+            bail!("The standard input is empty");
+        }
+        code
+    } else if let Some(bundle) = &bundle {
+        crate::judge::bundle(
+            &mut shell.stderr,
+            &base_dir,
+            bundle,
+            &env.clone().unwrap_or_default(),
+            shell.stdin_process_redirection,
+            shell.stderr_process_redirection,
+        )?
+    } else {
+        crate::fs::read_to_string(base_dir.join(&src))?
+    };
     let language_id = language_id.with_context(|| "Missing `languageId`")?;
 
-    if no_judge {
+    check_source_size(code.len(), top_level.maxSourceSize, service)?;
+
+    if from_clipboard || stdin {
+        // There is no file to test.
+    } else if no_judge {
         if let Some(transpile) = &transpile {
             crate::judge::transpile(
                 &mut shell.stderr,
                 &base_dir,
                 &src,
                 transpile,
+                &env.unwrap_or_default(),
                 shell.stdin_process_redirection,
                 shell.stdout_process_redirection,
                 shell.stderr_process_redirection,
             )?;
         }
     } else {
+        let config_path = config::snowchains_dhall_path(&cwd, config.as_deref())?;
+
         let status = std::process::Command::new(env::current_exe()?)
             .arg("j")
             .args(if debug { &[][..] } else { &["--release"] })
@@ -145,8 +287,19 @@ pub(crate) fn run(
                 vec![]
             })
             .args(&["--display-limit", &display_limit.to_string()])
-            .arg("--config")
-            .arg(base_dir.join("snowchains.dhall"))
+            .args(if let Some(jobs) = jobs {
+                vec!["--jobs".to_owned(), jobs.to_string()]
+            } else {
+                vec![]
+            })
+            .args(if let Some(timelimit) = timelimit {
+                vec!["--timelimit".to_owned(), timelimit.to_string()]
+            } else {
+                vec![]
+            })
+            .args(&["--config", &config_path])
+            .args(if strict { &["--strict"][..] } else { &[] })
+            .args(iter::repeat("-v").take(verbose.into()))
             .args(&["--color", &color.to_string()])
             .args(&["-s", service.to_kebab_case_str()])
             .args(if let Some(contest) = &contest {
@@ -168,10 +321,19 @@ pub(crate) fn run(
     }
 
     let watch_submission = !no_watch;
+    let watch_timeout = watch_timeout.map(Into::into);
 
-    let cookie_storage = CookieStorage::with_jsonl(crate::web::credentials::cookie_store_path()?)?;
+    let cookie_storage = CookieStorage::with_jsonl(crate::web::credentials::cookie_store_path(
+        service,
+        profile.as_deref(),
+    )?)?;
 
-    let timeout = Some(crate::web::SESSION_TIMEOUT);
+    let (timeout, connect_timeout) = crate::web::timeouts(timeout, connect_timeout);
+
+    let min_interval = crate::web::min_interval(min_interval);
+    let proxy = crate::web::proxy(proxy)?;
+
+    let (dry_run_language_id, code_size) = (language_id.clone(), code.len());
 
     let outcome = match service {
         PlatformKind::Atcoder => {
@@ -182,22 +344,31 @@ pub(crate) fn run(
                 problem,
             };
 
+            let mut username_and_password =
+                crate::web::credentials::atcoder_username_and_password(&shell, profile.as_deref());
+
             let credentials = AtcoderSubmitCredentials {
-                username_and_password: &mut crate::web::credentials::atcoder_username_and_password(
-                    &shell,
-                ),
+                username_and_password: &mut || username_and_password.get(),
             };
 
-            Atcoder::exec(Submit {
+            let outcome = Atcoder::exec(Submit {
                 target,
                 credentials,
                 language_id,
                 code,
                 watch_submission,
+                watch_timeout,
+                dry_run,
                 cookie_storage,
                 timeout,
+                connect_timeout,
+                min_interval,
+                proxy,
+                local_address: bind_address,
                 shell: &shell,
-            })
+            })?;
+            username_and_password.save()?;
+            Ok(outcome)
         }
         PlatformKind::Codeforces => {
             let target = ProblemInContest::Index {
@@ -205,28 +376,43 @@ pub(crate) fn run(
                 problem,
             };
 
-            let (api_key, api_secret) =
-                crate::web::credentials::codeforces_api_key_and_secret(&mut shell)?;
+            let (api_key, api_secret) = crate::web::credentials::codeforces_api_key_and_secret(
+                &mut shell,
+                profile.as_deref(),
+            )?;
 
             let shell = RefCell::new(&mut shell);
 
+            let mut username_and_password =
+                crate::web::credentials::codeforces_username_and_password(
+                    &shell,
+                    profile.as_deref(),
+                );
+
             let credentials = CodeforcesSubmitCredentials {
-                username_and_password:
-                    &mut crate::web::credentials::codeforces_username_and_password(&shell),
+                username_and_password: &mut || username_and_password.get(),
                 api_key,
                 api_secret,
             };
 
-            Codeforces::exec(Submit {
+            let outcome = Codeforces::exec(Submit {
                 target,
                 credentials,
                 language_id,
                 code,
                 watch_submission,
+                watch_timeout,
+                dry_run,
                 cookie_storage,
                 timeout,
+                connect_timeout,
+                min_interval,
+                proxy,
+                local_address: bind_address,
                 shell: &shell,
-            })
+            })?;
+            username_and_password.save()?;
+            Ok(outcome)
         }
         PlatformKind::Yukicoder => {
             let target = if let Some(contest) = contest {
@@ -236,7 +422,10 @@ pub(crate) fn run(
             };
 
             let credentials = YukicoderSubmitCredentials {
-                api_key: crate::web::credentials::yukicoder_api_key(&mut shell)?,
+                api_key: crate::web::credentials::yukicoder_api_key(
+                    &mut shell,
+                    profile.as_deref(),
+                )?,
             };
 
             let shell = RefCell::new(&mut shell);
@@ -247,17 +436,459 @@ pub(crate) fn run(
                 language_id,
                 code,
                 watch_submission,
+                watch_timeout,
+                dry_run,
                 cookie_storage: (),
                 timeout,
+                connect_timeout,
+                min_interval,
+                proxy,
+                local_address: bind_address,
                 shell,
             })
         }
+        PlatformKind::Other => unreachable!("checked above"),
     }?;
 
     if json {
         write!(shell.stdout, "{}", outcome.to_json())?;
         shell.stdout.flush()?;
+    } else if dry_run {
+        writeln!(shell.stdout, "Dry run. Nothing was submitted.")?;
+        writeln!(shell.stdout, "URL:         {}", outcome.submit_url)?;
+        writeln!(shell.stdout, "Language ID: {}", dry_run_language_id)?;
+        writeln!(shell.stdout, "Code size:   {} bytes", code_size)?;
+        shell.stdout.flush()?;
     }
 
     Ok(())
 }
+
+/// Runs `snowchains submit` for each problem of the contest, and prints the results.
+///
+/// The problems are the ones that have the test suites. The ones without the source files are
+/// skipped, and so are the ones already accepted on AtCoder unless `--force` is given.
+fn submit_all(
+    opt: OptSubmit,
+    ctx: crate::Context<impl BufRead, impl WriteColor, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptSubmit {
+        no_watch,
+        watch_timeout,
+        no_judge,
+        dry_run,
+        from_clipboard: _,
+        stdin: _,
+        all: _,
+        force,
+        interval,
+        jitter,
+        debug,
+        json: _,
+        testcases: _,
+        display_limit,
+        jobs,
+        timelimit,
+        config,
+        profile,
+        web:
+            WebOpt {
+                proxy,
+                bind_address,
+                timeout,
+                connect_timeout,
+                min_interval,
+            },
+        common: CommonOpt { strict, verbose },
+        color,
+        service,
+        contest,
+        language,
+        problem: _,
+    } = opt;
+
+    let crate::Context { cwd, mut shell } = ctx;
+
+    let (detected_target, workspace) = config::detect_target(&cwd, config.as_deref())?;
+
+    let service = service
+        .map(Ok)
+        .or_else(|| detected_target.parse_service().transpose())
+        .with_context(|| {
+            "`service` was not detected. To specify it, add `--service` to the arguments"
+        })??;
+
+    if service == PlatformKind::Other {
+        bail!("`other` does not support `submit`");
+    }
+
+    let contest = contest
+        .or(detected_target.contest)
+        .with_context(|| "`--all` requires `contest`")?;
+
+    let test_suite_dir = workspace
+        .join(".snowchains")
+        .join("tests")
+        .join(service.to_kebab_case_str())
+        .join(&contest);
+
+    let problems = test_suite_problems(&test_suite_dir)?;
+
+    if problems.is_empty() {
+        bail!("No test suites found in `{}`", test_suite_dir.display());
+    }
+
+    let proxy = crate::web::proxy(proxy)?;
+
+    let accepted = if service == PlatformKind::Atcoder && !force {
+        let (session_timeout, session_connect_timeout) =
+            crate::web::timeouts(timeout, connect_timeout);
+        let shell = RefCell::new(&mut shell);
+
+        let mut username_and_password =
+            crate::web::credentials::atcoder_username_and_password(&shell, profile.as_deref());
+
+        let outcome = Atcoder::exec(RetrieveSubmissionSummaries {
+            target: AtcoderRetrieveSubmissionSummariesTarget {
+                contest: contest.clone(),
+            },
+            credentials: AtcoderRetrieveSubmissionSummariesCredentials {
+                username_and_password: &mut || username_and_password.get(),
+            },
+            cookie_storage: CookieStorage::with_jsonl(crate::web::credentials::cookie_store_path(
+                service,
+                profile.as_deref(),
+            )?)?,
+            timeout: session_timeout,
+            connect_timeout: session_connect_timeout,
+            min_interval: crate::web::min_interval(min_interval),
+            proxy: proxy.clone(),
+            local_address: bind_address,
+            shell: &shell,
+        })?;
+        username_and_password.save()?;
+        Some(outcome)
+    } else {
+        None
+    };
+
+    let config_path = config::snowchains_dhall_path(&cwd, config.as_deref())?;
+
+    let mut results = vec![];
+    let mut submitted_any = false;
+
+    for problem in problems {
+        let (_, config::Language { src, .. }, _, base_dir) = config::target_and_language(
+            &cwd,
+            config.as_deref(),
+            Some(service),
+            Some(&contest),
+            Some(&problem),
+            language.as_deref(),
+            if debug {
+                config::Mode::Debug
+            } else {
+                config::Mode::Release
+            },
+        )?;
+
+        let result = if !base_dir.join(&src).exists() {
+            SubmitAllResult::NoSourceFile
+        } else if matches!(&accepted, Some(accepted) if accepted.is_accepted(&problem)) {
+            SubmitAllResult::Accepted
+        } else {
+            if submitted_any && !dry_run {
+                wait_before_submitting(
+                    &problem,
+                    interval.map_or(DEFAULT_INTERVAL, Into::into),
+                    jitter.map(Into::into),
+                    shell.progress_draw_target(),
+                );
+            }
+            submitted_any = true;
+
+            // Passed through the environment so that the credentials in the URL do not appear in
+            // the command line.
+            let mut command = std::process::Command::new(env::current_exe()?);
+            if let Some(proxy) = &proxy {
+                command.env("HTTPS_PROXY", proxy.as_str());
+            }
+
+            let status = command
+                .arg("submit")
+                .args(if no_watch { &["--no-watch"][..] } else { &[] })
+                .args(if let Some(watch_timeout) = watch_timeout {
+                    vec!["--watch-timeout".to_owned(), watch_timeout.to_string()]
+                } else {
+                    vec![]
+                })
+                .args(if no_judge { &["--no-judge"][..] } else { &[] })
+                .args(if dry_run { &["--dry-run"][..] } else { &[] })
+                .args(if debug { &["--debug"][..] } else { &[] })
+                .args(&["--display-limit", &display_limit.to_string()])
+                .args(if let Some(jobs) = jobs {
+                    vec!["--jobs".to_owned(), jobs.to_string()]
+                } else {
+                    vec![]
+                })
+                .args(if let Some(timelimit) = timelimit {
+                    vec!["--timelimit".to_owned(), timelimit.to_string()]
+                } else {
+                    vec![]
+                })
+                .args(&["--config", &config_path])
+                .args(if let Some(profile) = &profile {
+                    vec!["--profile".to_owned(), profile.clone()]
+                } else {
+                    vec![]
+                })
+                .args(if let Some(bind_address) = bind_address {
+                    vec!["--bind-address".to_owned(), bind_address.to_string()]
+                } else {
+                    vec![]
+                })
+                .args(if let Some(timeout) = timeout {
+                    vec!["--timeout".to_owned(), timeout.to_string()]
+                } else {
+                    vec![]
+                })
+                .args(if let Some(connect_timeout) = connect_timeout {
+                    vec!["--connect-timeout".to_owned(), connect_timeout.to_string()]
+                } else {
+                    vec![]
+                })
+                .args(if let Some(min_interval) = min_interval {
+                    vec!["--min-interval".to_owned(), min_interval.to_string()]
+                } else {
+                    vec![]
+                })
+                .args(if strict { &["--strict"][..] } else { &[] })
+                .args(iter::repeat("-v").take(verbose.into()))
+                .args(&["--color", &color.to_string()])
+                .args(&["-s", service.to_kebab_case_str()])
+                .args(&["-c", &contest])
+                .args(if let Some(language) = &language {
+                    vec!["-l".to_owned(), language.clone()]
+                } else {
+                    vec![]
+                })
+                .arg(&problem)
+                .status()?;
+
+            if status.success() {
+                SubmitAllResult::Submitted
+            } else {
+                SubmitAllResult::Failed
+            }
+        };
+
+        results.push((problem, result));
+    }
+
+    // Written by `judge` in the child processes.
+    let last_failures = crate::judge::read_last_failures(&workspace)?;
+
+    let rows = results
+        .iter()
+        .map(|(problem, result)| {
+            let problem_key = crate::judge::problem_key(service, Some(&*contest), problem);
+            let local_verdict = crate::judge::local_verdict(&last_failures, &problem_key);
+            (&**problem, *result, local_verdict)
+        })
+        .collect::<Vec<_>>();
+
+    writeln!(shell.stderr)?;
+    write!(shell.stderr, "{}", summary_table(&rows))?;
+    shell.stderr.flush()?;
+
+    let num_failures = results
+        .iter()
+        .filter(|(_, r)| *r == SubmitAllResult::Failed)
+        .count();
+    if num_failures > 0 {
+        bail!("{} of {} problems failed", num_failures, results.len());
+    }
+    Ok(())
+}
+
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(strum::Display, Debug, Clone, Copy, PartialEq)]
+enum SubmitAllResult {
+    Submitted,
+    Failed,
+    #[strum(serialize = "Skipped (no source file)")]
+    NoSourceFile,
+    #[strum(serialize = "Skipped (accepted)")]
+    Accepted,
+}
+
+/// Sleeps for `interval` plus a random duration of up to `jitter`, showing the countdown.
+fn wait_before_submitting(
+    problem: &str,
+    interval: Duration,
+    jitter: Option<Duration>,
+    draw_target: ProgressDrawTarget,
+) {
+    let jitter = jitter.map_or(Duration::ZERO, |jitter| {
+        rand::thread_rng().gen_range(Duration::ZERO..=jitter)
+    });
+    let deadline = Instant::now() + interval + jitter;
+
+    let progress = ProgressBar::with_draw_target(!0, draw_target);
+    progress.set_style(ProgressStyle::default_spinner().template("{prefix:.bold} {msg}"));
+    progress.set_prefix("Waiting");
+
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining == Duration::ZERO {
+            break;
+        }
+        progress.set_message(&format!(
+            "{}s before submitting `{}`",
+            (remaining.as_millis() + 999) / 1000,
+            problem,
+        ));
+        thread::sleep(remaining.min(Duration::from_secs(1)));
+    }
+
+    progress.finish_and_clear();
+}
+
+/// Returns the problems that have the test suite files in `dir`.
+fn test_suite_problems(dir: &Path) -> anyhow::Result<BTreeSet<String>> {
+    if !dir.exists() {
+        return Ok(btreeset![]);
+    }
+
+    let mut problems = btreeset![];
+
+    for entry in crate::fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        let is_test_suite = path.is_file()
+            && path
+                .extension()
+                .and_then(OsStr::to_str)
+                .map_or(false, |ext| ext.parse::<SuiteFormat>().is_ok());
+
+        if is_test_suite {
+            if let Some(stem) = path.file_stem().and_then(OsStr::to_str) {
+                problems.insert(stem.to_owned());
+            }
+        }
+    }
+
+    Ok(problems)
+}
+
+fn summary_table(rows: &[(&str, SubmitAllResult, String)]) -> Table {
+    let mut table = Table::new();
+
+    *table.get_format() = FormatBuilder::new()
+        .padding(1, 1)
+        .column_separator('│')
+        .borders('│')
+        .separator(LinePosition::Top, LineSeparator::new('─', '┬', '┌', '┐'))
+        .separator(LinePosition::Title, LineSeparator::new('─', '┼', '├', '┤'))
+        .separator(LinePosition::Bottom, LineSeparator::new('─', '┴', '└', '┘'))
+        .build();
+
+    table.set_titles(row!["Problem", "Result", "Local"]);
+
+    for (problem, result, local_verdict) in rows {
+        table.add_row(row![problem, result, local_verdict]);
+    }
+
+    table
+}
+
+/// Rejects code larger than `maxSourceSize` in `snowchains.dhall`, or the limit of the service.
+fn check_source_size(
+    code_size: usize,
+    configured_limit: Option<u64>,
+    service: PlatformKind,
+) -> anyhow::Result<()> {
+    let (limit, origin) = match (configured_limit, default_max_source_size(service)) {
+        (Some(limit), _) => (limit, "`maxSourceSize` in `snowchains.dhall`".to_owned()),
+        (None, Some(limit)) => (
+            limit,
+            format!(
+                "the built-in limit for {}. Set `maxSourceSize` in `snowchains.dhall` to change it",
+                service.to_kebab_case_str(),
+            ),
+        ),
+        (None, None) => return Ok(()),
+    };
+
+    if code_size as u64 > limit {
+        bail!(
+            "The code is {} bytes, which exceeds the limit of {} bytes ({})",
+            code_size,
+            limit,
+            origin,
+        );
+    }
+    Ok(())
+}
+
+fn default_max_source_size(service: PlatformKind) -> Option<u64> {
+    match service {
+        PlatformKind::Atcoder => Some(512 * 1024),
+        PlatformKind::Codeforces => Some(64 * 1024),
+        PlatformKind::Yukicoder | PlatformKind::Other => None,
+    }
+}
+
+fn read_clipboard() -> anyhow::Result<String> {
+    let code = arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_text())
+        .with_context(|| "Could not read the clipboard")?;
+
+    if code.trim().is_empty() {
+        bail!("The clipboard is empty");
+    }
+    Ok(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use maplit::btreeset;
+    use snowchains_core::web::PlatformKind;
+
+    #[test]
+    fn check_source_size() {
+        assert!(super::check_source_size(512 * 1024, None, PlatformKind::Atcoder).is_ok());
+        assert!(super::check_source_size(usize::MAX, None, PlatformKind::Yukicoder).is_ok());
+
+        let err = super::check_source_size(512 * 1024 + 1, None, PlatformKind::Atcoder)
+            .unwrap_err()
+            .to_string();
+        assert!(err.starts_with("The code is 524289 bytes, which exceeds the limit of 524288"));
+        assert!(err.contains("the built-in limit for atcoder"));
+
+        let err = super::check_source_size(101, Some(100), PlatformKind::Yukicoder)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("(`maxSourceSize` in `snowchains.dhall`)"));
+    }
+
+    #[test]
+    fn test_suite_problems() -> anyhow::Result<()> {
+        let tempdir = tempfile::Builder::new()
+            .prefix("snowchains-submit-test-")
+            .tempdir()?;
+
+        for name in &["a.yml", "b.json", "c.toml", "c.yml", "d.txt"] {
+            crate::fs::write(tempdir.path().join(name), "", false)?;
+        }
+        crate::fs::create_dir_all(tempdir.path().join("a"))?;
+
+        assert_eq!(
+            btreeset!["a".to_owned(), "b".to_owned(), "c".to_owned()],
+            super::test_suite_problems(tempdir.path())?,
+        );
+        assert!(super::test_suite_problems(&tempdir.path().join("nonexistent"))?.is_empty());
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
-use anyhow::Context as _;
+use crate::commands::{CommonOpt, WebOpt};
+use anyhow::{bail, Context as _};
 use snowchains_core::web::{
     Atcoder, AtcoderWatchSubmissionsCredentials, AtcoderWatchSubmissionsTarget, CookieStorage,
     PlatformKind, WatchSubmissions,
@@ -10,10 +11,20 @@ use termcolor::WriteColor;
 
 #[derive(StructOpt, Debug)]
 pub struct OptWatchSubmissions {
-    /// Path to `snowchains.dhall`
+    /// Path to `snowchains.dhall` [default: `$SNOWCHAINS_CONFIG` or the one in the ancestors]
     #[structopt(long)]
     pub config: Option<PathBuf>,
 
+    /// Uses the cookies and the tokens of the profile instead of the default ones
+    #[structopt(long, value_name("NAME"))]
+    pub profile: Option<String>,
+
+    #[structopt(flatten)]
+    pub web: WebOpt,
+
+    #[structopt(flatten)]
+    pub common: CommonOpt,
+
     /// Coloring
     #[structopt(
         long,
@@ -37,6 +48,16 @@ pub(crate) fn run(
 ) -> anyhow::Result<()> {
     let OptWatchSubmissions {
         config,
+        profile,
+        web:
+            WebOpt {
+                proxy,
+                bind_address,
+                timeout,
+                connect_timeout,
+                min_interval,
+            },
+        common: _,
         color: _,
         service,
         contest,
@@ -54,8 +75,13 @@ pub(crate) fn run(
         })??;
     let contest = contest.or(detected_target.contest);
 
-    let cookie_storage = CookieStorage::with_jsonl(crate::web::credentials::cookie_store_path()?)?;
-    let timeout = Some(crate::web::SESSION_TIMEOUT);
+    let cookie_storage = CookieStorage::with_jsonl(crate::web::credentials::cookie_store_path(
+        service,
+        profile.as_deref(),
+    )?)?;
+    let (timeout, connect_timeout) = crate::web::timeouts(timeout, connect_timeout);
+    let min_interval = crate::web::min_interval(min_interval);
+    let proxy = crate::web::proxy(proxy)?;
 
     match service {
         PlatformKind::Atcoder => {
@@ -65,10 +91,11 @@ pub(crate) fn run(
 
             let shell = RefCell::new(&mut shell);
 
+            let mut username_and_password =
+                crate::web::credentials::atcoder_username_and_password(&shell, profile.as_deref());
+
             let credentials = AtcoderWatchSubmissionsCredentials {
-                username_and_password: &mut crate::web::credentials::atcoder_username_and_password(
-                    &shell,
-                ),
+                username_and_password: &mut || username_and_password.get(),
             };
 
             Atcoder::exec(WatchSubmissions {
@@ -76,10 +103,16 @@ pub(crate) fn run(
                 credentials,
                 cookie_storage,
                 timeout,
+                connect_timeout,
+                min_interval,
+                proxy,
+                local_address: bind_address,
                 shell: &shell,
-            })
+            })?;
+            username_and_password.save()
         }
         PlatformKind::Codeforces => todo!(),
         PlatformKind::Yukicoder => todo!(),
+        PlatformKind::Other => bail!("`other` does not support `watch submissions`"),
     }
 }
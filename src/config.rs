@@ -7,13 +7,17 @@ use heck::{CamelCase as _, KebabCase as _, MixedCase as _, SnakeCase as _};
 use indexmap::IndexMap;
 use itertools::Itertools as _;
 use maplit::hashmap;
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
 use serde_dhall::{SimpleType, StaticType};
-use snowchains_core::web::PlatformKind;
+use snowchains_core::{
+    testsuite::PositiveFinite,
+    web::{OtherScraper, OtherScraperConfig, PlatformKind},
+};
 use std::{
     collections::BTreeMap,
     convert::Infallible,
-    fmt,
+    env, fmt,
+    num::NonZeroUsize,
     path::{Path, PathBuf},
 };
 
@@ -38,7 +42,7 @@ pub(crate) fn target_and_language(
     cli_opt_problem: Option<&str>,
     cli_opt_language: Option<&str>,
     cli_opt_mode: Mode,
-) -> anyhow::Result<(Target, Language, PathBuf)> {
+) -> anyhow::Result<(Target, Language, TopLevel, PathBuf)> {
     let path = find_snowchains_dhall(cwd, rel_path)?;
 
     let (target, language_name) = Detected::load_and_eval(cwd, &path)?.merge_with_cli_options(
@@ -49,13 +53,23 @@ pub(crate) fn target_and_language(
         cli_opt_mode,
     )?;
 
-    let mut languages = serde_dhall::from_str(&format!(
-        "let target = {} let config = {} in config.languages target",
-        target.to_dhall_expr(),
-        path,
-    ))
-    .parse::<BTreeMap<String, Language>>()
-    .with_context(|| format!("Could not evaluate `{}`", path))?;
+    let dir = Path::new(&path)
+        .parent()
+        .unwrap_or_else(|| path.as_ref())
+        .to_owned();
+
+    let languages_as = if target.service == PlatformKind::Other {
+        other_service(&dir)?.languages_as.with_context(|| {
+            format!(
+                "`languages_as` in `{}` is required to evaluate `languages` for `other`",
+                other_service_path(&dir).display(),
+            )
+        })?
+    } else {
+        target.service
+    };
+
+    let (mut languages, top_level) = eval_languages(&path, &target, languages_as)?;
 
     let expected_names = languages.keys().join(", ");
 
@@ -66,21 +80,141 @@ pub(crate) fn target_and_language(
         )
     })?;
 
-    let dir = Path::new(&path)
-        .parent()
-        .unwrap_or_else(|| path.as_ref())
-        .to_owned();
+    let language = top_level.languageDefaults.clone().apply(language);
+
+    Ok((target, language, top_level, dir))
+}
+
+/// Evaluates the optional top-level fields of `snowchains.dhall`.
+pub(crate) fn top_level(cwd: &Path, rel_path: Option<&Path>) -> anyhow::Result<TopLevel> {
+    let path = find_snowchains_dhall(cwd, rel_path)?;
+    eval_top_level(&path)
+}
+
+/// Returns the path of `snowchains.dhall` in use, to pass it down to child processes.
+pub(crate) fn snowchains_dhall_path(cwd: &Path, rel_path: Option<&Path>) -> anyhow::Result<String> {
+    find_snowchains_dhall(cwd, rel_path)
+}
+
+/// Evaluates `languages` for the target, and the top-level fields in the same evaluation.
+fn eval_languages(
+    path: &str,
+    target: &Target,
+    languages_as: PlatformKind,
+) -> anyhow::Result<(BTreeMap<String, RawLanguage>, TopLevel)> {
+    #[allow(non_snake_case)] // `topLevel`
+    #[derive(Deserialize)]
+    struct Evaluated {
+        languages: BTreeMap<String, RawLanguage>,
+        topLevel: TopLevel,
+    }
+
+    let Evaluated {
+        languages,
+        topLevel: top_level,
+    } = serde_dhall::from_str(&format!(
+        "let target = {} let config = {} // {} in {{ languages = config.languages target, \
+         topLevel = {} }}",
+        target.to_dhall_expr(languages_as),
+        TopLevel::DEFAULTS,
+        path,
+        TopLevel::PROJECTION,
+    ))
+    .parse()
+    .with_context(|| format!("Could not evaluate `{}`", path))?;
+
+    Ok((languages, top_level))
+}
 
-    Ok((target, language, dir))
+fn eval_top_level(path: &str) -> anyhow::Result<TopLevel> {
+    serde_dhall::from_str(&format!(
+        "let config = {} // {} in {}",
+        TopLevel::DEFAULTS,
+        path,
+        TopLevel::PROJECTION,
+    ))
+    .parse()
+    .with_context(|| format!("Could not evaluate the top-level fields of `{}`", path))
+}
+
+/// Evaluates each part of `snowchains.dhall`, and returns the path and the problems found.
+///
+/// `languages` is evaluated for each service and mode with a dummy contest and problem.
+pub(crate) fn check(cwd: &Path, rel_path: Option<&Path>) -> anyhow::Result<(String, Vec<String>)> {
+    let path = find_snowchains_dhall(cwd, rel_path)?;
+    let dir = Path::new(&path).parent().unwrap_or_else(|| path.as_ref());
+
+    let mut problems = vec![];
+
+    if let Err(err) = Detected::load_and_eval(cwd, &path) {
+        problems.push(format!("{:#}", err));
+    }
+
+    let language_defaults = eval_top_level(&path)
+        .map(|top_level| top_level.languageDefaults)
+        .map_err(|err| problems.push(format!("{:#}", err)))
+        .ok();
+
+    for &service in &[
+        PlatformKind::Atcoder,
+        PlatformKind::Codeforces,
+        PlatformKind::Yukicoder,
+    ] {
+        for &mode in &[Mode::Debug, Mode::Release] {
+            let target = Target {
+                service,
+                contest: Some("contest".to_owned()),
+                problem: "a".to_owned(),
+                mode,
+            };
+
+            let languages = match eval_languages(&path, &target, service) {
+                Ok((languages, _)) => languages,
+                Err(err) => {
+                    problems.push(format!(
+                        "`languages` for {} in {:?} mode: {:#}",
+                        service.to_kebab_case_str(),
+                        mode,
+                        err,
+                    ));
+                    continue;
+                }
+            };
+
+            for (name, language) in languages {
+                let language = language_defaults
+                    .clone()
+                    .unwrap_or_default()
+                    .apply(language);
+
+                if let Some(template) = &language.template {
+                    let template = Path::new(template);
+                    let template = dir.join(template.strip_prefix(".").unwrap_or(template));
+                    if !template.exists() {
+                        problems.push(format!(
+                            "The `template` of `{}` for {} does not exist: `{}`",
+                            name,
+                            service.to_kebab_case_str(),
+                            template.display(),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    if let Err(err) = eval_xtasks(&path) {
+        problems.push(format!("{:#}", err));
+    }
+
+    problems.dedup();
+    Ok((path, problems))
 }
 
 pub(crate) fn xtask(cwd: &Path, rel_path: Option<&Path>, name: &str) -> anyhow::Result<Script> {
     let path = find_snowchains_dhall(cwd, rel_path)?;
 
-    let xtask = serde_dhall::from_str(&format!("let config = {} in config.xtask", path))
-        .type_annotation(&map_annot(SimpleType::Text, Script::static_type()))
-        .parse::<IndexMap<String, _>>()
-        .with_context(|| format!("Could not evalute `{}`", path))?;
+    let xtask = eval_xtasks(&path)?;
 
     xtask.get(name).cloned().with_context(|| {
         format!(
@@ -93,23 +227,72 @@ pub(crate) fn xtask(cwd: &Path, rel_path: Option<&Path>, name: &str) -> anyhow::
     })
 }
 
+fn eval_xtasks(path: &str) -> anyhow::Result<IndexMap<String, Script>> {
+    serde_dhall::from_str(&format!("let config = {} in config.xtask", path))
+        .type_annotation(&map_annot(SimpleType::Text, Script::static_type()))
+        .parse()
+        .with_context(|| format!("Could not evalute `{}`", path))
+}
+
+pub(crate) struct OtherService {
+    pub(crate) scraper: OtherScraper,
+    pub(crate) languages_as: Option<PlatformKind>,
+}
+
+/// Loads `.snowchains/other-service.yml`, which defines how to scrape the `other` service.
+pub(crate) fn other_service(base_dir: &Path) -> anyhow::Result<OtherService> {
+    #[derive(Deserialize)]
+    struct OtherServiceFile {
+        #[serde(default)]
+        languages_as: Option<String>,
+        #[serde(flatten)]
+        scraper: OtherScraperConfig,
+    }
+
+    let path = other_service_path(base_dir);
+
+    ensure!(
+        path.exists(),
+        "`--service other` requires `{}`",
+        path.display(),
+    );
+
+    let OtherServiceFile {
+        languages_as,
+        scraper,
+    } = crate::fs::read_yaml(&path)?;
+
+    let invalid_config = || format!("Invalid config: `{}`", path.display());
+
+    let languages_as = languages_as
+        .map(|s| match s.parse() {
+            Ok(PlatformKind::Other) | Err(_) => Err(anyhow!(
+                "`languages_as`: expected one of [atcoder, codeforces, yukicoder], got {:?}",
+                s,
+            )),
+            Ok(service) => Ok(service),
+        })
+        .transpose()
+        .with_context(invalid_config)?;
+
+    let scraper = OtherScraper::new(scraper).with_context(invalid_config)?;
+
+    Ok(OtherService {
+        scraper,
+        languages_as,
+    })
+}
+
+fn other_service_path(base_dir: &Path) -> PathBuf {
+    base_dir.join(".snowchains").join("other-service.yml")
+}
+
 fn find_snowchains_dhall(cwd: &Path, rel_path: Option<&Path>) -> anyhow::Result<String> {
-    let path = if let Some(rel_path) = rel_path {
-        let rel_path = rel_path.strip_prefix(".").unwrap_or(rel_path);
-        let path = cwd.join(rel_path);
-        ensure!(path.exists(), "`{}` does not exist", path.display());
-        path
-    } else {
-        cwd.ancestors()
-            .map(|p| p.join("snowchains.dhall"))
-            .find(|p| p.exists())
-            .with_context(|| {
-                format!(
-                    "Could not find `snowchains.dhall` in `{}` or any parent directory",
-                    cwd.display(),
-                )
-            })?
-    };
+    let env_path = env::var_os("SNOWCHAINS_CONFIG")
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from);
+
+    let path = locate_snowchains_dhall(cwd, rel_path, env_path.as_deref())?;
 
     let path = path
         .into_os_string()
@@ -126,6 +309,42 @@ fn find_snowchains_dhall(cwd: &Path, rel_path: Option<&Path>) -> anyhow::Result<
     Ok(path)
 }
 
+/// Returns `--config`, `$SNOWCHAINS_CONFIG`, or the nearest `snowchains.dhall` in the ancestors of
+/// `cwd` in this order.
+fn locate_snowchains_dhall(
+    cwd: &Path,
+    cli_path: Option<&Path>,
+    env_path: Option<&Path>,
+) -> anyhow::Result<PathBuf> {
+    let explicit = |path: &Path, specified_with: &str| -> _ {
+        let path = cwd.join(path.strip_prefix(".").unwrap_or(path));
+        ensure!(
+            path.exists(),
+            "`{}` (specified with {}) does not exist",
+            path.display(),
+            specified_with,
+        );
+        Ok(path)
+    };
+
+    if let Some(cli_path) = cli_path {
+        return explicit(cli_path, "`--config`");
+    }
+    if let Some(env_path) = env_path {
+        return explicit(env_path, "`$SNOWCHAINS_CONFIG`");
+    }
+
+    cwd.ancestors()
+        .map(|p| p.join("snowchains.dhall"))
+        .find(|p| p.exists())
+        .with_context(|| {
+            format!(
+                "Could not find `snowchains.dhall` in `{}` or any parent directory",
+                cwd.display(),
+            )
+        })
+}
+
 fn quote(s: impl AsRef<str>) -> impl fmt::Display {
     InterpolatedText::<Infallible>::from(s.as_ref().to_owned())
 }
@@ -263,17 +482,119 @@ pub(crate) struct Script {
     pub(crate) content: String,
 }
 
-#[allow(non_snake_case)] // for `StaticType`
-#[derive(Debug, Deserialize, StaticType)]
+#[allow(non_snake_case)] // `languageId`
+#[derive(Debug)]
 pub(crate) struct Language {
     pub(crate) src: String,
     pub(crate) transpile: Option<Compile>,
     pub(crate) compile: Option<Compile>,
     pub(crate) run: Command,
     pub(crate) languageId: Option<String>,
+    pub(crate) env: Option<BTreeMap<String, String>>,
+    /// Path to a starter template for `generate`, relative to the directory of
+    /// `snowchains.dhall`.
+    pub(crate) template: Option<String>,
+    /// Scales the timelimits of the test cases in `judge`. Not applied to `--timelimit`.
+    pub(crate) timelimitMultiplier: Option<PositiveFinite<f64>>,
+    /// Command whose standard output is submitted instead of `src` in `submit`.
+    pub(crate) bundle: Option<Command>,
 }
 
-#[derive(Debug, Deserialize, StaticType)]
+/// `Language` as written in `snowchains.dhall`.
+///
+/// Each optional field is `None` if it is missing, and `Some(None)` if it is explicitly `None`.
+#[allow(non_snake_case)] // `languageId`
+#[derive(Debug, Deserialize)]
+struct RawLanguage {
+    src: String,
+    #[serde(default, deserialize_with = "present")]
+    transpile: Option<Option<Compile>>,
+    #[serde(default, deserialize_with = "present")]
+    compile: Option<Option<Compile>>,
+    run: Command,
+    #[serde(default, deserialize_with = "present")]
+    languageId: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present")]
+    env: Option<Option<BTreeMap<String, String>>>,
+    #[serde(default, deserialize_with = "present")]
+    template: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present")]
+    timelimitMultiplier: Option<Option<PositiveFinite<f64>>>,
+    #[serde(default, deserialize_with = "present")]
+    bundle: Option<Option<Command>>,
+}
+
+fn present<'de, T: Deserialize<'de>, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Option<T>>, D::Error> {
+    Option::deserialize(deserializer).map(Some)
+}
+
+/// The optional top-level fields of `snowchains.dhall`.
+#[allow(non_snake_case)] // `maxSourceSize` and `languageDefaults`
+#[derive(Debug, Deserialize)]
+pub(crate) struct TopLevel {
+    /// `Some 0` means the number of logical CPUs, which is the same as `None`.
+    jobs: Option<u64>,
+    /// In bytes.
+    pub(crate) maxSourceSize: Option<u64>,
+    pub(crate) browser: Option<String>,
+    languageDefaults: LanguageDefaults,
+}
+
+impl TopLevel {
+    /// Fills the fields missing in the configs that are not annotated with `Config`.
+    const DEFAULTS: &'static str = "{ jobs = None Natural, maxSourceSize = None Natural, \
+                                    browser = None Text, languageDefaults = {=} }";
+
+    const PROJECTION: &'static str = "{ jobs = config.jobs, maxSourceSize = config.maxSourceSize, \
+                                      browser = config.browser, \
+                                      languageDefaults = config.languageDefaults }";
+
+    pub(crate) fn jobs(&self) -> Option<NonZeroUsize> {
+        self.jobs.and_then(|jobs| NonZeroUsize::new(jobs as _))
+    }
+}
+
+/// The optional top-level `languageDefaults`.
+///
+/// Each field fills the one of the languages that is missing. An explicit `None` in a language
+/// opts out of the default.
+#[allow(non_snake_case)] // `languageId`
+#[derive(Debug, Default, Deserialize, Clone)]
+struct LanguageDefaults {
+    transpile: Option<Compile>,
+    compile: Option<Compile>,
+    languageId: Option<String>,
+    env: Option<BTreeMap<String, String>>,
+    template: Option<String>,
+    timelimitMultiplier: Option<PositiveFinite<f64>>,
+    bundle: Option<Command>,
+}
+
+impl LanguageDefaults {
+    fn apply(self, language: RawLanguage) -> Language {
+        macro_rules! fill(($($field:ident),*) => {
+            Language {
+                src: language.src,
+                run: language.run,
+                $($field: language.$field.unwrap_or(self.$field),)*
+            }
+        });
+
+        fill!(
+            transpile,
+            compile,
+            languageId,
+            env,
+            template,
+            timelimitMultiplier,
+            bundle
+        )
+    }
+}
+
+#[derive(Debug, Deserialize, StaticType, Clone)]
 pub(crate) struct Compile {
     pub(crate) command: Command,
     pub(crate) output: String,
@@ -288,7 +609,8 @@ pub(crate) struct Target {
 }
 
 impl Target {
-    fn to_dhall_expr(&self) -> String {
+    /// `service` replaces `self.service`, since `Other` is not a part of `Service` in Dhall.
+    fn to_dhall_expr(&self, service: PlatformKind) -> String {
         format!(
             r"let Service = < Atcoder | Codeforces | Yukicoder >
 
@@ -316,7 +638,7 @@ in  {{ service = Service.{}
     , mode = Mode.{}
     }}
 ",
-            self.service.to_pascal_case_str(),
+            service.to_pascal_case_str(),
             if let Some(contest) = &self.contest {
                 format!(
                     r"Some {{ lowercase =  {}, uppercase =  {}, snakeCase =  {}, kebabCase =  {}, mixedCase =  {}, pascalCase = {} }}",
@@ -349,3 +671,105 @@ pub(crate) enum Mode {
     Debug,
     Release,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    #[test]
+    fn locate_snowchains_dhall() -> anyhow::Result<()> {
+        let tempdir = tempfile::Builder::new()
+            .prefix("snowchains-config-test-")
+            .tempdir()?;
+        let root = tempdir.path();
+
+        std::fs::create_dir_all(root.join("contest").join("a"))?;
+        std::fs::write(root.join("snowchains.dhall"), "")?;
+        std::fs::write(root.join("other.dhall"), "")?;
+
+        let locate = |cwd: &Path, cli_path: Option<&str>, env_path: Option<&str>| {
+            super::locate_snowchains_dhall(cwd, cli_path.map(Path::new), env_path.map(Path::new))
+        };
+
+        let cwd = root.join("contest").join("a");
+
+        assert_eq!(root.join("snowchains.dhall"), locate(&cwd, None, None)?);
+        assert_eq!(
+            root.join("other.dhall"),
+            locate(root, None, Some("./other.dhall"))?,
+        );
+        assert_eq!(
+            root.join("snowchains.dhall"),
+            locate(root, Some("snowchains.dhall"), Some("other.dhall"))?,
+        );
+
+        let err = locate(root, None, Some("nonexistent.dhall")).unwrap_err();
+        assert!(err
+            .to_string()
+            .ends_with("(specified with `$SNOWCHAINS_CONFIG`) does not exist"));
+        Ok(())
+    }
+
+    #[test]
+    fn language_defaults() -> anyhow::Result<()> {
+        let defaults = serde_dhall::from_str(
+            r#"{ env = Some (toMap { RUST_BACKTRACE = "1" }), languageId = Some "4003",
+                 template = Some "./template.rs" }"#,
+        )
+        .parse::<super::LanguageDefaults>()?;
+
+        let language = |fields: &str| -> anyhow::Result<_> {
+            let language = serde_dhall::from_str(&format!(
+                r#"{{ src = "a.rs"
+                    , run = < Args : List Text
+                            | Script : {{ program : Text, extension : Text, content : Text }}
+                            >.Args [ "./a" ]
+                    {}
+                    }}"#,
+                fields,
+            ))
+            .parse::<super::RawLanguage>()?;
+            Ok(defaults.clone().apply(language))
+        };
+
+        let filled = language(r#", languageId = Some "4050""#)?;
+        assert_eq!(Some("4050"), filled.languageId.as_deref());
+        assert_eq!(
+            Some(&maplit::btreemap!("RUST_BACKTRACE".to_owned() => "1".to_owned())),
+            filled.env.as_ref(),
+        );
+        assert_eq!(Some("./template.rs"), filled.template.as_deref());
+        assert!(filled.compile.is_none());
+
+        let opted_out = language(", languageId = None Text, template = None Text")?;
+        assert_eq!(None, opted_out.languageId);
+        assert_eq!(None, opted_out.template);
+        assert!(opted_out.env.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn top_level() -> anyhow::Result<()> {
+        let tempdir = tempfile::Builder::new()
+            .prefix("snowchains-config-test-")
+            .tempdir()?;
+
+        let path = tempdir.path().join("snowchains.dhall");
+        let path = path.to_str().unwrap();
+
+        std::fs::write(path, "{ jobs = Some 2, browser = Some \"firefox\" }")?;
+        let top_level = super::eval_top_level(path)?;
+        assert_eq!(2, top_level.jobs().unwrap().get());
+        assert_eq!(None, top_level.maxSourceSize);
+        assert_eq!(Some("firefox"), top_level.browser.as_deref());
+
+        std::fs::write(
+            path,
+            "{ jobs = Some 0, languageDefaults = { languageId = Some \"1\" } }",
+        )?;
+        let top_level = super::eval_top_level(path)?;
+        assert_eq!(None, top_level.jobs());
+        assert_eq!(Some("1"), top_level.languageDefaults.languageId.as_deref());
+        Ok(())
+    }
+}
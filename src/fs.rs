@@ -1,6 +1,9 @@
 use anyhow::Context as _;
 use serde::{de::DeserializeOwned, Serialize};
-use std::{fs::Metadata, path::Path};
+use std::{
+    fs::{Metadata, ReadDir},
+    path::Path,
+};
 
 pub(crate) fn metadata(path: impl AsRef<Path>) -> anyhow::Result<Metadata> {
     let path = path.as_ref();
@@ -13,6 +16,11 @@ pub(crate) fn read_to_string(path: impl AsRef<Path>) -> anyhow::Result<String> {
     std::fs::read_to_string(path).with_context(|| format!("Could not read `{}`", path.display()))
 }
 
+pub(crate) fn read_dir(path: impl AsRef<Path>) -> anyhow::Result<ReadDir> {
+    let path = path.as_ref();
+    std::fs::read_dir(path).with_context(|| format!("Could not read `{}`", path.display()))
+}
+
 pub(crate) fn read_json<T: DeserializeOwned, P: AsRef<Path>>(path: P) -> anyhow::Result<T> {
     let path = path.as_ref();
     let content = read_to_string(path)?;
@@ -27,6 +35,13 @@ pub(crate) fn read_yaml<T: DeserializeOwned, P: AsRef<Path>>(path: P) -> anyhow:
         .with_context(|| format!("Could not parse the YAML at `{}`", path.display()))
 }
 
+pub(crate) fn read_toml<T: DeserializeOwned, P: AsRef<Path>>(path: P) -> anyhow::Result<T> {
+    let path = path.as_ref();
+    let content = read_to_string(path)?;
+    toml::from_str(&content)
+        .with_context(|| format!("Could not parse the TOML at `{}`", path.display()))
+}
+
 pub(crate) fn write(
     path: impl AsRef<Path>,
     contents: impl AsRef<[u8]>,
@@ -50,6 +65,22 @@ pub(crate) fn write_json(
     write(path, serde_json::to_string(&value)?, create_dir_all)
 }
 
+pub(crate) fn rename(from: impl AsRef<Path>, to: impl AsRef<Path>) -> anyhow::Result<()> {
+    let (from, to) = (from.as_ref(), to.as_ref());
+    std::fs::rename(from, to)
+        .with_context(|| format!("Could not rename `{}` to `{}`", from.display(), to.display()))
+}
+
+pub(crate) fn remove_file(path: impl AsRef<Path>) -> anyhow::Result<()> {
+    let path = path.as_ref();
+    std::fs::remove_file(path).with_context(|| format!("Could not remove `{}`", path.display()))
+}
+
+pub(crate) fn remove_dir_all(path: impl AsRef<Path>) -> anyhow::Result<()> {
+    let path = path.as_ref();
+    std::fs::remove_dir_all(path).with_context(|| format!("Could not remove `{}`", path.display()))
+}
+
 pub(crate) fn create_dir_all(path: impl AsRef<Path>) -> anyhow::Result<()> {
     std::fs::create_dir_all(&path)
         .with_context(|| format!("Could not create `{}`", path.as_ref().display()))
@@ -1,23 +1,38 @@
 use crate::config;
-use anyhow::bail;
+use anyhow::{anyhow, bail, Context as _};
 use az::SaturatingAs as _;
 use human_size::{Byte, Size};
 use indicatif::ProgressDrawTarget;
 use itertools::Itertools as _;
-use maplit::btreemap;
+use notify::{DebouncedEvent, RecommendedWatcher, RecursiveMode, Watcher as _};
 use snowchains_core::{
-    color_spec, judge::CommandExpression, testsuite::TestSuite, web::PlatformKind,
+    color_spec,
+    judge::{CommandExpression, JudgeOptions, PrintPrettyOptions, Verdict},
+    testsuite::{
+        BatchTestCase, BatchTestSuite, ExtraOutput, InteractiveTestCase, PositiveFinite, TestSuite,
+    },
+    web::PlatformKind,
 };
 use std::{
-    collections::HashSet,
+    collections::{BTreeMap, BTreeSet, HashSet},
     ffi::OsStr,
-    io::Write as _,
+    fmt,
+    io::{self, Write as _},
     iter, mem,
+    num::NonZeroUsize,
     ops::Deref,
     path::{Path, PathBuf},
     process::Stdio,
+    str::FromStr,
+    sync::{
+        atomic::{self, AtomicBool},
+        mpsc,
+    },
+    thread,
+    time::Duration,
 };
-use termcolor::{Color, WriteColor};
+use strum::{EnumString, EnumVariantNames};
+use termcolor::{Color, NoColor, WriteColor};
 
 pub(crate) struct Args<W1, W2> {
     pub(crate) stdout: W1,
@@ -25,7 +40,8 @@ pub(crate) struct Args<W1, W2> {
     pub(crate) stdin_process_redirection: fn() -> Stdio,
     pub(crate) stdout_process_redirection: fn() -> Stdio,
     pub(crate) stderr_process_redirection: fn() -> Stdio,
-    pub(crate) progress_draw_target: ProgressDrawTarget,
+    pub(crate) progress_draw_target: fn() -> ProgressDrawTarget,
+    pub(crate) strict: bool,
     pub(crate) base_dir: PathBuf,
     pub(crate) service: PlatformKind,
     pub(crate) contest: Option<String>,
@@ -34,8 +50,138 @@ pub(crate) struct Args<W1, W2> {
     pub(crate) transpile: Option<config::Compile>,
     pub(crate) compile: Option<config::Compile>,
     pub(crate) run: config::Command,
+    pub(crate) env: Option<BTreeMap<String, String>>,
     pub(crate) test_case_names: Option<HashSet<String>>,
     pub(crate) display_limit: Size,
+    pub(crate) diff_only: bool,
+    pub(crate) failed_first: bool,
+    pub(crate) only_failing: bool,
+    pub(crate) show_stderr: bool,
+    pub(crate) sort_by_time: bool,
+    pub(crate) save_output: Option<PathBuf>,
+    pub(crate) memory_budget: Option<Size>,
+    pub(crate) memory_per_case: Option<Size>,
+    pub(crate) repeat: Option<NonZeroUsize>,
+    pub(crate) tolerate_extra_output: Option<ExtraOutput>,
+    pub(crate) jobs: Option<NonZeroUsize>,
+    pub(crate) timelimit: Option<TimelimitOverride>,
+    pub(crate) timelimit_multiplier: Option<PositiveFinite<f64>>,
+    pub(crate) memory_limit: Option<u64>,
+    pub(crate) format: OutputFormat,
+    pub(crate) output: Option<PathBuf>,
+}
+
+/// `--format`.
+#[derive(EnumVariantNames, EnumString, strum::Display, Debug, Clone, Copy)]
+#[strum(serialize_all = "lowercase")]
+pub enum OutputFormat {
+    Pretty,
+    Tap,
+    Junit,
+}
+
+/// `--suite-format`.
+#[derive(EnumVariantNames, EnumString, strum::Display, Debug, Clone, Copy, PartialEq)]
+#[strum(serialize_all = "lowercase")]
+pub enum SuiteFormat {
+    Yml,
+    Yaml,
+    Json,
+    Toml,
+}
+
+impl SuiteFormat {
+    /// Formats in the order that [`find_test_suite`] looks for.
+    const ALL: [Self; 4] = [Self::Yml, Self::Yaml, Self::Json, Self::Toml];
+
+    pub(crate) fn serialize(self, test_suite: &TestSuite) -> String {
+        match self {
+            Self::Yml | Self::Yaml => test_suite.to_yaml_pretty(),
+            Self::Json => serde_json::to_string_pretty(test_suite).expect("should not fail") + "\n",
+            // Goes through `toml::Value` so that the tables come after the other values.
+            Self::Toml => toml::Value::try_from(test_suite)
+                .and_then(|value| toml::to_string_pretty(&value))
+                .expect("should not fail"),
+        }
+    }
+}
+
+/// Returns the path to the test suite file for `problem` in `dir`.
+///
+/// Looks for `{problem}.yml`, `{problem}.yaml`, `{problem}.json`, and `{problem}.toml`, and returns
+/// the `.yml` one if none of them exists. Fails if more than one of them exist.
+pub(crate) fn find_test_suite(dir: &Path, problem: &str) -> anyhow::Result<PathBuf> {
+    let path = |format: SuiteFormat| dir.join(problem).with_extension(format.to_string());
+
+    let found = SuiteFormat::ALL
+        .iter()
+        .map(|&format| path(format))
+        .filter(|path| path.exists())
+        .collect::<Vec<_>>();
+
+    match &*found {
+        [] => Ok(path(SuiteFormat::Yml)),
+        [found] => Ok(found.clone()),
+        found => bail!(
+            "Found multiple test suites for `{}`: {}. Remove all but one of them",
+            problem,
+            found
+                .iter()
+                .map(|path| format!("`{}`", path.display()))
+                .format(", "),
+        ),
+    }
+}
+
+/// Returns the existing test suite files that are the same as `path` except for the format.
+pub(crate) fn other_test_suites(path: &Path, format: SuiteFormat) -> Vec<PathBuf> {
+    SuiteFormat::ALL
+        .iter()
+        .filter(|&&other| other != format)
+        .map(|other| path.with_extension(other.to_string()))
+        .filter(|path| path.exists())
+        .collect()
+}
+
+/// Multiplies `timelimit` by the `timelimitMultiplier` of the language.
+fn scale_timelimit(timelimit: Duration, multiplier: PositiveFinite<f64>) -> Duration {
+    timelimit.mul_f64(multiplier.get())
+}
+
+pub(crate) fn read_test_suite(path: &Path) -> anyhow::Result<TestSuite> {
+    match path.extension().and_then(OsStr::to_str) {
+        Some("json") => crate::fs::read_json(path),
+        Some("toml") => crate::fs::read_toml(path),
+        _ => crate::fs::read_yaml(path),
+    }
+}
+
+/// `--timelimit`. `0` and `none` disable the timelimit.
+#[derive(Debug, Clone, Copy)]
+pub struct TimelimitOverride(Option<Duration>);
+
+impl FromStr for TimelimitOverride {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let timelimit = if s.eq_ignore_ascii_case("none") {
+            None
+        } else if let Ok(millis) = s.parse::<u64>() {
+            Some(Duration::from_millis(millis))
+        } else {
+            Some(humantime::parse_duration(s)?)
+        };
+        Ok(Self(timelimit.filter(|t| *t > Duration::from_secs(0))))
+    }
+}
+
+impl fmt::Display for TimelimitOverride {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(timelimit) => write!(f, "{}", timelimit.as_millis()),
+            None => write!(f, "none"),
+        }
+    }
 }
 
 pub(crate) fn judge(args: Args<impl WriteColor, impl WriteColor>) -> anyhow::Result<()> {
@@ -46,6 +192,7 @@ pub(crate) fn judge(args: Args<impl WriteColor, impl WriteColor>) -> anyhow::Res
         stdout_process_redirection,
         stderr_process_redirection,
         progress_draw_target,
+        strict,
         base_dir,
         service,
         contest,
@@ -54,8 +201,25 @@ pub(crate) fn judge(args: Args<impl WriteColor, impl WriteColor>) -> anyhow::Res
         transpile,
         compile,
         run,
+        env,
         test_case_names,
         display_limit,
+        diff_only,
+        failed_first,
+        only_failing,
+        show_stderr,
+        sort_by_time,
+        save_output,
+        memory_budget,
+        memory_per_case,
+        repeat,
+        tolerate_extra_output,
+        jobs,
+        timelimit,
+        timelimit_multiplier,
+        memory_limit,
+        format,
+        output,
     } = args;
 
     let test_suite_dir = base_dir
@@ -63,23 +227,92 @@ pub(crate) fn judge(args: Args<impl WriteColor, impl WriteColor>) -> anyhow::Res
         .join("tests")
         .join(service.to_kebab_case_str())
         .join(contest.as_deref().unwrap_or(""));
-    let test_suite_path = test_suite_dir.join(problem).with_extension("yml");
+    let test_suite_path = find_test_suite(&test_suite_dir, &problem)?;
 
-    let test_cases = match crate::fs::read_yaml(&test_suite_path)? {
-        TestSuite::Batch(test_sutie) => {
-            test_sutie.load_test_cases(&test_suite_dir, test_case_names, |_| {
-                unimplemented!("`SystemTestCases` is not impelemented");
-            })?
+    let problem_key = problem_key(service, contest.as_deref(), &problem);
+
+    let mut test_cases = match read_test_suite(&test_suite_path)? {
+        TestSuite::Batch(mut test_sutie) => {
+            override_expected_outputs(&base_dir, &problem_key, &mut test_sutie)?;
+
+            TestCases::Batch(test_sutie.load_test_cases_with_shell(
+                &test_suite_dir,
+                test_case_names,
+                |_| {
+                    unimplemented!("`SystemTestCases` is not impelemented");
+                },
+                LoaderShell {
+                    stderr: &mut stderr,
+                    strict,
+                    progress_draw_target,
+                },
+            )?)
         }
-        _ => todo!("currently only `Batch` is supported"),
+        TestSuite::Interactive(test_suite) => {
+            TestCases::Interactive(test_suite.load_test_cases(&test_suite_dir, test_case_names)?)
+        }
+        TestSuite::Unsubmittable => bail!("`{}` is unsubmittable", test_suite_path.display()),
     };
 
+    if let Some(TimelimitOverride(timelimit)) = timelimit {
+        match &mut test_cases {
+            TestCases::Batch(test_cases) => {
+                test_cases.iter_mut().for_each(|c| c.timelimit = timelimit)
+            }
+            TestCases::Interactive(test_cases) => {
+                test_cases.iter_mut().for_each(|c| c.timelimit = timelimit)
+            }
+        }
+    } else if let Some(multiplier) = timelimit_multiplier {
+        let scale = |timelimit: &mut Option<Duration>| {
+            *timelimit = timelimit.map(|t| scale_timelimit(t, multiplier));
+        };
+
+        match &mut test_cases {
+            TestCases::Batch(test_cases) => {
+                test_cases.iter_mut().for_each(|c| scale(&mut c.timelimit))
+            }
+            TestCases::Interactive(test_cases) => {
+                test_cases.iter_mut().for_each(|c| scale(&mut c.timelimit))
+            }
+        }
+    }
+
+    if let (Some(memory_limit), TestCases::Batch(test_cases)) = (memory_limit, &mut test_cases) {
+        for test_case in test_cases {
+            test_case.memorylimit = Some(memory_limit);
+        }
+    }
+
+    let mut last_failures = read_last_failures(&base_dir)?;
+
+    if let Some(failures) = last_failures.get(&problem_key) {
+        match &mut test_cases {
+            TestCases::Batch(test_cases) => filter_failures(
+                test_cases,
+                |c| &c.name,
+                failures,
+                only_failing,
+                failed_first,
+            ),
+            TestCases::Interactive(test_cases) => filter_failures(
+                test_cases,
+                |c| &c.name,
+                failures,
+                only_failing,
+                failed_first,
+            ),
+        }
+    }
+
     let redirections = (
         stdin_process_redirection,
         stdout_process_redirection,
         stderr_process_redirection,
     );
 
+    let env = env.unwrap_or_default();
+
     let mut newline = false;
 
     for (action, msg) in &[(transpile, "Transpiling..."), (compile, "Compiling...")] {
@@ -88,7 +321,15 @@ pub(crate) fn judge(args: Args<impl WriteColor, impl WriteColor>) -> anyhow::Res
                 writeln!(stderr)?;
             }
 
-            build(&mut stderr, &base_dir, &src, action, redirections, msg)?;
+            build(
+                &mut stderr,
+                &base_dir,
+                &src,
+                action,
+                &env,
+                redirections,
+                msg,
+            )?;
         }
     }
 
@@ -107,7 +348,7 @@ pub(crate) fn judge(args: Args<impl WriteColor, impl WriteColor>) -> anyhow::Res
                 program: args.get(0).cloned().unwrap_or_default().into(),
                 args: args.into_iter().skip(1).map(Into::into).collect(),
                 cwd: base_dir,
-                env: btreemap!(),
+                env: env.iter().map(|(k, v)| (k.into(), v.into())).collect(),
             };
 
             (cmd, None)
@@ -128,7 +369,7 @@ pub(crate) fn judge(args: Args<impl WriteColor, impl WriteColor>) -> anyhow::Res
                 program: program.into(),
                 args: vec![tempfile.path().into()],
                 cwd: base_dir,
-                env: btreemap!(),
+                env: env.iter().map(|(k, v)| (k.into(), v.into())).collect(),
             };
 
             (cmd, Some(tempfile))
@@ -150,34 +391,342 @@ pub(crate) fn judge(args: Args<impl WriteColor, impl WriteColor>) -> anyhow::Res
     stderr.reset()?;
     writeln!(stderr, " {}", cmd.cwd.display())?;
 
+    if let TestCases::Interactive(test_cases) = &test_cases {
+        if let Some(InteractiveTestCase { tester, .. }) = test_cases.first() {
+            stderr.set_color(color_spec!(Bold, Fg(Color::Magenta)))?;
+            write!(stderr, "Tester:")?;
+            stderr.reset()?;
+            writeln!(
+                stderr,
+                " {}",
+                shell_escape_args(&tester.program, &tester.args)
+            )?;
+        }
+    }
+
     stderr.flush()?;
 
-    let outcome = snowchains_core::judge::judge(
-        progress_draw_target,
-        tokio::signal::ctrl_c,
-        &cmd,
-        &test_cases,
-    )?;
+    let outcome = match &test_cases {
+        TestCases::Batch(test_cases) => snowchains_core::judge::judge_with_options(
+            progress_draw_target(),
+            ctrl_c,
+            &cmd,
+            test_cases,
+            JudgeOptions {
+                jobs,
+                memory_budget: memory_budget.map(|s| s.into::<Byte>().value().saturating_as()),
+                memory_per_case: memory_per_case.map(|s| s.into::<Byte>().value().saturating_as()),
+                repeat,
+                extra_output: tolerate_extra_output,
+            },
+        )?,
+        TestCases::Interactive(test_cases) => snowchains_core::judge::judge_interactive(
+            progress_draw_target(),
+            ctrl_c,
+            &cmd,
+            test_cases,
+        )?,
+    };
 
     if let Some(tempfile) = tempfile {
         tempfile.close()?;
     }
 
+    // Only the test cases that have been run are updated so that running a part of them (e.g. with
+    // `--only-failing` or `--testcases`) does not forget the other failures.
+    let failures = last_failures.entry(problem_key.clone()).or_default();
+    for verdict in &outcome.verdicts {
+        if let Some(name) = verdict.test_case_name() {
+            if matches!(verdict, Verdict::Accepted { .. }) {
+                failures.remove(name);
+            } else {
+                failures.insert(name.to_owned());
+            }
+        }
+    }
+    crate::fs::write_json(&last_failures_path(&base_dir), &last_failures, true)?;
+
+    if let Some(save_output) = &save_output {
+        let file_names =
+            save_output_file_names(outcome.verdicts.iter().map(Verdict::test_case_name));
+        for (verdict, file_name) in outcome.verdicts.iter().zip(file_names) {
+            if let Some(stdout) = verdict.stdout() {
+                crate::fs::write(save_output.join(file_name), stdout, true)?;
+            }
+        }
+    }
+
     writeln!(stderr)?;
     stderr.flush()?;
-    outcome.print_pretty(
-        stdout,
-        Some(display_limit.into::<Byte>().value().saturating_as()),
-    )?;
+    let display_limit = Some(display_limit.into::<Byte>().value().saturating_as());
+
+    let print = |wtr: &mut dyn WriteColor| match format {
+        OutputFormat::Pretty => outcome.print_pretty_with_options(
+            wtr,
+            PrintPrettyOptions {
+                display_limit,
+                skip_accepted: diff_only,
+                show_stderr,
+                sort_by_time,
+            },
+        ),
+        OutputFormat::Tap => outcome.print_tap(wtr, display_limit),
+        OutputFormat::Junit => outcome.print_junit(wtr, &problem_key, display_limit),
+    };
+
+    match output {
+        Some(output) => {
+            let mut buf = NoColor::new(vec![]);
+            print(&mut buf)?;
+            crate::fs::write(output, buf.into_inner(), true)?;
+        }
+        None => print(&mut stdout)?,
+    }
 
     outcome.error_on_fail()
 }
 
+enum TestCases {
+    Batch(Vec<BatchTestCase>),
+    Interactive(Vec<InteractiveTestCase>),
+}
+
+/// `{service}/{contest}/{problem}`, the key of a problem in `last-failures.json`.
+pub(crate) fn problem_key(service: PlatformKind, contest: Option<&str>, problem: &str) -> String {
+    format!(
+        "{}/{}/{}",
+        service.to_kebab_case_str(),
+        contest.unwrap_or(""),
+        problem,
+    )
+}
+
+fn last_failures_path(base_dir: &Path) -> PathBuf {
+    base_dir.join(".snowchains").join("last-failures.json")
+}
+
+/// Reads the names of the test cases that failed in the last runs, for each problem.
+///
+/// A problem that has been judged without any failure has an empty set.
+pub(crate) fn read_last_failures(
+    base_dir: &Path,
+) -> anyhow::Result<BTreeMap<String, BTreeSet<String>>> {
+    let path = last_failures_path(base_dir);
+    if path.exists() {
+        crate::fs::read_json(&path)
+    } else {
+        Ok(BTreeMap::new())
+    }
+}
+
+/// Summarizes the last local result of a problem for the summary tables.
+pub(crate) fn local_verdict(
+    last_failures: &BTreeMap<String, BTreeSet<String>>,
+    problem_key: &str,
+) -> String {
+    match last_failures.get(problem_key) {
+        None => "-".to_owned(),
+        Some(failures) if failures.is_empty() => "Passed".to_owned(),
+        Some(failures) => format!("{} failed", failures.len()),
+    }
+}
+
+/// Reports the warnings while loading the test cases.
+struct LoaderShell<W> {
+    stderr: W,
+    strict: bool,
+    progress_draw_target: fn() -> ProgressDrawTarget,
+}
+
+impl<W: WriteColor> snowchains_core::web::Shell for LoaderShell<W> {
+    fn progress_draw_target(&self) -> ProgressDrawTarget {
+        (self.progress_draw_target)()
+    }
+
+    fn warn<T: fmt::Display>(&mut self, message: T) -> io::Result<()> {
+        crate::shell::warn(&mut self.stderr, self.strict, message)
+    }
+}
+
+/// Applies `--only-failing` and `--failed-first`.
+fn filter_failures<T>(
+    test_cases: &mut Vec<T>,
+    name: fn(&T) -> &Option<String>,
+    failures: &BTreeSet<String>,
+    only_failing: bool,
+    failed_first: bool,
+) {
+    let failed = |case: &T| matches!(name(case), Some(name) if failures.contains(name));
+
+    if only_failing && !failures.is_empty() {
+        test_cases.retain(failed);
+    }
+
+    if failed_first {
+        test_cases.sort_by_key(|case| !failed(case));
+    }
+}
+
+/// Names the files for `--save-output`.
+///
+/// Unnamed test cases are named `#{n}`. If a name is already taken, `~{k}` is appended to it.
+fn save_output_file_names<'a>(names: impl IntoIterator<Item = Option<&'a str>>) -> Vec<String> {
+    let mut taken = HashSet::new();
+
+    names
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let name = match name {
+                Some(name) => name.replace(&['/', '\\'][..], "_"),
+                None => format!("#{}", i + 1),
+            };
+            let mut file_name = format!("{}.out", name);
+            let mut k = 1;
+            while !taken.insert(file_name.clone()) {
+                k += 1;
+                file_name = format!("{}~{}.out", name, k);
+            }
+            file_name
+        })
+        .collect()
+}
+
+/// Replaces the expected outputs with ones in `.snowchains/expected-overrides.yml`.
+///
+/// The file maps `{service}/{contest}/{problem}` to maps from test case names to expected outputs.
+/// It only affects local judging.
+fn override_expected_outputs(
+    base_dir: &Path,
+    problem_key: &str,
+    test_suite: &mut BatchTestSuite,
+) -> anyhow::Result<()> {
+    let path = base_dir.join(".snowchains").join("expected-overrides.yml");
+
+    if !path.exists() {
+        return Ok(());
+    }
+
+    let mut overrides =
+        crate::fs::read_yaml::<BTreeMap<String, BTreeMap<String, String>>, _>(&path)?;
+
+    let mut overrides = match overrides.remove(problem_key) {
+        Some(overrides) => overrides,
+        None => return Ok(()),
+    };
+
+    for case in &mut test_suite.cases {
+        if let Some(out) = case.name.as_ref().and_then(|name| overrides.remove(name)) {
+            case.out = Some(out.into());
+        }
+    }
+
+    if !overrides.is_empty() {
+        bail!(
+            "No such test cases in `{}`: {:?} (specified in `{}`)",
+            problem_key,
+            overrides.keys().collect::<Vec<_>>(),
+            path.display(),
+        );
+    }
+
+    Ok(())
+}
+
+static CTRL_C_RECEIVED: AtomicBool = AtomicBool::new(false);
+
+async fn ctrl_c() -> tokio::io::Result<()> {
+    tokio::signal::ctrl_c().await?;
+    CTRL_C_RECEIVED.store(true, atomic::Ordering::SeqCst);
+    Ok(())
+}
+
+pub(crate) fn ctrl_c_received() -> bool {
+    CTRL_C_RECEIVED.load(atomic::Ordering::SeqCst)
+}
+
+/// Watches a source file for `--watch-files`.
+pub(crate) struct SourceWatcher {
+    modified: tokio::sync::mpsc::UnboundedReceiver<notify::Result<()>>,
+    _watcher: RecommendedWatcher,
+}
+
+impl SourceWatcher {
+    pub(crate) fn new(path: &Path) -> anyhow::Result<Self> {
+        // Editors often write a file in several steps.
+        const DEBOUNCE: Duration = Duration::from_millis(300);
+
+        let path = path.to_owned();
+
+        // Editors often replace a file instead of writing to it, which would detach a watch on the
+        // file itself.
+        let dir = path
+            .parent()
+            .with_context(|| format!("Could not get the parent of {}", path.display()))?;
+
+        let (events_tx, events_rx) = mpsc::channel();
+        let mut watcher = notify::watcher(events_tx, DEBOUNCE)?;
+        watcher.watch(dir, RecursiveMode::NonRecursive)?;
+
+        let (modified_tx, modified_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        // Ends when `watcher` is dropped.
+        thread::spawn(move || {
+            for event in events_rx {
+                let modified = match event {
+                    DebouncedEvent::Create(p)
+                    | DebouncedEvent::Write(p)
+                    | DebouncedEvent::Rename(_, p)
+                        if p == path =>
+                    {
+                        Ok(())
+                    }
+                    DebouncedEvent::Error(err, _) => Err(err),
+                    _ => continue,
+                };
+                if modified_tx.send(modified).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Self {
+            modified: modified_rx,
+            _watcher: watcher,
+        })
+    }
+
+    /// Blocks until the file is modified. Returns `false` if <kbd>Ctrl-c</kbd> is pressed.
+    ///
+    /// Modifications made since the last call are reported at once.
+    pub(crate) fn wait(&mut self) -> anyhow::Result<bool> {
+        let rx = &mut self.modified;
+
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?
+            .block_on(async {
+                tokio::select! {
+                    modified = rx.recv() => {
+                        modified.with_context(|| "The file watcher stopped")??;
+                        while let Ok(modified) = rx.try_recv() {
+                            modified?;
+                        }
+                        Ok(true)
+                    }
+                    result = ctrl_c() => result.map(|()| false).map_err(Into::into),
+                }
+            })
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn transpile(
     stderr: impl WriteColor,
     base_dir: &Path,
     src: &str,
     transpile: &config::Compile,
+    env: &BTreeMap<String, String>,
     stdin_process_redirection: fn() -> Stdio,
     stdout_process_redirection: fn() -> Stdio,
     stderr_process_redirection: fn() -> Stdio,
@@ -187,6 +736,7 @@ pub(crate) fn transpile(
         base_dir,
         src,
         transpile,
+        env,
         (
             stdin_process_redirection,
             stdout_process_redirection,
@@ -196,12 +746,74 @@ pub(crate) fn transpile(
     )
 }
 
+/// Runs the `bundle` command of a language and returns its standard output.
+pub(crate) fn bundle(
+    mut stderr: impl WriteColor,
+    base_dir: &Path,
+    bundle: &config::Command,
+    env: &BTreeMap<String, String>,
+    stdin_process_redirection: fn() -> Stdio,
+    stderr_process_redirection: fn() -> Stdio,
+) -> anyhow::Result<String> {
+    stderr.set_color(color_spec!(Bold))?;
+    write!(stderr, "Bundling...")?;
+    stderr.reset()?;
+    writeln!(stderr)?;
+    stderr.flush()?;
+
+    let stdout = match bundle {
+        config::Command::Args(args) => run_command_with_stdout(
+            args.get(0).map(Deref::deref).unwrap_or(""),
+            args.iter().skip(1),
+            base_dir,
+            env,
+            stdin_process_redirection(),
+            stderr_process_redirection(),
+            &mut stderr,
+        )?,
+        config::Command::Script(config::Script {
+            program,
+            extension,
+            content,
+        }) => {
+            let mut tempfile = tempfile::Builder::new()
+                .prefix("snowchains-bundle")
+                .suffix(&format!(".{}", extension))
+                .tempfile()?;
+
+            tempfile.write_all(content.as_ref())?;
+
+            let stdout = run_command_with_stdout(
+                program,
+                &[tempfile.path()],
+                base_dir,
+                env,
+                stdin_process_redirection(),
+                stderr_process_redirection(),
+                &mut stderr,
+            )?;
+
+            tempfile.close()?;
+            stdout
+        }
+    };
+
+    let stdout = String::from_utf8(stdout)
+        .map_err(|_| anyhow!("The output of the `bundle` command is not valid UTF-8"))?;
+
+    if stdout.trim().is_empty() {
+        bail!("The output of the `bundle` command is empty");
+    }
+    Ok(stdout)
+}
+
 #[allow(clippy::type_complexity)]
 fn build(
     mut stderr: impl WriteColor,
     base_dir: &Path,
     src: &str,
     build_action: &config::Compile,
+    env: &BTreeMap<String, String>,
     redirections: (fn() -> Stdio, fn() -> Stdio, fn() -> Stdio),
     msg: &'static str,
 ) -> anyhow::Result<()> {
@@ -247,6 +859,7 @@ fn build(
                 args.get(0).map(Deref::deref).unwrap_or(""),
                 args.iter().skip(1),
                 base_dir,
+                env,
                 stdin_process_redirection(),
                 stdout_process_redirection(),
                 stderr_process_redirection(),
@@ -268,6 +881,7 @@ fn build(
                     program,
                     &[tempfile.path()],
                     base_dir,
+                    env,
                     stdin_process_redirection(),
                     stdout_process_redirection(),
                     stderr_process_redirection(),
@@ -282,10 +896,12 @@ fn build(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn run_command<S1: AsRef<OsStr>, S2: AsRef<OsStr>, I: IntoIterator<Item = S2>, W: WriteColor>(
     program: S1,
     args: I,
     base_dir: &Path,
+    env: &BTreeMap<String, String>,
     stdin_process_redirection: Stdio,
     stdout_process_redirection: Stdio,
     stderr_process_redirection: Stdio,
@@ -315,6 +931,7 @@ fn run_command<S1: AsRef<OsStr>, S2: AsRef<OsStr>, I: IntoIterator<Item = S2>, W
     let status = std::process::Command::new(program)
         .args(&args)
         .current_dir(base_dir)
+        .envs(env)
         .stdin(stdin_process_redirection)
         .stdout(stdout_process_redirection)
         .stderr(stderr_process_redirection)
@@ -335,6 +952,60 @@ fn run_command<S1: AsRef<OsStr>, S2: AsRef<OsStr>, I: IntoIterator<Item = S2>, W
     Ok(())
 }
 
+fn run_command_with_stdout<S1: AsRef<OsStr>, S2: AsRef<OsStr>, I: IntoIterator<Item = S2>>(
+    program: S1,
+    args: I,
+    base_dir: &Path,
+    env: &BTreeMap<String, String>,
+    stdin_process_redirection: Stdio,
+    stderr_process_redirection: Stdio,
+    mut stderr: impl WriteColor,
+) -> anyhow::Result<Vec<u8>> {
+    let program = program.as_ref();
+
+    let args = args
+        .into_iter()
+        .map(|s| s.as_ref().to_owned())
+        .collect::<Vec<_>>();
+
+    let shell_escaped = shell_escape_args(program, &args);
+
+    stderr.set_color(color_spec!(Bold, Fg(Color::Magenta)))?;
+    write!(stderr, "Command:")?;
+    stderr.reset()?;
+    writeln!(stderr, " {}", shell_escaped)?;
+
+    stderr.set_color(color_spec!(Bold, Fg(Color::Magenta)))?;
+    write!(stderr, "Working Directory:")?;
+    stderr.reset()?;
+    writeln!(stderr, " {}", base_dir.display())?;
+
+    stderr.flush()?;
+
+    let output = std::process::Command::new(program)
+        .args(&args)
+        .current_dir(base_dir)
+        .envs(env)
+        .stdin(stdin_process_redirection)
+        .stdout(Stdio::piped())
+        .stderr(stderr_process_redirection)
+        .output()?;
+
+    if !output.status.success() {
+        bail!(
+            "{} {}",
+            shell_escaped,
+            if let Some(code) = output.status.code() {
+                format!("exited with code {}", code)
+            } else {
+                "was terminated by signal".to_owned()
+            },
+        );
+    }
+
+    Ok(output.stdout)
+}
+
 fn shell_escape_args(program: impl AsRef<OsStr>, args: &[impl AsRef<OsStr>]) -> String {
     format!(
         "`{}`",
@@ -344,3 +1015,114 @@ fn shell_escape_args(program: impl AsRef<OsStr>, args: &[impl AsRef<OsStr>]) ->
             .format(" "),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::SuiteFormat;
+    use maplit::{btreemap, btreeset};
+    use snowchains_core::testsuite::TestSuite;
+    use std::time::Duration;
+
+    #[test]
+    fn toml_round_trip() {
+        for yaml in &[
+            r#"---
+type: Batch
+timelimit: 2s
+match:
+  Float:
+    relative_error: 0.01
+    absolute_error: 0.01
+cases:
+  - name: Sample 1
+    in: "1\n"
+    out: "6.28318530717958623200\n"
+  - name: Sample 2
+    in: "73\n"
+    timelimit: 3s
+extend:
+  - type: Text
+    path: "./a"
+    in: /in/*.txt
+    out: /out/*.txt
+"#,
+            r#"---
+type: Interactive
+timelimit: 2s
+tester:
+  command: [python3, ./tester.py]
+each_args:
+  - ["1"]
+  - ["2", "--hard"]
+"#,
+            "---\ntype: Unsubmittable\n",
+        ] {
+            let expected = serde_yaml::from_str::<TestSuite>(yaml).unwrap();
+            let toml = SuiteFormat::Toml.serialize(&expected);
+            assert_eq!(expected, toml::from_str::<TestSuite>(&toml).unwrap());
+        }
+    }
+
+    #[test]
+    fn scale_timelimit() {
+        assert_eq!(
+            Duration::from_millis(3000),
+            super::scale_timelimit(Duration::from_millis(1000), "3".parse().unwrap()),
+        );
+        assert_eq!(
+            Duration::from_millis(1500),
+            super::scale_timelimit(Duration::from_millis(1000), "1.5".parse().unwrap()),
+        );
+    }
+
+    #[test]
+    fn save_output_file_names() {
+        assert_eq!(
+            ["1.out", "#2.out", "a_b.out", "a_b~2.out", "#2~2.out"],
+            *super::save_output_file_names(vec![
+                Some("1"),
+                None,
+                Some("a/b"),
+                Some("a_b"),
+                Some("#2"),
+            ]),
+        );
+    }
+
+    #[test]
+    fn local_verdict() {
+        let last_failures = btreemap!(
+            "atcoder/abc100/a".to_owned() => btreeset!(),
+            "atcoder/abc100/b".to_owned() => btreeset!("1".to_owned(), "3".to_owned()),
+        );
+
+        let local_verdict = |key| super::local_verdict(&last_failures, key);
+
+        assert_eq!("Passed", local_verdict("atcoder/abc100/a"));
+        assert_eq!("2 failed", local_verdict("atcoder/abc100/b"));
+        assert_eq!("-", local_verdict("atcoder/abc100/c"));
+    }
+
+    #[test]
+    fn find_test_suite() -> anyhow::Result<()> {
+        let tempdir = tempfile::Builder::new()
+            .prefix("snowchains-judge-test-")
+            .tempdir()?;
+        let dir = tempdir.path();
+
+        assert_eq!(dir.join("a.yml"), super::find_test_suite(dir, "a")?);
+
+        crate::fs::write(dir.join("a.json"), "", false)?;
+        assert_eq!(dir.join("a.json"), super::find_test_suite(dir, "a")?);
+        assert_eq!(
+            vec![dir.join("a.json")],
+            super::other_test_suites(&dir.join("a.yml"), SuiteFormat::Yml),
+        );
+        assert!(super::other_test_suites(&dir.join("a.json"), SuiteFormat::Json).is_empty());
+
+        crate::fs::write(dir.join("a.yml"), "", false)?;
+        let err = super::find_test_suite(dir, "a").unwrap_err().to_string();
+        assert!(err.starts_with("Found multiple test suites for `a`"));
+        Ok(())
+    }
+}
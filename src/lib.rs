@@ -6,13 +6,16 @@ pub mod shell;
 mod web;
 
 pub use crate::commands::{
-    init::OptInit, judge::OptJudge, login::OptLogin, participate::OptParticipate,
+    add_case::OptAddCase, check_config::OptCheckConfig, clean::OptClean, generate::OptGenerate,
+    import_dir::OptImportDir, init::OptInit, judge::OptJudge, login::OptLogin, logout::OptLogout,
+    participate::OptParticipate, retrieve_contests::OptRetrieveContests,
     retrieve_languages::OptRetrieveLanguages,
     retrieve_submission_summaries::OptRetrieveSubmissionSummaries,
-    retrieve_testcases::OptRetrieveTestcases, submit::OptSubmit,
-    watch_submissions::OptWatchSubmissions, xtask::OptXtask,
+    retrieve_testcases::{OnConflict, OptRetrieveTestcases},
+    submit::OptSubmit,
+    watch_submissions::OptWatchSubmissions, xtask::OptXtask, CommonOpt, WebOpt,
 };
-use std::{env, io::BufRead, path::PathBuf};
+use std::{env, ffi::OsString, io::BufRead, path::PathBuf};
 use structopt::{
     clap::{self, AppSettings},
     StructOpt,
@@ -33,6 +36,10 @@ pub enum Opt {
     #[structopt(author, visible_alias("l"))]
     Login(OptLogin),
 
+    /// Logs out from a service
+    #[structopt(author)]
+    Logout(OptLogout),
+
     /// Participates in a contest
     Participate(OptParticipate),
 
@@ -44,10 +51,30 @@ pub enum Opt {
     #[structopt(author, visible_alias("d"))]
     Download(OptRetrieveTestcases),
 
+    /// Alias for `retrieve languages`
+    #[structopt(author)]
+    ListLanguages(OptRetrieveLanguages),
+
+    /// Alias for `retrieve contests`
+    #[structopt(author)]
+    ListContests(OptRetrieveContests),
+
     /// Watches data
     #[structopt(author, visible_alias("w"))]
     Watch(OptWatch),
 
+    /// Imports test cases from `*.in` and `*.out` files in a directory
+    #[structopt(author)]
+    ImportDir(OptImportDir),
+
+    /// Creates a source file from the template of the language
+    #[structopt(author, visible_alias("g"))]
+    Generate(OptGenerate),
+
+    /// Appends a test case to the test suite of a problem
+    #[structopt(author)]
+    AddCase(OptAddCase),
+
     /// Tests code
     #[structopt(author, visible_aliases(&["j", "test", "t"]))]
     Judge(OptJudge),
@@ -56,6 +83,14 @@ pub enum Opt {
     #[structopt(author, visible_alias("s"))]
     Submit(OptSubmit),
 
+    /// Removes the compiled files, and optionally the test suites
+    #[structopt(author)]
+    Clean(OptClean),
+
+    /// Evaluates `snowchains.dhall` and reports all the problems found in it
+    #[structopt(author)]
+    CheckConfig(OptCheckConfig),
+
     /// Runs a custom subcommand written in the config file
     #[structopt(author, visible_alias("x"), setting = AppSettings::TrailingVarArg)]
     Xtask(OptXtask),
@@ -74,6 +109,10 @@ pub enum OptRetrieve {
     /// Retrieves submission summaries
     #[structopt(author, visible_alias("ss"))]
     SubmissionSummaries(OptRetrieveSubmissionSummaries),
+
+    /// Retrieves list of contests
+    #[structopt(author, visible_alias("c"))]
+    Contests(OptRetrieveContests),
 }
 
 #[derive(StructOpt, Debug)]
@@ -85,9 +124,16 @@ pub enum OptWatch {
 
 impl Opt {
     pub fn from_args_with_workaround_for_clap_issue_1538() -> Self {
-        let mut args = env::args_os().collect::<Vec<_>>();
+        Self::try_from_iter_with_workaround_for_clap_issue_1538(env::args_os())
+            .unwrap_or_else(|e| e.exit())
+    }
+
+    pub fn try_from_iter_with_workaround_for_clap_issue_1538(
+        args: impl IntoIterator<Item = impl Into<OsString>>,
+    ) -> clap::Result<Self> {
+        let mut args = args.into_iter().map(Into::into).collect::<Vec<_>>();
 
-        Self::from_iter_safe(&args).unwrap_or_else(|clap::Error { kind, .. }| {
+        Self::from_iter_safe(&args).or_else(|clap::Error { kind, .. }| {
             if matches!(
                 args.get(1).and_then(|s| s.to_str()),
                 Some("x") | Some("xtask")
@@ -102,14 +148,43 @@ impl Opt {
                 args.insert(3, "--".into());
             }
 
-            Self::from_iter(args)
+            Self::from_iter_safe(args)
         })
     }
 
+    pub fn common(&self) -> CommonOpt {
+        match *self {
+            Self::Init(OptInit { common, .. })
+            | Self::Login(OptLogin { common, .. })
+            | Self::Logout(OptLogout { common, .. })
+            | Self::Participate(OptParticipate { common, .. })
+            | Self::Retrieve(OptRetrieve::Languages(OptRetrieveLanguages { common, .. }))
+            | Self::Retrieve(OptRetrieve::Testcases(OptRetrieveTestcases { common, .. }))
+            | Self::Retrieve(OptRetrieve::SubmissionSummaries(OptRetrieveSubmissionSummaries {
+                common,
+                ..
+            }))
+            | Self::Download(OptRetrieveTestcases { common, .. })
+            | Self::ListLanguages(OptRetrieveLanguages { common, .. })
+            | Self::Retrieve(OptRetrieve::Contests(OptRetrieveContests { common, .. }))
+            | Self::ListContests(OptRetrieveContests { common, .. })
+            | Self::Watch(OptWatch::Submissions(OptWatchSubmissions { common, .. }))
+            | Self::ImportDir(OptImportDir { common, .. })
+            | Self::Generate(OptGenerate { common, .. })
+            | Self::AddCase(OptAddCase { common, .. })
+            | Self::Judge(OptJudge { common, .. })
+            | Self::Submit(OptSubmit { common, .. })
+            | Self::Clean(OptClean { common, .. })
+            | Self::CheckConfig(OptCheckConfig { common, .. }) => common,
+            Self::Xtask(_) => CommonOpt::default(),
+        }
+    }
+
     pub fn color(&self) -> crate::ColorChoice {
         match *self {
             Self::Init(OptInit { color, .. })
             | Self::Login(OptLogin { color, .. })
+            | Self::Logout(OptLogout { color, .. })
             | Self::Participate(OptParticipate { color, .. })
             | Self::Retrieve(OptRetrieve::Languages(OptRetrieveLanguages { color, .. }))
             | Self::Retrieve(OptRetrieve::Testcases(OptRetrieveTestcases { color, .. }))
@@ -118,9 +193,17 @@ impl Opt {
                 ..
             }))
             | Self::Download(OptRetrieveTestcases { color, .. })
+            | Self::ListLanguages(OptRetrieveLanguages { color, .. })
+            | Self::Retrieve(OptRetrieve::Contests(OptRetrieveContests { color, .. }))
+            | Self::ListContests(OptRetrieveContests { color, .. })
             | Self::Watch(OptWatch::Submissions(OptWatchSubmissions { color, .. }))
+            | Self::ImportDir(OptImportDir { color, .. })
+            | Self::Generate(OptGenerate { color, .. })
+            | Self::AddCase(OptAddCase { color, .. })
             | Self::Judge(OptJudge { color, .. })
-            | Self::Submit(OptSubmit { color, .. }) => color,
+            | Self::Submit(OptSubmit { color, .. })
+            | Self::Clean(OptClean { color, .. })
+            | Self::CheckConfig(OptCheckConfig { color, .. }) => color,
             Self::Xtask(_) => crate::ColorChoice::Auto,
         }
     }
@@ -139,6 +222,16 @@ pub struct Context<R, W1, W2> {
     pub shell: crate::shell::Shell<R, W1, W2>,
 }
 
+/// Parses `args` (including the binary name) and runs the command.
+pub fn run_from_iter<R: BufRead, W1: WriteColor, W2: WriteColor>(
+    args: impl IntoIterator<Item = impl Into<OsString>>,
+    mut ctx: Context<R, W1, W2>,
+) -> anyhow::Result<()> {
+    let opt = Opt::try_from_iter_with_workaround_for_clap_issue_1538(args)?;
+    ctx.shell.strict |= opt.common().strict;
+    run(opt, ctx)
+}
+
 pub fn run<R: BufRead, W1: WriteColor, W2: WriteColor>(
     opt: Opt,
     ctx: Context<R, W1, W2>,
@@ -146,6 +239,7 @@ pub fn run<R: BufRead, W1: WriteColor, W2: WriteColor>(
     match opt {
         Opt::Init(opt) => commands::init::run(opt, ctx),
         Opt::Login(opt) => commands::login::run(opt, ctx),
+        Opt::Logout(opt) => commands::logout::run(opt, ctx),
         Opt::Participate(opt) => commands::participate::run(opt, ctx),
         Opt::Retrieve(OptRetrieve::Languages(opt)) => commands::retrieve_languages::run(opt, ctx),
         Opt::Retrieve(OptRetrieve::Testcases(opt)) => commands::retrieve_testcases::run(opt, ctx),
@@ -153,9 +247,17 @@ pub fn run<R: BufRead, W1: WriteColor, W2: WriteColor>(
             commands::retrieve_submission_summaries::run(opt, ctx)
         }
         Opt::Download(opt) => commands::retrieve_testcases::run(opt, ctx),
+        Opt::ListLanguages(opt) => commands::retrieve_languages::run(opt, ctx),
+        Opt::Retrieve(OptRetrieve::Contests(opt)) => commands::retrieve_contests::run(opt, ctx),
+        Opt::ListContests(opt) => commands::retrieve_contests::run(opt, ctx),
         Opt::Watch(OptWatch::Submissions(opt)) => commands::watch_submissions::run(opt, ctx),
+        Opt::ImportDir(opt) => commands::import_dir::run(opt, ctx),
+        Opt::Generate(opt) => commands::generate::run(opt, ctx),
+        Opt::AddCase(opt) => commands::add_case::run(opt, ctx),
         Opt::Judge(opt) => commands::judge::run(opt, ctx),
         Opt::Submit(opt) => commands::submit::run(opt, ctx),
+        Opt::Clean(opt) => commands::clean::run(opt, ctx),
+        Opt::CheckConfig(opt) => commands::check_config::run(opt, ctx),
         Opt::Xtask(opt) => commands::xtask::run(opt, ctx),
     }
 }
@@ -1,4 +1,5 @@
 use anyhow::Context as _;
+use log::LevelFilter;
 use snowchains_core::color_spec;
 use std::{
     env,
@@ -10,6 +11,9 @@ use termcolor::{Color, WriteColor as _};
 fn main() {
     let opt = snowchains::Opt::from_args_with_workaround_for_clap_issue_1538();
     let color = opt.color();
+    let snowchains::CommonOpt { strict, verbose } = opt.common();
+
+    init_logger(verbose);
 
     run_with_large_stack(|| {
         let stdin = io::stdin();
@@ -19,6 +23,7 @@ fn main() {
             stdout,
             mut stderr,
             stderr_tty,
+            strict: _,
             stdin_process_redirection,
             stdout_process_redirection,
             stderr_process_redirection,
@@ -32,6 +37,7 @@ fn main() {
                     stdout,
                     stderr: &mut stderr,
                     stderr_tty,
+                    strict,
                     stdin_process_redirection,
                     stdout_process_redirection,
                     stderr_process_redirection,
@@ -66,6 +72,23 @@ fn main() {
     });
 }
 
+/// Initializes the logger with the level of `-v`. `$RUST_LOG` overrides it if set.
+fn init_logger(verbose: u8) {
+    let level = match verbose {
+        0 => LevelFilter::Warn,
+        1 => LevelFilter::Info,
+        2 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    };
+
+    let mut builder = env_logger::Builder::new();
+    builder.filter_level(level);
+    if let Ok(filters) = env::var("RUST_LOG") {
+        builder.parse_filters(&filters);
+    }
+    let _ = builder.try_init();
+}
+
 fn run_with_large_stack(f: impl FnOnce() + Send) {
     crossbeam_utils::thread::scope(|scope| {
         scope
@@ -2,7 +2,7 @@ use indicatif::ProgressDrawTarget;
 use snowchains_core::{color_spec, web::StatusCodeColor};
 use std::{
     env, fmt,
-    io::{self, BufRead, Stdin, StdinLock, Write},
+    io::{self, BufRead, Read as _, Stdin, StdinLock, Write},
     process::Stdio,
 };
 use termcolor::{BufferedStandardStream, Color, WriteColor};
@@ -12,6 +12,7 @@ pub struct Shell<R, W1, W2> {
     pub stdout: W1,
     pub stderr: W2,
     pub stderr_tty: bool,
+    pub strict: bool,
     pub stdin_process_redirection: fn() -> Stdio,
     pub stdout_process_redirection: fn() -> Stdio,
     pub stderr_process_redirection: fn() -> Stdio,
@@ -19,6 +20,8 @@ pub struct Shell<R, W1, W2> {
 
 impl<'a> Shell<StdinLock<'a>, BufferedStandardStream, BufferedStandardStream> {
     pub fn new(stdin: &'a Stdin, color: crate::ColorChoice) -> Self {
+        let color = resolve_color_choice(color);
+
         let convert_with_atty_fitler = |stream| match (color, atty::is(stream)) {
             (crate::ColorChoice::Auto, true) => termcolor::ColorChoice::Auto,
             (crate::ColorChoice::Always, _) => termcolor::ColorChoice::Always,
@@ -30,6 +33,7 @@ impl<'a> Shell<StdinLock<'a>, BufferedStandardStream, BufferedStandardStream> {
             stdout: BufferedStandardStream::stdout(convert_with_atty_fitler(atty::Stream::Stdout)),
             stderr: BufferedStandardStream::stderr(convert_with_atty_fitler(atty::Stream::Stderr)),
             stderr_tty: atty::is(atty::Stream::Stderr),
+            strict: false,
             stdin_process_redirection: Stdio::inherit,
             stdout_process_redirection: Stdio::inherit,
             stderr_process_redirection: Stdio::inherit,
@@ -37,17 +41,48 @@ impl<'a> Shell<StdinLock<'a>, BufferedStandardStream, BufferedStandardStream> {
     }
 }
 
+/// Resolves `Auto` with `$NO_COLOR` and `$CLICOLOR_FORCE`.
+///
+/// A non-empty `$NO_COLOR` disables coloring, and `$CLICOLOR_FORCE` other than `0` enables it even
+/// if the output is not a terminal. `$NO_COLOR` takes precedence.
+fn resolve_color_choice(color: crate::ColorChoice) -> crate::ColorChoice {
+    let is_set = |name: &str| matches!(env::var_os(name), Some(v) if !v.is_empty() && v != "0");
+
+    match color {
+        crate::ColorChoice::Auto if env::var_os("NO_COLOR").map_or(false, |v| !v.is_empty()) => {
+            crate::ColorChoice::Never
+        }
+        crate::ColorChoice::Auto if is_set("CLICOLOR_FORCE") => crate::ColorChoice::Always,
+        color => color,
+    }
+}
+
 impl<R, W1, W2: WriteColor> Shell<R, W1, W2> {
     pub(crate) fn warn(&mut self, message: impl fmt::Display) -> io::Result<()> {
-        self.stderr
-            .set_color(color_spec!(Bold, Fg(Color::Yellow)))?;
-        write!(self.stderr, "warning:")?;
-        self.stderr.reset()?;
-        writeln!(self.stderr, " {}", message)?;
-        self.stderr.flush()
+        warn(&mut self.stderr, self.strict, message)
     }
 }
 
+/// Prints `message` as a warning, or fails with it if `strict` is enabled.
+pub(crate) fn warn(
+    mut stderr: impl WriteColor,
+    strict: bool,
+    message: impl fmt::Display,
+) -> io::Result<()> {
+    if strict {
+        return Err(io::Error::other(format!(
+            "{} (`--strict` is enabled)",
+            message,
+        )));
+    }
+
+    stderr.set_color(color_spec!(Bold, Fg(Color::Yellow)))?;
+    write!(stderr, "warning:")?;
+    stderr.reset()?;
+    writeln!(stderr, " {}", message)?;
+    stderr.flush()
+}
+
 impl<R: BufRead, W1, W2: Write> Shell<R, W1, W2> {
     pub(crate) fn read_reply(&mut self, prompt: &'static str) -> io::Result<String> {
         write!(self.stderr, "{}", prompt)?;
@@ -151,4 +186,91 @@ impl<R: BufRead> TtyOrPiped<R> {
             Self::Piped(r) => rpassword::read_password_with_reader(Some(r)),
         }
     }
+
+    /// Reads lines until a line of `.` or EOF, and returns them without the terminator.
+    pub(crate) fn read_lines_until_dot(&mut self) -> io::Result<String> {
+        fn read_lines_until_dot(mut rdr: impl BufRead) -> io::Result<String> {
+            let (mut acc, mut line) = ("".to_owned(), "".to_owned());
+            loop {
+                line.clear();
+                if rdr.read_line(&mut line)? == 0 || line.trim_end_matches(&['\r', '\n'][..]) == "."
+                {
+                    return Ok(acc);
+                }
+                acc += &line;
+            }
+        }
+
+        match self {
+            Self::Tty => read_lines_until_dot(io::stdin().lock()),
+            Self::Piped(r) => read_lines_until_dot(r),
+        }
+    }
+
+    pub(crate) fn read_to_string(&mut self) -> io::Result<String> {
+        let mut buf = "".to_owned();
+        match self {
+            Self::Tty => io::stdin().read_to_string(&mut buf),
+            Self::Piped(r) => r.read_to_string(&mut buf),
+        }?;
+        Ok(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ColorChoice;
+    use rusty_fork::rusty_fork_test;
+    use std::env;
+
+    rusty_fork_test! {
+        #[test]
+        fn resolve_color_choice_without_env_vars() {
+            env::remove_var("NO_COLOR");
+            env::remove_var("CLICOLOR_FORCE");
+
+            assert!(matches!(resolve(ColorChoice::Auto), ColorChoice::Auto));
+        }
+
+        #[test]
+        fn resolve_color_choice_with_no_color() {
+            env::set_var("NO_COLOR", "1");
+            env::remove_var("CLICOLOR_FORCE");
+
+            assert!(matches!(resolve(ColorChoice::Auto), ColorChoice::Never));
+            assert!(matches!(resolve(ColorChoice::Always), ColorChoice::Always));
+
+            env::set_var("CLICOLOR_FORCE", "1");
+
+            assert!(matches!(resolve(ColorChoice::Auto), ColorChoice::Never));
+        }
+
+        #[test]
+        fn resolve_color_choice_with_clicolor_force() {
+            env::remove_var("NO_COLOR");
+            env::set_var("CLICOLOR_FORCE", "1");
+
+            assert!(matches!(resolve(ColorChoice::Auto), ColorChoice::Always));
+            assert!(matches!(resolve(ColorChoice::Never), ColorChoice::Never));
+
+            env::set_var("CLICOLOR_FORCE", "0");
+
+            assert!(matches!(resolve(ColorChoice::Auto), ColorChoice::Auto));
+        }
+    }
+
+    fn resolve(color: ColorChoice) -> ColorChoice {
+        super::resolve_color_choice(color)
+    }
+
+    #[test]
+    fn read_lines_until_dot() -> std::io::Result<()> {
+        let mut stdin = super::TtyOrPiped::Piped(&b"1 2\n\n3\n.\n4\r\n.\r\n5\n"[..]);
+
+        assert_eq!("1 2\n\n3\n", stdin.read_lines_until_dot()?);
+        assert_eq!("4\r\n", stdin.read_lines_until_dot()?);
+        assert_eq!("5\n", stdin.read_lines_until_dot()?);
+        assert_eq!("", stdin.read_lines_until_dot()?);
+        Ok(())
+    }
 }
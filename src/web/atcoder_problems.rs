@@ -0,0 +1,280 @@
+//! [AtCoder Problems](https://kenkoooo.com/atcoder/) API.
+
+use anyhow::{bail, ensure, Context as _};
+use serde::{de::DeserializeOwned, Deserialize};
+use std::{
+    collections::{BTreeSet, HashMap},
+    net::IpAddr,
+    path::PathBuf,
+    str::FromStr,
+    time::{Duration, SystemTime},
+};
+use termcolor::Color;
+use url::Url;
+
+static BASE_URL: &str = "https://kenkoooo.com/atcoder/resources/";
+
+const CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DifficultyRange {
+    min: Option<f64>,
+    max: Option<f64>,
+}
+
+impl DifficultyRange {
+    fn contains(self, difficulty: f64) -> bool {
+        !matches!(self.min, Some(min) if min > difficulty)
+            && !matches!(self.max, Some(max) if max < difficulty)
+    }
+}
+
+impl FromStr for DifficultyRange {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let parse = |s: &str| -> anyhow::Result<_> {
+            let s = s.trim();
+            if s.is_empty() {
+                Ok(None)
+            } else {
+                s.parse()
+                    .map(Some)
+                    .with_context(|| format!("Invalid difficulty: {:?}", s))
+            }
+        };
+
+        let (min, max) = match s.find('-') {
+            Some(i) => (parse(&s[..i])?, parse(&s[i + 1..])?),
+            None => {
+                let difficulty = parse(s)?;
+                (difficulty, difficulty)
+            }
+        };
+
+        ensure!(
+            min.is_some() || max.is_some(),
+            "Expected `<MIN>-<MAX>`, `<MIN>-`, or `-<MAX>`",
+        );
+
+        if let (Some(min), Some(max)) = (min, max) {
+            ensure!(min <= max, "`{}` is greater than `{}`", min, max);
+        }
+
+        Ok(Self { min, max })
+    }
+}
+
+#[derive(Deserialize)]
+struct Problem {
+    id: String,
+    contest_id: String,
+    problem_index: String,
+}
+
+#[derive(Deserialize)]
+struct ProblemModel {
+    difficulty: Option<f64>,
+}
+
+/// Returns the indexes of the problems in `contest` whose difficulties are in `range`.
+///
+/// The difficulties are clipped as [`difficulties`] does, so that `range` matches the shown ones.
+/// The API responses are cached in the cache directory for a day.
+pub(crate) fn problem_indexes_by_difficulty(
+    contest: &str,
+    range: DifficultyRange,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    proxy: Option<&Url>,
+    local_address: Option<IpAddr>,
+) -> anyhow::Result<BTreeSet<String>> {
+    let problems = fetch::<Vec<Problem>>(
+        "problems.json",
+        timeout,
+        connect_timeout,
+        proxy,
+        local_address,
+    )?;
+    let models = fetch::<HashMap<String, ProblemModel>>(
+        "problem-models.json",
+        timeout,
+        connect_timeout,
+        proxy,
+        local_address,
+    )?;
+
+    let problems = problems
+        .into_iter()
+        .filter(|p| p.contest_id.eq_ignore_ascii_case(contest))
+        .collect::<Vec<_>>();
+
+    if problems.is_empty() {
+        bail!(
+            "Could not find the contest {:?} in AtCoder Problems",
+            contest
+        );
+    }
+
+    Ok(problems
+        .into_iter()
+        .filter(|p| {
+            matches!(
+                models.get(&p.id),
+                Some(ProblemModel { difficulty: Some(d) }) if range.contains(clip_difficulty(*d))
+            )
+        })
+        .map(|p| p.problem_index)
+        .collect())
+}
+
+/// Returns the difficulties of the problems by their IDs (e.g. "abc100_a").
+///
+/// The difficulties lower than 400 are clipped in the same way as AtCoder Problems shows them.
+pub(crate) fn difficulties(
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    proxy: Option<&Url>,
+    local_address: Option<IpAddr>,
+) -> anyhow::Result<HashMap<String, f64>> {
+    let models = fetch::<HashMap<String, ProblemModel>>(
+        "problem-models.json",
+        timeout,
+        connect_timeout,
+        proxy,
+        local_address,
+    )?;
+
+    Ok(models
+        .into_iter()
+        .flat_map(|(id, ProblemModel { difficulty })| difficulty.map(|d| (id, clip_difficulty(d))))
+        .collect())
+}
+
+fn clip_difficulty(difficulty: f64) -> f64 {
+    if difficulty >= 400.0 {
+        difficulty
+    } else {
+        400.0 / ((400.0 - difficulty) / 400.0).exp()
+    }
+}
+
+/// Returns the color of the AtCoder rating for `difficulty`.
+pub(crate) fn difficulty_color(difficulty: f64) -> Color {
+    let (r, g, b) = match difficulty {
+        d if d < 400.0 => (0x80, 0x80, 0x80),
+        d if d < 800.0 => (0x80, 0x40, 0x00),
+        d if d < 1200.0 => (0x00, 0x80, 0x00),
+        d if d < 1600.0 => (0x00, 0xc0, 0xc0),
+        d if d < 2000.0 => (0x00, 0x00, 0xff),
+        d if d < 2400.0 => (0xc0, 0xc0, 0x00),
+        d if d < 2800.0 => (0xff, 0x80, 0x00),
+        _ => (0xff, 0x00, 0x00),
+    };
+    Color::Rgb(r, g, b)
+}
+
+fn fetch<T: DeserializeOwned>(
+    name: &str,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    proxy: Option<&Url>,
+    local_address: Option<IpAddr>,
+) -> anyhow::Result<T> {
+    let cache_path = cache_dir()?.join(name);
+
+    let elapsed = std::fs::metadata(&cache_path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| SystemTime::now().duration_since(t).ok());
+    let is_fresh = matches!(elapsed, Some(elapsed) if elapsed < CACHE_TTL);
+
+    if is_fresh {
+        if let Ok(value) = crate::fs::read_json(&cache_path) {
+            return Ok(value);
+        }
+    }
+
+    let url = format!("{}{}", BASE_URL, name);
+
+    let mut client = reqwest::blocking::Client::builder().user_agent(concat!(
+        env!("CARGO_PKG_NAME"),
+        "/",
+        env!("CARGO_PKG_VERSION")
+    ));
+    if let Some(timeout) = timeout {
+        client = client.timeout(timeout);
+    }
+    if let Some(connect_timeout) = connect_timeout {
+        client = client.connect_timeout(connect_timeout);
+    }
+    if let Some(proxy) = proxy {
+        client = client.proxy(snowchains_core::web::proxy_from_url(proxy)?);
+    }
+    if let Some(local_address) = local_address {
+        client = client.local_address(local_address);
+    }
+
+    let text = client
+        .build()?
+        .get(&url)
+        .send()
+        .and_then(reqwest::blocking::Response::error_for_status)
+        .and_then(reqwest::blocking::Response::text)
+        .with_context(|| format!("Could not fetch {}", url))?;
+
+    let value = serde_json::from_str(&text)
+        .with_context(|| format!("Could not parse the response from {}", url))?;
+
+    crate::fs::write(&cache_path, text, true)?;
+
+    Ok(value)
+}
+
+fn cache_dir() -> anyhow::Result<PathBuf> {
+    let cache_dir = dirs_next::cache_dir().with_context(|| "Could not find the cache directory")?;
+    Ok(cache_dir.join("snowchains").join("atcoder-problems"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DifficultyRange;
+    use termcolor::Color;
+
+    #[test]
+    fn clip_difficulty() {
+        assert_eq!(1200.0, super::clip_difficulty(1200.0));
+        assert_eq!(400.0, super::clip_difficulty(400.0));
+        assert!((super::clip_difficulty(0.0) - 400.0 / 1f64.exp()).abs() < 1e-9);
+        assert!(super::clip_difficulty(-1000.0) > 0.0);
+    }
+
+    #[test]
+    fn difficulty_color() {
+        assert_eq!(Color::Rgb(0x80, 0x80, 0x80), super::difficulty_color(399.0));
+        assert_eq!(Color::Rgb(0x80, 0x40, 0x00), super::difficulty_color(400.0));
+        assert_eq!(
+            Color::Rgb(0x00, 0xc0, 0xc0),
+            super::difficulty_color(1599.0)
+        );
+        assert_eq!(
+            Color::Rgb(0xff, 0x00, 0x00),
+            super::difficulty_color(3200.0)
+        );
+    }
+
+    #[test]
+    fn parse_difficulty_range() {
+        let range = |s: &str| s.parse::<DifficultyRange>().ok();
+
+        let expected = |min, max| Some(DifficultyRange { min, max });
+
+        assert_eq!(expected(Some(400.0), Some(800.0)), range("400-800"));
+        assert_eq!(expected(Some(400.0), None), range("400-"));
+        assert_eq!(expected(None, Some(800.0)), range("-800"));
+        assert_eq!(expected(Some(400.0), Some(400.0)), range("400"));
+        assert_eq!(None, range("-"));
+        assert_eq!(None, range("800-400"));
+        assert_eq!(None, range("a-b"));
+    }
+}
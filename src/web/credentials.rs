@@ -1,43 +1,227 @@
-use anyhow::Context as _;
-use serde::{Deserialize, Serialize};
+use anyhow::{anyhow, ensure, Context as _};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use snowchains_core::web::PlatformKind;
 use std::{
     cell::RefCell,
+    env,
     io::{BufRead, Write},
-    path::PathBuf,
+    mem,
+    path::{Path, PathBuf},
 };
 
-pub(crate) fn cookie_store_path() -> anyhow::Result<PathBuf> {
+/// Returns the path to the cookie store for `service` and `profile`.
+///
+/// If `$SNOWCHAINS_COOKIES` is set, it is used with `$service` replaced by the service name (e.g.
+/// `~/.local/share/snowchains/$service.jsonl`) and `$profile` replaced by the profile name
+/// (`default` if no profile is given). A path without `$service` is shared by all of the services.
+/// A leading `~` is replaced with the home directory.
+pub(crate) fn cookie_store_path(
+    service: PlatformKind,
+    profile: Option<&str>,
+) -> anyhow::Result<PathBuf> {
+    if let Some(template) = env::var_os("SNOWCHAINS_COOKIES") {
+        let template = template
+            .into_string()
+            .map_err(|s| anyhow!("`$SNOWCHAINS_COOKIES` must be valid UTF-8: {:?}", s))?;
+        if let Some(profile) = profile {
+            validate_profile(profile)?;
+            ensure!(
+                template.contains("$profile"),
+                "`$SNOWCHAINS_COOKIES` must contain `$profile` to use `--profile`",
+            );
+        }
+        let path = expand_cookie_store_path(&template, service, profile);
+        return expand_tilde(&path, dirs_next::home_dir);
+    }
+
+    Ok(data_dir(profile)?.join("cookies.jsonl"))
+}
+
+fn expand_cookie_store_path(
+    template: &str,
+    service: PlatformKind,
+    profile: Option<&str>,
+) -> PathBuf {
+    template
+        .replace("$service", service.to_kebab_case_str())
+        .replace("$profile", profile.unwrap_or("default"))
+        .into()
+}
+
+fn expand_tilde(path: &Path, home_dir: fn() -> Option<PathBuf>) -> anyhow::Result<PathBuf> {
+    match path.strip_prefix("~") {
+        Ok(rest) => Ok(home_dir()
+            .with_context(|| "Could not find the home directory")?
+            .join(rest)),
+        Err(_) => Ok(path.to_owned()),
+    }
+}
+
+/// Returns the directory for the cookies and the tokens of `profile`.
+///
+/// The default profile uses `snowchains` in the local data directory, and a named one uses
+/// `snowchains/profiles/{name}`.
+fn data_dir(profile: Option<&str>) -> anyhow::Result<PathBuf> {
     let data_local_dir =
-        dirs_next::data_local_dir().with_context(|| "Could not find the local date directory")?;
-    Ok(data_local_dir.join("snowchains").join("cookies.jsonl"))
+        dirs_next::data_local_dir().with_context(|| "Could not find the local data directory")?;
+    let dir = data_local_dir.join("snowchains");
+
+    Ok(if let Some(profile) = profile {
+        dir.join("profiles").join(validate_profile(profile)?)
+    } else {
+        dir
+    })
+}
+
+fn validate_profile(profile: &str) -> anyhow::Result<&str> {
+    ensure!(
+        !profile.is_empty()
+            && profile
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'),
+        "Invalid profile name {:?}: use only ASCII letters, digits, `-`, and `_`",
+        profile,
+    );
+    Ok(profile)
 }
 
 pub(crate) fn atcoder_username_and_password<'a, R: BufRead, W1, W2: Write>(
     shell: &'a RefCell<&'a mut crate::shell::Shell<R, W1, W2>>,
-) -> impl FnMut() -> anyhow::Result<(String, String)> + 'a {
-    username_and_password(shell, "Username: ")
+    profile: Option<&'a str>,
+) -> UsernameAndPassword<'a, R, W1, W2> {
+    UsernameAndPassword::new(shell, profile, "Username: ", "ATCODER")
 }
 
 pub(crate) fn codeforces_username_and_password<'a, R: BufRead, W1, W2: Write>(
     shell: &'a RefCell<&'a mut crate::shell::Shell<R, W1, W2>>,
-) -> impl FnMut() -> anyhow::Result<(String, String)> + 'a {
-    username_and_password(shell, "Handle/Email: ")
+    profile: Option<&'a str>,
+) -> UsernameAndPassword<'a, R, W1, W2> {
+    UsernameAndPassword::new(shell, profile, "Handle/Email: ", "CODEFORCES")
 }
 
-pub(crate) fn username_and_password<'a, R: BufRead, W1, W2: Write>(
+/// Reads the username and the password from `$SNOWCHAINS_{service}_USERNAME` and
+/// `$SNOWCHAINS_{service}_PASSWORD` if both are set, and prompts for them otherwise.
+///
+/// The environment variables are used only once. If they are rejected, it fails instead of
+/// prompting.
+///
+/// With the `keyring` feature, the keyring of the OS is tried before prompting. If the ones in the
+/// keyring are rejected, they are removed from it. The prompted ones are saved to it by `save`,
+/// which is called after they are accepted.
+#[cfg_attr(not(feature = "keyring"), allow(dead_code))]
+pub(crate) struct UsernameAndPassword<'a, R, W1, W2> {
     shell: &'a RefCell<&'a mut crate::shell::Shell<R, W1, W2>>,
+    profile: Option<&'a str>,
     username_prompt: &'static str,
-) -> impl FnMut() -> anyhow::Result<(String, String)> + 'a {
-    move || -> _ {
-        let mut shell = shell.borrow_mut();
-        let username = shell.read_reply(username_prompt)?;
+    service: &'static str,
+    env_vars_used: bool,
+    #[cfg(feature = "keyring")]
+    keyring_used: bool,
+    #[cfg(feature = "keyring")]
+    prompted: Option<KeyringUsernameAndPassword>,
+}
+
+impl<'a, R: BufRead, W1, W2: Write> UsernameAndPassword<'a, R, W1, W2> {
+    fn new(
+        shell: &'a RefCell<&'a mut crate::shell::Shell<R, W1, W2>>,
+        profile: Option<&'a str>,
+        username_prompt: &'static str,
+        service: &'static str,
+    ) -> Self {
+        Self {
+            shell,
+            profile,
+            username_prompt,
+            service,
+            env_vars_used: false,
+            #[cfg(feature = "keyring")]
+            keyring_used: false,
+            #[cfg(feature = "keyring")]
+            prompted: None,
+        }
+    }
+
+    pub(crate) fn get(&mut self) -> anyhow::Result<(String, String)> {
+        let username_var = format!("SNOWCHAINS_{}_USERNAME", self.service);
+        let password_var = format!("SNOWCHAINS_{}_PASSWORD", self.service);
+
+        if let (Some(username), Some(password)) = (env_var(&username_var)?, env_var(&password_var)?)
+        {
+            ensure!(
+                !mem::replace(&mut self.env_vars_used, true),
+                "Could not log in with `${}` and `${}`",
+                username_var,
+                password_var,
+            );
+            return Ok((username, password));
+        }
+
+        #[cfg(feature = "keyring")]
+        {
+            let keyring_name = self.keyring_name()?;
+
+            if mem::replace(&mut self.keyring_used, false) {
+                // The ones in the keyring were rejected.
+                super::os_keyring::delete(&keyring_name)?;
+            } else if let Some(KeyringUsernameAndPassword { username, password }) =
+                super::os_keyring::get(&keyring_name)?
+            {
+                self.keyring_used = true;
+                return Ok((username, password));
+            }
+        }
+
+        let mut shell = self.shell.borrow_mut();
+        let username = shell.read_reply(self.username_prompt)?;
         let password = shell.read_password("Password: ")?;
+
+        #[cfg(feature = "keyring")]
+        {
+            self.prompted = Some(KeyringUsernameAndPassword {
+                username: username.clone(),
+                password: password.clone(),
+            });
+        }
+
         Ok((username, password))
     }
+
+    /// Saves the last prompted ones to the keyring with the `keyring` feature.
+    pub(crate) fn save(self) -> anyhow::Result<()> {
+        #[cfg(feature = "keyring")]
+        {
+            if let Some(prompted) = &self.prompted {
+                super::os_keyring::set(&self.keyring_name()?, prompted)?;
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "keyring")]
+    fn keyring_name(&self) -> anyhow::Result<String> {
+        keyring_name(&self.service.to_lowercase(), self.profile)
+    }
+}
+
+#[cfg(feature = "keyring")]
+#[derive(Deserialize, Serialize)]
+struct KeyringUsernameAndPassword {
+    username: String,
+    password: String,
+}
+
+fn env_var(name: &str) -> anyhow::Result<Option<String>> {
+    match env::var(name) {
+        Ok(value) if value.is_empty() => Ok(None),
+        Ok(value) => Ok(Some(value)),
+        Err(env::VarError::NotPresent) => Ok(None),
+        Err(err) => Err(err).with_context(|| format!("Could not read `${}`", name)),
+    }
 }
 
 pub(crate) fn dropbox_access_token() -> anyhow::Result<String> {
-    let path = token_path("dropbox.json")?;
+    // Not tied to the accounts of the services.
+    let path = token_path("dropbox.json", None)?;
 
     let Dropbox { access_token } = crate::fs::read_json(&path)
         .with_context(|| format!("First, save the access token to `{}`", path.display()))?;
@@ -52,14 +236,13 @@ pub(crate) fn dropbox_access_token() -> anyhow::Result<String> {
 
 pub(crate) fn codeforces_api_key_and_secret(
     shell: &mut crate::shell::Shell<impl BufRead, impl Sized, impl Write>,
+    profile: Option<&str>,
 ) -> anyhow::Result<(String, String)> {
-    let path = token_path("codeforces.json")?;
-
     let Codeforces {
         api_key,
         api_secret,
-    } = if path.exists() {
-        crate::fs::read_json(path)?
+    } = if let Some(pair) = load_token("codeforces", profile)? {
+        pair
     } else {
         let api_key = shell.read_password("Codeforces `api_key`: ")?;
         let api_secret = shell.read_password("Codeforces `api_secret`: ")?;
@@ -69,7 +252,7 @@ pub(crate) fn codeforces_api_key_and_secret(
             api_secret,
         };
 
-        crate::fs::write_json(path, &pair, true)?;
+        save_token("codeforces", profile, &pair)?;
         pair
     };
 
@@ -84,24 +267,146 @@ pub(crate) fn codeforces_api_key_and_secret(
 
 pub(crate) fn yukicoder_api_key(
     shell: &mut crate::shell::Shell<impl BufRead, impl Sized, impl Write>,
+    profile: Option<&str>,
 ) -> anyhow::Result<String> {
-    let path = token_path("yukicoder.json")?;
+    if let Some(api_key) = env_var("SNOWCHAINS_YUKICODER_API_KEY")? {
+        return Ok(api_key);
+    }
 
-    if path.exists() {
-        crate::fs::read_json(path)
+    if let Some(api_key) = load_token("yukicoder", profile)? {
+        Ok(api_key)
     } else {
         let api_key = shell.read_password("yukicoder API key: ")?;
-        crate::fs::write_json(path, &api_key, true)?;
+        save_token("yukicoder", profile, &api_key)?;
         Ok(api_key)
     }
 }
 
-fn token_path(file_name: &str) -> anyhow::Result<PathBuf> {
-    let data_local_dir =
-        dirs_next::data_local_dir().with_context(|| "Could not find the local data directory")?;
+/// Loads the token saved with `save_token`.
+///
+/// The tokens are stored in `tokens/{name}.json` of the profile, or in the keyring of the OS with
+/// the `keyring` feature.
+#[cfg(not(feature = "keyring"))]
+fn load_token<T: DeserializeOwned>(name: &str, profile: Option<&str>) -> anyhow::Result<Option<T>> {
+    let path = token_path(&format!("{}.json", name), profile)?;
+
+    if path.exists() {
+        crate::fs::read_json(path).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// With the `keyring` feature, a token in `tokens/{name}.json` saved without the feature is moved
+/// to the keyring.
+#[cfg(feature = "keyring")]
+fn load_token<T: DeserializeOwned + Serialize>(
+    name: &str,
+    profile: Option<&str>,
+) -> anyhow::Result<Option<T>> {
+    let keyring_name = keyring_name(name, profile)?;
+
+    if let Some(token) = super::os_keyring::get(&keyring_name)? {
+        return Ok(Some(token));
+    }
+
+    let path = token_path(&format!("{}.json", name), profile)?;
+
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let token = crate::fs::read_json(&path)?;
+    super::os_keyring::set(&keyring_name, &token)?;
+    crate::fs::remove_file(&path)?;
+    log::info!("Moved `{}` to the keyring", path.display());
+    Ok(Some(token))
+}
+
+#[cfg(not(feature = "keyring"))]
+fn save_token(name: &str, profile: Option<&str>, value: &impl Serialize) -> anyhow::Result<()> {
+    crate::fs::write_json(token_path(&format!("{}.json", name), profile)?, value, true)
+}
+
+#[cfg(feature = "keyring")]
+fn save_token(name: &str, profile: Option<&str>, value: &impl Serialize) -> anyhow::Result<()> {
+    super::os_keyring::set(&keyring_name(name, profile)?, value)
+}
+
+#[cfg(feature = "keyring")]
+fn keyring_name(name: &str, profile: Option<&str>) -> anyhow::Result<String> {
+    let profile = profile.map(validate_profile).transpose()?;
+    Ok(format!("{}@{}", name, profile.unwrap_or("default")))
+}
+
+fn token_path(file_name: &str, profile: Option<&str>) -> anyhow::Result<PathBuf> {
+    Ok(data_dir(profile)?.join("tokens").join(file_name))
+}
 
-    Ok(data_local_dir
-        .join("snowchains")
-        .join("tokens")
-        .join(file_name))
+#[cfg(test)]
+mod tests {
+    use snowchains_core::web::PlatformKind;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn expand_cookie_store_path() {
+        let expand = super::expand_cookie_store_path;
+
+        assert_eq!(
+            Path::new("/cookies/atcoder.jsonl"),
+            expand("/cookies/$service.jsonl", PlatformKind::Atcoder, None),
+        );
+        assert_eq!(
+            Path::new("/cookies/yukicoder/jar.jsonl"),
+            expand("/cookies/$service/jar.jsonl", PlatformKind::Yukicoder, None),
+        );
+        assert_eq!(
+            Path::new("/cookies.jsonl"),
+            expand("/cookies.jsonl", PlatformKind::Codeforces, None),
+        );
+        assert_eq!(
+            Path::new("/cookies/default/atcoder.jsonl"),
+            expand(
+                "/cookies/$profile/$service.jsonl",
+                PlatformKind::Atcoder,
+                None
+            ),
+        );
+        assert_eq!(
+            Path::new("/cookies/sub/atcoder.jsonl"),
+            expand(
+                "/cookies/$profile/$service.jsonl",
+                PlatformKind::Atcoder,
+                Some("sub"),
+            ),
+        );
+    }
+
+    #[test]
+    fn expand_tilde() {
+        let home_dir = || Some(PathBuf::from("/home/user"));
+
+        assert_eq!(
+            Path::new("/home/user/.cookies/atcoder.jsonl"),
+            super::expand_tilde(Path::new("~/.cookies/atcoder.jsonl"), home_dir).unwrap(),
+        );
+        assert_eq!(
+            Path::new("/cookies/~/atcoder.jsonl"),
+            super::expand_tilde(Path::new("/cookies/~/atcoder.jsonl"), home_dir).unwrap(),
+        );
+        assert_eq!(
+            Path::new("~user/atcoder.jsonl"),
+            super::expand_tilde(Path::new("~user/atcoder.jsonl"), home_dir).unwrap(),
+        );
+        assert!(super::expand_tilde(Path::new("~/atcoder.jsonl"), || None).is_err());
+    }
+
+    #[test]
+    fn validate_profile() {
+        assert_eq!("sub_1-a", super::validate_profile("sub_1-a").unwrap());
+        assert!(super::validate_profile("").is_err());
+        assert!(super::validate_profile("../sub").is_err());
+        assert!(super::validate_profile("a/b").is_err());
+        assert!(super::validate_profile("..").is_err());
+    }
 }
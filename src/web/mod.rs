@@ -1,10 +1,134 @@
+pub(crate) mod atcoder_problems;
 pub(crate) mod credentials;
+#[cfg(feature = "keyring")]
+mod os_keyring;
 
+use anyhow::Context as _;
 use heck::{CamelCase as _, KebabCase as _, MixedCase as _, SnakeCase as _};
 use serde::Serialize;
-use std::time::Duration;
+use std::{
+    env,
+    process::{Command, Stdio},
+    thread,
+    time::Duration,
+};
+use termcolor::WriteColor;
+use url::Url;
 
 pub(crate) const SESSION_TIMEOUT: Duration = Duration::from_secs(30);
+pub(crate) const MIN_REQUEST_INTERVAL: Duration = Duration::from_millis(300);
+
+/// Returns the timeout of each request and the one of connecting, from `--timeout` and
+/// `--connect-timeout`.
+///
+/// `--timeout` also sets the connect timeout unless `--connect-timeout` is given.
+pub(crate) fn timeouts(
+    timeout: Option<humantime::Duration>,
+    connect_timeout: Option<humantime::Duration>,
+) -> (Option<Duration>, Option<Duration>) {
+    let timeout = timeout.map_or(SESSION_TIMEOUT, Into::into);
+    let connect_timeout = connect_timeout.map_or(timeout, Into::into);
+    (Some(timeout), Some(connect_timeout))
+}
+
+/// Returns the minimum interval between requests from `--min-interval`.
+///
+/// `0s` disables it.
+pub(crate) fn min_interval(min_interval: Option<humantime::Duration>) -> Option<Duration> {
+    Some(min_interval.map_or(MIN_REQUEST_INTERVAL, Into::into))
+        .filter(|d| *d > Duration::from_secs(0))
+}
+
+/// Returns `--proxy`, or the proxy in `$HTTPS_PROXY` or `$ALL_PROXY` if it is not given.
+pub(crate) fn proxy(arg: Option<Url>) -> anyhow::Result<Option<Url>> {
+    proxy_with_env(arg, |key| env::var(key).ok())
+}
+
+fn proxy_with_env(
+    arg: Option<Url>,
+    var: impl Fn(&str) -> Option<String>,
+) -> anyhow::Result<Option<Url>> {
+    if arg.is_some() {
+        return Ok(arg);
+    }
+
+    for &key in &["HTTPS_PROXY", "https_proxy", "ALL_PROXY", "all_proxy"] {
+        if let Some(value) = var(key).filter(|v| !v.is_empty()) {
+            // Like curl, regard a proxy without a scheme as an HTTP one.
+            let url = if value.contains("://") {
+                value.parse()
+            } else {
+                format!("http://{}", value).parse()
+            };
+            let url =
+                url.with_context(|| format!("Invalid proxy URL in `${}`: {:?}", key, value))?;
+            return Ok(Some(url));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Opens `url` with `--browser`, `browser` in `snowchains.dhall`, `$BROWSER`, or the default
+/// opener of the OS.
+///
+/// Failing to launch the browser is a warning, since it is not necessary for the command itself.
+pub(crate) fn open_in_browser(
+    shell: &mut crate::shell::Shell<impl Sized, impl Sized, impl WriteColor>,
+    browser: Option<&str>,
+    url: &Url,
+) -> anyhow::Result<()> {
+    let (program, args) = browser_command(browser, |key| env::var(key).ok());
+
+    let result = Command::new(&program)
+        .args(&args)
+        .arg(url.as_str())
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+
+    match result {
+        // Waits for the browser in the background so that it does not remain as a zombie.
+        Ok(mut child) => {
+            thread::spawn(move || child.wait());
+        }
+        Err(err) => {
+            shell.warn(format!(
+                "Could not open {} with `{}`: {}",
+                url, program, err
+            ))?;
+        }
+    }
+    Ok(())
+}
+
+fn browser_command(
+    browser: Option<&str>,
+    var: impl Fn(&str) -> Option<String>,
+) -> (String, Vec<String>) {
+    let command = browser
+        .map(ToOwned::to_owned)
+        .or_else(|| var("BROWSER"))
+        .filter(|s| !s.trim().is_empty());
+
+    if let Some(command) = command {
+        let mut words = command.split_whitespace().map(ToOwned::to_owned);
+        let program = words.next().expect("should not be empty");
+        return (program, words.collect());
+    }
+
+    if cfg!(windows) {
+        (
+            "rundll32".to_owned(),
+            vec!["url.dll,FileProtocolHandler".to_owned()],
+        )
+    } else if cfg!(target_os = "macos") {
+        ("open".to_owned(), vec![])
+    } else {
+        ("xdg-open".to_owned(), vec![])
+    }
+}
 
 #[derive(Debug, Serialize)]
 pub(crate) struct CaseConversions {
@@ -31,3 +155,109 @@ impl CaseConversions {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, time::Duration};
+
+    #[test]
+    fn min_interval() {
+        let min_interval = |arg: Option<&str>| super::min_interval(arg.map(|s| s.parse().unwrap()));
+
+        assert_eq!(Some(Duration::from_millis(300)), min_interval(None));
+        assert_eq!(Some(Duration::from_secs(1)), min_interval(Some("1s")));
+        assert_eq!(None, min_interval(Some("0s")));
+    }
+
+    #[test]
+    fn timeouts() {
+        let timeouts = |timeout: Option<&str>, connect_timeout: Option<&str>| {
+            super::timeouts(
+                timeout.map(|s| s.parse().unwrap()),
+                connect_timeout.map(|s| s.parse().unwrap()),
+            )
+        };
+
+        assert_eq!(
+            (Some(Duration::from_secs(30)), Some(Duration::from_secs(30))),
+            timeouts(None, None),
+        );
+        assert_eq!(
+            (Some(Duration::from_secs(5)), Some(Duration::from_secs(5))),
+            timeouts(Some("5s"), None),
+        );
+        assert_eq!(
+            (Some(Duration::from_secs(60)), Some(Duration::from_secs(3))),
+            timeouts(Some("1m"), Some("3s")),
+        );
+        assert_eq!(
+            (
+                Some(Duration::from_secs(30)),
+                Some(Duration::from_millis(500))
+            ),
+            timeouts(None, Some("500ms")),
+        );
+    }
+
+    #[test]
+    fn proxy_with_env() {
+        let proxy = |arg: Option<&str>, vars: &[(&str, &str)]| {
+            let vars = vars.iter().copied().collect::<HashMap<_, _>>();
+            super::proxy_with_env(arg.map(|s| s.parse().unwrap()), |k| {
+                vars.get(k).map(|&v| v.to_owned())
+            })
+            .map(|url| url.map(String::from))
+        };
+
+        assert_eq!(None, proxy(None, &[]).unwrap());
+        assert_eq!(
+            Some("http://arg:8080/".to_owned()),
+            proxy(
+                Some("http://arg:8080"),
+                &[("HTTPS_PROXY", "http://env:8080")]
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            Some("http://proxy.example:8080/".to_owned()),
+            proxy(
+                None,
+                &[
+                    ("ALL_PROXY", "http://all:8080"),
+                    ("HTTPS_PROXY", "proxy.example:8080")
+                ],
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            Some("socks5://all:1080".to_owned()),
+            proxy(None, &[("all_proxy", "socks5://all:1080")]).unwrap(),
+        );
+
+        let err = proxy(None, &[("HTTPS_PROXY", "http://[::1")]).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Invalid proxy URL in `$HTTPS_PROXY`"));
+    }
+
+    #[test]
+    fn browser_command() {
+        let browser_command = |arg: Option<&str>, vars: &[(&str, &str)]| {
+            let vars = vars.iter().copied().collect::<HashMap<_, _>>();
+            super::browser_command(arg, |k| vars.get(k).map(|&v| v.to_owned()))
+        };
+
+        assert_eq!(
+            ("firefox".to_owned(), vec!["--new-tab".to_owned()]),
+            browser_command(Some("firefox --new-tab"), &[("BROWSER", "w3m")]),
+        );
+        assert_eq!(
+            ("w3m".to_owned(), vec![]),
+            browser_command(None, &[("BROWSER", "w3m")]),
+        );
+        assert_eq!(
+            browser_command(None, &[]),
+            browser_command(None, &[("BROWSER", " ")]),
+        );
+    }
+}
@@ -0,0 +1,31 @@
+//! The keyring of the OS, which stores the credentials with the `keyring` feature.
+
+use anyhow::Context as _;
+use serde::{de::DeserializeOwned, Serialize};
+
+const SERVICE: &str = "snowchains";
+
+pub(crate) fn get<T: DeserializeOwned>(name: &str) -> anyhow::Result<Option<T>> {
+    match keyring::Entry::new(SERVICE, name).get_password() {
+        Ok(json) => serde_json::from_str(&json)
+            .map(Some)
+            .with_context(|| format!("Invalid `{}` in the keyring", name)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(err) => Err(err).with_context(|| format!("Could not read `{}` from the keyring", name)),
+    }
+}
+
+pub(crate) fn set(name: &str, value: &impl Serialize) -> anyhow::Result<()> {
+    keyring::Entry::new(SERVICE, name)
+        .set_password(&serde_json::to_string(value)?)
+        .with_context(|| format!("Could not save `{}` to the keyring", name))
+}
+
+pub(crate) fn delete(name: &str) -> anyhow::Result<()> {
+    match keyring::Entry::new(SERVICE, name).delete_password() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(err) => {
+            Err(err).with_context(|| format!("Could not remove `{}` from the keyring", name))
+        }
+    }
+}